@@ -162,6 +162,15 @@ async fn main() -> Result<(), Error> {
                 framed.send(&rsp).await.context("Could not send response")?;
                 println!("S: {BLUE}{rsp:#?}{RESET}");
             }
+            Event::ActionRequired(Action::SendBad(tag)) => {
+                println!("[!] Send bad.");
+                let rsp = Response::Status(
+                    Status::bad(tag, None, "could not parse command.")
+                        .context("Could not create `Status`")?,
+                );
+                framed.send(&rsp).await.context("Could not send response")?;
+                println!("S: {BLUE}{rsp:#?}{RESET}");
+            }
         }
     }
 }
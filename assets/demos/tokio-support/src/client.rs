@@ -82,7 +82,7 @@ impl TokioDecoder for ImapClientCodec {
                     Err(GreetingDecodeError::Incomplete) => {
                         return Ok(None);
                     }
-                    Err(GreetingDecodeError::Failed) => {
+                    Err(GreetingDecodeError::Failed(_)) => {
                         let discarded = src.split_to(src.len());
                         src.clear();
                         return Err(ImapClientCodecError::ParsingFailed(discarded));
@@ -156,7 +156,7 @@ impl TokioDecoder for ImapClientCodec {
                                                 ));
                                             }
                                         }
-                                        ResponseDecodeError::Failed => {
+                                        ResponseDecodeError::Failed(_) => {
                                             let consumed = src.split_to(*to_consume_acc);
                                             self.state =
                                                 FramingState::ReadLine { to_consume_acc: 0 };
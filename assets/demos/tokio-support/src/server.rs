@@ -7,6 +7,7 @@ use imap_codec::{
     encode::Encoder,
     imap_types::{
         command::Command,
+        core::Tag,
         response::{Greeting, Response},
     },
     CommandCodec, GreetingCodec, ResponseCodec,
@@ -37,8 +38,6 @@ pub enum ImapServerCodecError {
     Io(#[from] IoError),
     #[error(transparent)]
     Framing(#[from] FramingError),
-    #[error("Parsing failed")]
-    ParsingFailed(BytesMut),
 }
 
 impl PartialEq for ImapServerCodecError {
@@ -46,7 +45,6 @@ impl PartialEq for ImapServerCodecError {
         match (self, other) {
             (Self::Io(error1), Self::Io(error2)) => error1.kind() == error2.kind(),
             (Self::Framing(kind1), Self::Framing(kind2)) => kind1 == kind2,
-            (Self::ParsingFailed(x), Self::ParsingFailed(y)) => x == y,
             _ => false,
         }
     }
@@ -63,6 +61,10 @@ pub enum Event {
 pub enum Action {
     SendLiteralAck(u32),
     SendLiteralReject(u32),
+    /// The command could not be parsed and was discarded.
+    ///
+    /// Contains the tag of the command (if one could be recovered) to reply with `<tag> BAD`.
+    SendBad(Option<Tag<'static>>),
 }
 
 impl TokioDecoder for ImapServerCodec {
@@ -126,11 +128,15 @@ impl TokioDecoder for ImapServerCodec {
                                             )));
                                         }
                                     }
-                                    CommandDecodeError::Failed => {
-                                        let consumed = src.split_to(*to_consume_acc);
+                                    CommandDecodeError::Failed { tag, .. } => {
+                                        let tag = tag.into_static();
+
+                                        src.advance(*to_consume_acc);
                                         self.state = FramingState::ReadLine { to_consume_acc: 0 };
 
-                                        return Err(ImapServerCodecError::ParsingFailed(consumed));
+                                        return Ok(Some(Event::ActionRequired(Action::SendBad(
+                                            tag,
+                                        ))));
                                     }
                                 },
                             }
@@ -202,8 +208,6 @@ mod tests {
         core::{AString, AtomExt, IString, Literal},
         secret::Secret,
     };
-    #[cfg(feature = "quirk_crlf_relaxed")]
-    use imap_types::core::Tag;
     use tokio_util::codec::Decoder;
 
     use super::*;
@@ -224,9 +228,9 @@ mod tests {
             (b"xxxx", Ok(None)),
             (
                 b"\r\n",
-                Err(ImapServerCodecError::ParsingFailed(BytesMut::from(
-                    b"xxxx\r\n".as_ref(),
-                ))),
+                Ok(Some(Event::ActionRequired(Action::SendBad(Some(
+                    Tag::try_from("xxxx").unwrap(),
+                ))))),
             ),
         ];
 
@@ -297,9 +301,13 @@ mod tests {
         let tests = [
             (
                 b"xxx\r\n".as_ref(),
-                Err(ImapServerCodecError::ParsingFailed(BytesMut::from(
-                    b"xxx\r\n".as_ref(),
-                ))),
+                Ok(Some(Event::ActionRequired(Action::SendBad(Some(
+                    Tag::try_from("xxx").unwrap(),
+                ))))),
+            ),
+            (
+                b"* noop\r\n".as_ref(),
+                Ok(Some(Event::ActionRequired(Action::SendBad(None)))),
             ),
             (
                 b"a noop\n",
@@ -332,9 +340,9 @@ mod tests {
             ),
             (
                 b"a login alice {1-}\r\n",
-                Err(ImapServerCodecError::ParsingFailed(BytesMut::from(
-                    b"a login alice {1-}\r\n".as_ref(),
-                ))),
+                Ok(Some(Event::ActionRequired(Action::SendBad(Some(
+                    Tag::try_from("a").unwrap(),
+                ))))),
             ),
             (
                 // Ohhhhhh, IMAP :-/
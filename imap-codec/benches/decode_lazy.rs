@@ -0,0 +1,116 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use imap_codec::{
+    decode::{Decoder, LazyCommandBody, LazyMacroOrMessageDataItemNames},
+    CommandCodec,
+};
+
+/// Count allocations to show the difference next to the timings.
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn decode(codec: &CommandCodec, input: &[u8]) -> usize {
+    let (_remaining, cmd) = codec.decode(input).unwrap();
+
+    // Keep the command alive until here.
+    black_box(cmd);
+
+    0
+}
+
+fn decode_lazy(codec: &CommandCodec, input: &[u8]) -> usize {
+    let (_remaining, cmd) = codec.decode_lazy(input).unwrap();
+
+    // Visit every item, like an application would.
+    match cmd.body {
+        LazyCommandBody::Fetch {
+            sequence_set,
+            macro_or_item_names,
+            ..
+        } => {
+            let items = match macro_or_item_names {
+                LazyMacroOrMessageDataItemNames::Macro(_) => 0,
+                LazyMacroOrMessageDataItemNames::MessageDataItemNames(names) => {
+                    names.map(black_box).count()
+                }
+            };
+
+            sequence_set.map(black_box).count() + items
+        }
+        LazyCommandBody::Store {
+            sequence_set,
+            flags,
+            ..
+        } => sequence_set.map(black_box).count() + flags.map(black_box).count(),
+        LazyCommandBody::Search { criteria, .. } => criteria.map(black_box).count(),
+        _ => unreachable!(),
+    }
+}
+
+fn allocations(f: impl FnOnce() -> usize) -> usize {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    black_box(f());
+    ALLOCATIONS.load(Ordering::Relaxed) - before
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    // # Setup
+    let codec = CommandCodec::default();
+    let inputs: [(&str, &[u8]); 3] = [
+        (
+            "fetch",
+            b"A UID FETCH 1:4,7,9,12:20,25,31:* (UID FLAGS INTERNALDATE RFC822.SIZE BODY.PEEK[HEADER.FIELDS (From To Subject Date)])\r\n",
+        ),
+        (
+            "store",
+            b"A UID STORE 1,3,5:9,11,13:* +FLAGS.SILENT (\\Seen \\Answered \\Flagged $Forwarded $Junk)\r\n",
+        ),
+        (
+            "search",
+            b"A UID SEARCH UNSEEN UNDELETED SINCE 1-Jan-2024 FROM alice LARGER 1024 NOT FLAGGED\r\n",
+        ),
+    ];
+
+    for (name, input) in inputs {
+        println!(
+            "{name}: decode allocates {} times, decode_lazy allocates {} times",
+            allocations(|| decode(&codec, input)),
+            allocations(|| decode_lazy(&codec, input)),
+        );
+
+        let mut group = c.benchmark_group(name);
+
+        group.bench_function("decode", |b| {
+            b.iter(|| decode(&codec, black_box(input)));
+        });
+
+        group.bench_function("decode_lazy", |b| {
+            b.iter(|| decode_lazy(&codec, black_box(input)));
+        });
+
+        group.finish();
+    }
+}
+
+criterion_group!(benches, criterion_benchmark);
+
+criterion_main!(benches);
@@ -53,12 +53,34 @@ fn main() {
                 read_more(&mut buffer, Role::Client);
             }
             // Parser failed.
-            Err(CommandDecodeError::Failed) => {
-                println!("Error parsing command.");
-                println!("Clearing buffer.");
+            Err(CommandDecodeError::Failed {
+                tag,
+                line_length,
+                failure,
+            }) => {
+                println!("Error parsing command: {failure}.");
 
-                // Clear the buffer and proceed with loop.
-                buffer.clear();
+                // Reply with a tagged `BAD` when the tag could be recovered ...
+                match tag {
+                    Some(tag) => println!("S: {COLOR_SERVER}{} BAD ...{RESET}", tag.inner()),
+                    None => println!("S: {COLOR_SERVER}* BAD ...{RESET}"),
+                }
+
+                // ... and skip the offending line (or clear the buffer if it is not complete).
+                match line_length {
+                    Some(line_length) if line_length <= buffer.len() => {
+                        buffer.drain(..line_length);
+                    }
+                    // The data of a rejected non-synchronizing literal is still missing.
+                    Some(_) => {
+                        println!("Closing connection.");
+                        return;
+                    }
+                    None => {
+                        println!("Clearing buffer.");
+                        buffer.clear();
+                    }
+                }
             }
         }
     }
@@ -43,8 +43,8 @@ fn main() {
                 read_more(&mut buffer, Role::Server);
             }
             // Parser failed.
-            Err(GreetingDecodeError::Failed) => {
-                println!("Error parsing greeting.");
+            Err(GreetingDecodeError::Failed(failure)) => {
+                println!("Error parsing greeting: {failure}.");
                 println!("Clearing buffer.");
 
                 // Clear the buffer and proceed with loop.
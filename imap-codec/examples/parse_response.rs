@@ -54,8 +54,8 @@ fn main() {
                 read_more(&mut buffer, Role::Server);
             }
             // Parser failed.
-            Err(ResponseDecodeError::Failed) => {
-                println!("Error parsing response.");
+            Err(ResponseDecodeError::Failed(failure)) => {
+                println!("Error parsing response: {failure}.");
                 println!("Clearing buffer.");
 
                 // Clear the buffer and proceed with loop.
@@ -56,7 +56,7 @@ macro_rules! impl_decode_target {
                                 DecodeError::LiteralFound { .. } => {
                                     assert!(index < output.len());
                                 }
-                                DecodeError::Failed => {
+                                DecodeError::Failed(_) => {
                                     panic!("Expected `Ok` or `Incomplete`, got `Failed`");
                                 }
                             },
@@ -1,7 +1,3 @@
-#[cfg(not(feature = "quirk_crlf_relaxed"))]
-use abnf_core::streaming::crlf;
-#[cfg(feature = "quirk_crlf_relaxed")]
-use abnf_core::streaming::crlf_relaxed as crlf;
 use imap_types::{
     auth::{AuthMechanism, AuthenticateData},
     secret::Secret,
@@ -10,7 +6,7 @@ use nom::{combinator::map, sequence::terminated};
 
 use crate::{
     core::{atom, base64},
-    decode::IMAPResult,
+    decode::{crlf, IMAPResult},
 };
 
 // ----- Unsorted IMAP parsers -----
@@ -5,18 +5,22 @@ use imap_types::{
         MultiPartExtensionData, SinglePartExtensionData, SpecificFields,
     },
     core::{IString, NString, NonEmptyVec},
+    envelope::Envelope,
 };
 use nom::{
     branch::alt,
     bytes::streaming::{tag, tag_no_case},
     combinator::{map, opt},
-    multi::{many0, many1, separated_list1},
+    error::context,
     sequence::{delimited, preceded, tuple},
 };
 
 use crate::{
     core::{nil, nstring, number, string},
-    decode::{IMAPErrorKind, IMAPParseError, IMAPResult},
+    decode::{
+        limits, many0, many1, quirks, separated_list1, IMAPErrorKind, IMAPParseError, IMAPResult,
+        Limit,
+    },
     envelope::envelope,
 };
 
@@ -27,7 +31,7 @@ use crate::{
 pub(crate) fn body(
     remaining_recursions: usize,
 ) -> impl Fn(&[u8]) -> IMAPResult<&[u8], BodyStructure> {
-    move |input: &[u8]| body_limited(input, remaining_recursions)
+    move |input: &[u8]| context("body", |input| body_limited(input, remaining_recursions))(input)
 }
 
 fn body_limited<'a>(
@@ -37,7 +41,8 @@ fn body_limited<'a>(
     if remaining_recursions == 0 {
         return Err(nom::Err::Failure(IMAPParseError {
             input,
-            kind: IMAPErrorKind::RecursionLimitExceeded,
+            context: Vec::new(),
+            kind: IMAPErrorKind::LimitExceeded(Limit::BodyStructureNesting),
         }));
     }
 
@@ -71,7 +76,8 @@ fn body_type_1part_limited<'a>(
     if remaining_recursions == 0 {
         return Err(nom::Err::Failure(IMAPParseError {
             input,
-            kind: IMAPErrorKind::RecursionLimitExceeded,
+            context: Vec::new(),
+            kind: IMAPErrorKind::LimitExceeded(Limit::BodyStructureNesting),
         }));
     }
 
@@ -128,18 +134,20 @@ fn body_type_msg_limited<'a>(
     if remaining_recursions == 0 {
         return Err(nom::Err::Failure(IMAPParseError {
             input,
-            kind: IMAPErrorKind::RecursionLimitExceeded,
+            context: Vec::new(),
+            kind: IMAPErrorKind::LimitExceeded(Limit::BodyStructureNesting),
         }));
     }
 
-    let body = move |input: &'a [u8]| body_limited(input, remaining_recursions.saturating_sub(1));
+    let body =
+        move |input: &'a [u8]| body_nil_rectified(input, remaining_recursions.saturating_sub(1));
 
     let mut parser = tuple((
         media_message,
         sp,
         body_fields,
         sp,
-        envelope,
+        body_envelope,
         sp,
         body,
         sp,
@@ -162,6 +170,79 @@ fn body_type_msg_limited<'a>(
     ))
 }
 
+/// `body` of a `MESSAGE/RFC822` part or of a multipart
+///
+/// # Quirks
+///
+/// * `NIL` is rectified to an empty `TEXT/PLAIN` body with
+///   [`DecodeQuirks::nil_body_structure_lists`](crate::decode::DecodeQuirks::nil_body_structure_lists).
+fn body_nil_rectified(
+    input: &[u8],
+    remaining_recursions: usize,
+) -> IMAPResult<&[u8], BodyStructure> {
+    if !quirks().nil_body_structure_lists {
+        return body(remaining_recursions)(input);
+    }
+
+    alt((
+        body(remaining_recursions),
+        map(nil, |_| {
+            log::warn!("Rectified `NIL` body to an empty `TEXT/PLAIN` body");
+
+            BodyStructure::Single {
+                body: Body {
+                    basic: BasicFields {
+                        parameter_list: Vec::new(),
+                        id: NString(None),
+                        description: NString(None),
+                        // Safety: `unwrap` can't panic because "7BIT" is a valid `IString`.
+                        content_transfer_encoding: IString::try_from("7BIT").unwrap(),
+                        size: 0,
+                    },
+                    specific: SpecificFields::Text {
+                        // Safety: `unwrap` can't panic because "PLAIN" is a valid `IString`.
+                        subtype: IString::try_from("PLAIN").unwrap(),
+                        number_of_lines: 0,
+                    },
+                },
+                extension_data: None,
+            }
+        }),
+    ))(input)
+}
+
+/// `envelope` of a `MESSAGE/RFC822` part
+///
+/// # Quirks
+///
+/// * `NIL` is rectified to an envelope with all fields `NIL` with
+///   [`DecodeQuirks::nil_body_structure_lists`](crate::decode::DecodeQuirks::nil_body_structure_lists).
+fn body_envelope(input: &[u8]) -> IMAPResult<&[u8], Envelope> {
+    if !quirks().nil_body_structure_lists {
+        return envelope(input);
+    }
+
+    alt((
+        envelope,
+        map(nil, |_| {
+            log::warn!("Rectified `NIL` envelope to an envelope with all fields `NIL`");
+
+            Envelope {
+                date: NString(None),
+                subject: NString(None),
+                from: Vec::new(),
+                sender: Vec::new(),
+                reply_to: Vec::new(),
+                to: Vec::new(),
+                cc: Vec::new(),
+                bcc: Vec::new(),
+                in_reply_to: NString(None),
+                message_id: NString(None),
+            }
+        }),
+    ))(input)
+}
+
 /// `body-type-text = media-text SP
 ///                   body-fields SP
 ///                   body-fld-lines`
@@ -277,21 +358,21 @@ pub(crate) fn body_fld_enc(input: &[u8]) -> IMAPResult<&[u8], IString> {
 /// The following erroneous messages were observed:
 ///
 /// * A negative number, specifically `-1`, in Dovecot.
-#[allow(clippy::needless_return)]
+///
+/// Negative numbers are rectified to `0` with
+/// [`DecodeQuirks::rectify_numbers`](crate::decode::DecodeQuirks::rectify_numbers).
 pub(crate) fn body_fld_octets(input: &[u8]) -> IMAPResult<&[u8], u32> {
-    #[cfg(not(feature = "quirk_rectify_numbers"))]
-    return number(input);
-
-    #[cfg(feature = "quirk_rectify_numbers")]
-    {
-        return alt((
-            number,
-            map(tuple((tag("-"), number)), |(_, _)| {
-                log::warn!("Rectified negative number to 0");
-                0
-            }),
-        ))(input);
+    if !quirks().rectify_numbers {
+        return number(input);
     }
+
+    alt((
+        number,
+        map(tuple((tag("-"), number)), |(_, _)| {
+            log::warn!("Rectified negative number to 0");
+            0
+        }),
+    ))(input)
 }
 
 #[inline]
@@ -323,7 +404,10 @@ pub(crate) fn body_ext_1part(input: &[u8]) -> IMAPResult<&[u8], SinglePartExtens
                             opt(map(
                                 tuple((
                                     preceded(sp, body_fld_loc),
-                                    many0(preceded(sp, body_extension(8))),
+                                    many0(preceded(
+                                        sp,
+                                        body_extension(limits().max_body_structure_nesting),
+                                    )),
                                 )),
                                 |(location, extensions)| Location {
                                     location,
@@ -409,7 +493,8 @@ fn body_extension_limited<'a>(
     if remaining_recursion == 0 {
         return Err(nom::Err::Failure(IMAPParseError {
             input,
-            kind: IMAPErrorKind::RecursionLimitExceeded,
+            context: Vec::new(),
+            kind: IMAPErrorKind::LimitExceeded(Limit::BodyStructureNesting),
         }));
     }
 
@@ -439,12 +524,13 @@ fn body_type_mpart_limited(
     if remaining_recursion == 0 {
         return Err(nom::Err::Failure(IMAPParseError {
             input,
-            kind: IMAPErrorKind::RecursionLimitExceeded,
+            context: Vec::new(),
+            kind: IMAPErrorKind::LimitExceeded(Limit::BodyStructureNesting),
         }));
     }
 
     let mut parser = tuple((
-        many1(body(remaining_recursion)),
+        many1(move |input| body_nil_rectified(input, remaining_recursion)),
         sp,
         media_subtype,
         opt(preceded(sp, body_ext_mpart)),
@@ -486,7 +572,10 @@ pub(crate) fn body_ext_mpart(input: &[u8]) -> IMAPResult<&[u8], MultiPartExtensi
                             opt(map(
                                 tuple((
                                     preceded(sp, body_fld_loc),
-                                    many0(preceded(sp, body_extension(8))),
+                                    many0(preceded(
+                                        sp,
+                                        body_extension(limits().max_body_structure_nesting),
+                                    )),
                                 )),
                                 |(location, extensions)| Location {
                                     location,
@@ -583,7 +672,10 @@ mod tests {
     };
 
     use super::*;
-    use crate::testing::{kat_inverse_response, known_answer_test_encode};
+    use crate::{
+        decode::{with_quirks, DecodeQuirks},
+        testing::{kat_inverse_response, known_answer_test_encode},
+    };
 
     #[test]
     fn test_parse_media_basic() {
@@ -762,21 +854,24 @@ mod tests {
 
     #[test]
     fn test_number_quirk() {
-        assert_eq!(body_fld_octets(b"0)").unwrap().1, 0);
-        assert_eq!(body_fld_octets(b"1)").unwrap().1, 1);
+        let mut quirks = DecodeQuirks::strict();
 
-        #[cfg(not(feature = "quirk_rectify_numbers"))]
-        {
-            assert!(dbg!(body_fld_octets(b"-0)")).is_err());
+        with_quirks(quirks, || {
+            assert_eq!(body_fld_octets(b"0)").unwrap().1, 0);
+            assert_eq!(body_fld_octets(b"1)").unwrap().1, 1);
+            assert!(body_fld_octets(b"-0)").is_err());
             assert!(body_fld_octets(b"-1)").is_err());
             assert!(body_fld_octets(b"-999999)").is_err());
-        }
+        });
 
-        #[cfg(feature = "quirk_rectify_numbers")]
-        {
+        quirks.rectify_numbers = true;
+
+        with_quirks(quirks, || {
+            assert_eq!(body_fld_octets(b"0)").unwrap().1, 0);
+            assert_eq!(body_fld_octets(b"1)").unwrap().1, 1);
             assert_eq!(body_fld_octets(b"-0)").unwrap().1, 0);
             assert_eq!(body_fld_octets(b"-1)").unwrap().1, 0);
             assert_eq!(body_fld_octets(b"-999999)").unwrap().1, 0);
-        }
+        });
     }
 }
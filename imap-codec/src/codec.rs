@@ -1,6 +1,8 @@
 pub mod decode;
 pub mod encode;
 
+use decode::{DecodeLimits, DecodeQuirks};
+
 /// Codec for greetings.
 #[derive(Debug, Default)]
 // We use `#[non_exhaustive]` to prevent users from using struct literal syntax.
@@ -8,27 +10,39 @@ pub mod encode;
 // This allows to add configuration options later. For example, the
 // codec could transparently replace all literals with non-sync literals.
 #[non_exhaustive]
-pub struct GreetingCodec;
+pub struct GreetingCodec {
+    pub(crate) quirks: DecodeQuirks,
+}
 
 /// Codec for commands.
 #[derive(Debug, Default)]
 #[non_exhaustive]
-pub struct CommandCodec;
+pub struct CommandCodec {
+    pub(crate) limits: DecodeLimits,
+    pub(crate) quirks: DecodeQuirks,
+}
 
 /// Codec for authenticate data lines.
 #[derive(Debug, Default)]
 #[non_exhaustive]
-pub struct AuthenticateDataCodec;
+pub struct AuthenticateDataCodec {
+    pub(crate) quirks: DecodeQuirks,
+}
 
 /// Codec for responses.
 #[derive(Debug, Default)]
 #[non_exhaustive]
-pub struct ResponseCodec;
+pub struct ResponseCodec {
+    pub(crate) limits: DecodeLimits,
+    pub(crate) quirks: DecodeQuirks,
+}
 
 /// Codec for idle dones.
 #[derive(Debug, Default)]
 #[non_exhaustive]
-pub struct IdleDoneCodec;
+pub struct IdleDoneCodec {
+    pub(crate) quirks: DecodeQuirks,
+}
 
 macro_rules! impl_codec_new {
     ($codec:ty) => {
@@ -47,6 +61,49 @@ impl_codec_new!(AuthenticateDataCodec);
 impl_codec_new!(ResponseCodec);
 impl_codec_new!(IdleDoneCodec);
 
+macro_rules! impl_codec_limits {
+    ($codec:ty) => {
+        impl $codec {
+            /// Use the given limits during decoding.
+            pub fn with_limits(mut self, limits: DecodeLimits) -> Self {
+                self.limits = limits;
+                self
+            }
+
+            /// Limits used during decoding.
+            pub fn limits(&self) -> &DecodeLimits {
+                &self.limits
+            }
+        }
+    };
+}
+
+impl_codec_limits!(CommandCodec);
+impl_codec_limits!(ResponseCodec);
+
+macro_rules! impl_codec_quirks {
+    ($codec:ty) => {
+        impl $codec {
+            /// Tolerate the given quirks during decoding.
+            pub fn with_quirks(mut self, quirks: DecodeQuirks) -> Self {
+                self.quirks = quirks;
+                self
+            }
+
+            /// Quirks tolerated during decoding.
+            pub fn quirks(&self) -> &DecodeQuirks {
+                &self.quirks
+            }
+        }
+    };
+}
+
+impl_codec_quirks!(GreetingCodec);
+impl_codec_quirks!(CommandCodec);
+impl_codec_quirks!(AuthenticateDataCodec);
+impl_codec_quirks!(ResponseCodec);
+impl_codec_quirks!(IdleDoneCodec);
+
 #[cfg(test)]
 mod tests {
     use std::num::NonZeroU32;
@@ -64,7 +121,9 @@ mod tests {
 
     use super::*;
     use crate::{
-        decode::{CommandDecodeError, Decoder, GreetingDecodeError, ResponseDecodeError},
+        decode::{
+            CommandDecodeError, DecodeQuirks, Decoder, GreetingDecodeError, ResponseDecodeError,
+        },
         testing::{
             kat_inverse_authenticate_data, kat_inverse_command, kat_inverse_done,
             kat_inverse_greeting, kat_inverse_response,
@@ -184,9 +243,6 @@ mod tests {
             (b"* OK ".as_ref(), Err(GreetingDecodeError::Incomplete)),
             (b"* OK .".as_ref(), Err(GreetingDecodeError::Incomplete)),
             (b"* OK .\r".as_ref(), Err(GreetingDecodeError::Incomplete)),
-            // Failed
-            (b"**".as_ref(), Err(GreetingDecodeError::Failed)),
-            (b"* NO x\r\n".as_ref(), Err(GreetingDecodeError::Failed)),
         ];
 
         for (test, expected) in tests {
@@ -200,6 +256,19 @@ mod tests {
                 assert_eq!(expected, got);
             }
         }
+
+        let tests = [
+            // Failed
+            (b"**".as_ref(), 1),
+            (b"* NO x\r\n".as_ref(), 2),
+        ];
+
+        for (test, offset) in tests {
+            match GreetingCodec::default().decode(test) {
+                Err(GreetingDecodeError::Failed(failure)) => assert_eq!(failure.offset(), offset),
+                got => panic!("Expected `Failed`, got {got:?}"),
+            }
+        }
     }
 
     #[test]
@@ -235,9 +304,6 @@ mod tests {
                 b"a select {5}\r\nxxx".as_ref(),
                 Err(CommandDecodeError::Incomplete),
             ),
-            // Failed
-            (b"* noop\r\n".as_ref(), Err(CommandDecodeError::Failed)),
-            (b"A  noop\r\n".as_ref(), Err(CommandDecodeError::Failed)),
         ];
 
         for (test, expected) in tests {
@@ -251,6 +317,21 @@ mod tests {
                 assert_eq!(expected, got);
             }
         }
+
+        let tests = [
+            // Failed
+            (b"* noop\r\n".as_ref(), 0),
+            (b"A  noop\r\n".as_ref(), 2),
+        ];
+
+        for (test, offset) in tests {
+            match CommandCodec::default().decode(test) {
+                Err(CommandDecodeError::Failed { failure, .. }) => {
+                    assert_eq!(failure.offset(), offset)
+                }
+                got => panic!("Expected `Failed`, got {got:?}"),
+            }
+        }
     }
 
     #[test]
@@ -277,12 +358,6 @@ mod tests {
                 b"* 1 FETCH (RFC822 {5}\r\n".as_ref(),
                 Err(ResponseDecodeError::LiteralFound { length: 5 }),
             ),
-            // Failed
-            (
-                b"*  search 1 2 3\r\n".as_ref(),
-                Err(ResponseDecodeError::Failed),
-            ),
-            (b"A search\r\n".as_ref(), Err(ResponseDecodeError::Failed)),
         ];
 
         for (test, expected) in tests {
@@ -296,5 +371,48 @@ mod tests {
                 assert_eq!(expected, got);
             }
         }
+
+        let tests = [
+            // Failed
+            (b"*  search 1 2 3\r\n".as_ref(), 2),
+            (b"A search\r\n".as_ref(), 2),
+        ];
+
+        for (test, offset) in tests {
+            match ResponseCodec::default().decode(test) {
+                Err(ResponseDecodeError::Failed(failure)) => assert_eq!(failure.offset(), offset),
+                got => panic!("Expected `Failed`, got {got:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_quirks_per_codec() {
+        let strict = ResponseCodec::new().with_quirks(DecodeQuirks::strict());
+        let lenient = ResponseCodec::new().with_quirks(DecodeQuirks::lenient());
+
+        let tests = [
+            b"* OK [HIGHESTMODSEQ 1]\r\n".as_ref(),
+            b"* SEARCH 1\n".as_ref(),
+            b"* 1 FETCH (BODYSTRUCTURE (\"TEXT\" \"PLAIN\" NIL NIL NIL \"7BIT\" -1 1))\r\n"
+                .as_ref(),
+        ];
+
+        for test in tests {
+            assert!(matches!(
+                strict.decode(test),
+                Err(ResponseDecodeError::Failed(_)) | Err(ResponseDecodeError::Incomplete)
+            ));
+            assert!(lenient.decode(test).is_ok());
+        }
+
+        let strict = CommandCodec::new().with_quirks(DecodeQuirks::strict());
+        let lenient = CommandCodec::new().with_quirks(DecodeQuirks::lenient());
+
+        assert!(strict.decode(b"A NOOP\n").is_err());
+        assert_eq!(
+            lenient.decode(b"A NOOP\n"),
+            Ok((b"".as_ref(), Command::new("A", CommandBody::Noop).unwrap()))
+        );
     }
 }
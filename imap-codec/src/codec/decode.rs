@@ -8,11 +8,17 @@
 //! This makes real-world decoding of IMAP more elaborate.
 //!
 //! Have a look at the [parse_command](https://github.com/duesee/imap-codec/blob/main/imap-codec/examples/parse_command.rs) example to see how a real-world application could decode IMAP.
+//!
+//! When messages are received in many chunks, e.g., a `FETCH` response carrying a large literal, use a [`StreamingDecoder`].
+//! It remembers its progress across calls and only invokes the parser once the message is complete.
 
-use std::num::{ParseIntError, TryFromIntError};
+use std::{
+    fmt::{Display, Formatter},
+    num::{ParseIntError, TryFromIntError},
+};
 
 #[cfg(feature = "bounded-static")]
-use bounded_static::{IntoBoundedStatic, ToStatic};
+use bounded_static::{IntoBoundedStatic, ToBoundedStatic, ToStatic};
 use imap_types::{
     auth::AuthenticateData,
     command::Command,
@@ -20,25 +26,52 @@ use imap_types::{
     extensions::idle::IdleDone,
     response::{Greeting, Response},
 };
-use nom::error::{ErrorKind, FromExternalError, ParseError};
+use nom::{
+    error::{context, ContextError, ErrorKind, FromExternalError, ParseError},
+    InputLength,
+};
 
 use crate::{
     auth::authenticate_data,
     command::command,
+    core::tag_imap,
     extensions::idle::idle_done,
     response::{greeting, response},
     AuthenticateDataCodec, CommandCodec, GreetingCodec, IdleDoneCodec, ResponseCodec,
 };
 
+mod lazy;
+mod limits;
+mod lossless;
+mod quirks;
+mod sink;
+mod stream;
+
+pub use lazy::{LazyCommand, LazyCommandBody, LazyList, LazyMacroOrMessageDataItemNames};
+use limits::with_limits;
+pub(crate) use limits::{
+    check_length, limit_exceeded, limits, many0, many1, separated_list0, separated_list1,
+    separated_list1_limited,
+};
+pub use limits::{DecodeLimits, Limit};
+pub use lossless::Lossless;
+pub use quirks::DecodeQuirks;
+pub(crate) use quirks::{crlf, quirks, with_quirks};
+pub use sink::{LiteralHandle, LiteralSinkDecoder};
+pub use stream::StreamingDecoder;
+
 /// An extended version of [`nom::IResult`].
 pub(crate) type IMAPResult<'a, I, O> = Result<(I, O), nom::Err<IMAPParseError<'a, I>>>;
 
 /// An extended version of [`nom::error::Error`].
 #[derive(Debug)]
 pub(crate) struct IMAPParseError<'a, I> {
-    #[allow(unused)]
     pub input: I,
     pub kind: IMAPErrorKind<'a>,
+    /// Grammar rules (innermost first) that were active when the error occurred.
+    ///
+    /// Note: This is only populated through [`nom::error::context`].
+    pub context: Vec<&'static str>,
 }
 
 /// An extended version of [`nom::error::ErrorKind`].
@@ -53,31 +86,47 @@ pub(crate) enum IMAPErrorKind<'a> {
     BadBase64,
     BadDateTime,
     LiteralContainsNull,
-    RecursionLimitExceeded,
+    LimitExceeded(Limit),
     Nom(ErrorKind),
 }
 
-impl<'a, I> ParseError<I> for IMAPParseError<'a, I> {
+impl<'a, I: InputLength> ParseError<I> for IMAPParseError<'a, I> {
     fn from_error_kind(input: I, kind: ErrorKind) -> Self {
         Self {
             input,
             kind: IMAPErrorKind::Nom(kind),
+            context: Vec::new(),
         }
     }
 
-    fn append(input: I, kind: ErrorKind, _: Self) -> Self {
-        Self {
-            input,
-            kind: IMAPErrorKind::Nom(kind),
+    fn append(_: I, _: ErrorKind, other: Self) -> Self {
+        // Keep the inner error because it points to where parsing actually failed.
+        other
+    }
+
+    fn or(self, other: Self) -> Self {
+        // Keep the error of the `alt` branch that got furthest (the first one on a tie).
+        if self.input.input_len() <= other.input.input_len() {
+            self
+        } else {
+            other
         }
     }
 }
 
+impl<'a, I> ContextError<I> for IMAPParseError<'a, I> {
+    fn add_context(_: I, ctx: &'static str, mut other: Self) -> Self {
+        other.context.push(ctx);
+        other
+    }
+}
+
 impl<'a, I> FromExternalError<I, ParseIntError> for IMAPParseError<'a, I> {
     fn from_external_error(input: I, _: ErrorKind, _: ParseIntError) -> Self {
         Self {
             input,
             kind: IMAPErrorKind::BadNumber,
+            context: Vec::new(),
         }
     }
 }
@@ -87,6 +136,7 @@ impl<'a, I> FromExternalError<I, TryFromIntError> for IMAPParseError<'a, I> {
         Self {
             input,
             kind: IMAPErrorKind::BadNumber,
+            context: Vec::new(),
         }
     }
 }
@@ -96,6 +146,7 @@ impl<'a, I> FromExternalError<I, base64::DecodeError> for IMAPParseError<'a, I>
         Self {
             input,
             kind: IMAPErrorKind::BadBase64,
+            context: Vec::new(),
         }
     }
 }
@@ -110,6 +161,18 @@ pub trait Decoder {
     fn decode<'a>(&self, input: &'a [u8])
         -> Result<(&'a [u8], Self::Message<'a>), Self::Error<'a>>;
 
+    /// Decode a message and keep a reference to its source (see [`Lossless`]).
+    #[allow(clippy::type_complexity)]
+    fn decode_lossless<'a>(
+        &self,
+        input: &'a [u8],
+    ) -> Result<(&'a [u8], Lossless<'a, Self::Message<'a>>), Self::Error<'a>> {
+        let (remaining, message) = self.decode(input)?;
+        let source = &input[..input.len() - remaining.len()];
+
+        Ok((remaining, Lossless::new(message, source)))
+    }
+
     #[cfg(feature = "bounded-static")]
     #[cfg_attr(docsrs, doc(cfg(feature = "bounded-static")))]
     fn decode_static<'a>(
@@ -125,6 +188,133 @@ pub trait Decoder {
     }
 }
 
+/// Details about why decoding failed.
+///
+/// This is meant for diagnostics, e.g., to distinguish a client bug from a parser bug in logs.
+/// Use the [`Display`] implementation for a human-readable description.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DecodeFailure {
+    offset: usize,
+    kind: DecodeFailureKind,
+    context: Vec<&'static str>,
+}
+
+/// Kind of a [`DecodeFailure`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum DecodeFailureKind {
+    /// Input didn't match the grammar.
+    ///
+    /// Contains the kind of the [`nom`] parser that failed.
+    Syntax(ErrorKind),
+    /// A number was out of range.
+    BadNumber,
+    /// Base64 data could not be decoded.
+    BadBase64,
+    /// A date or time was invalid, e.g., "31-Feb-2023".
+    BadDateTime,
+    /// A literal contained a NUL byte.
+    LiteralContainsNull,
+    /// A limit of the decoder was exceeded (see [`DecodeLimits`]).
+    LimitExceeded(Limit),
+}
+
+impl DecodeFailure {
+    fn new(input: &[u8], error: IMAPParseError<&[u8]>) -> Self {
+        let kind = match error.kind {
+            IMAPErrorKind::BadNumber => DecodeFailureKind::BadNumber,
+            IMAPErrorKind::BadBase64 => DecodeFailureKind::BadBase64,
+            IMAPErrorKind::BadDateTime => DecodeFailureKind::BadDateTime,
+            IMAPErrorKind::LiteralContainsNull => DecodeFailureKind::LiteralContainsNull,
+            IMAPErrorKind::LimitExceeded(limit) => DecodeFailureKind::LimitExceeded(limit),
+            IMAPErrorKind::Nom(kind) => DecodeFailureKind::Syntax(kind),
+            // Note: Literals are reported separately by the decoders that support them.
+            IMAPErrorKind::Literal { .. } => DecodeFailureKind::Syntax(ErrorKind::Verify),
+        };
+
+        let mut context = error.context;
+        context.reverse();
+
+        Self {
+            offset: input.len() - error.input.len(),
+            kind,
+            context,
+        }
+    }
+
+    /// Byte offset (in the decoded input) at which decoding failed.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Kind of failure.
+    pub fn kind(&self) -> DecodeFailureKind {
+        self.kind
+    }
+
+    /// Grammar rules that were active when decoding failed (outermost first).
+    ///
+    /// For example, `["command", "command body", "sequence-set"]`.
+    pub fn context(&self) -> &[&'static str] {
+        &self.context
+    }
+
+    /// What the decoder expected at [`offset`](Self::offset), e.g., "SP after tag".
+    ///
+    /// This is the innermost grammar rule.
+    pub fn expected(&self) -> Option<&'static str> {
+        self.context.last().copied()
+    }
+}
+
+impl Display for DecodeFailure {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match (self.kind, self.expected()) {
+            (DecodeFailureKind::Syntax(_), Some(expected)) => write!(f, "expected {expected}")?,
+            (DecodeFailureKind::Syntax(_), None) => f.write_str("unexpected input")?,
+            (DecodeFailureKind::BadNumber, _) => f.write_str("bad number")?,
+            (DecodeFailureKind::BadBase64, _) => f.write_str("bad base64")?,
+            (DecodeFailureKind::BadDateTime, _) => f.write_str("bad date-time")?,
+            (DecodeFailureKind::LiteralContainsNull, _) => f.write_str("literal contains NUL")?,
+            (DecodeFailureKind::LimitExceeded(limit), _) => {
+                write!(f, "{} limit exceeded", limit.describe())?
+            }
+        }
+
+        write!(f, " at byte {}", self.offset)?;
+
+        // The innermost rule is already part of the expectation.
+        let outer = match self.kind {
+            DecodeFailureKind::Syntax(_) => &self.context[..self.context.len().saturating_sub(1)],
+            _ => &self.context[..],
+        };
+
+        if !outer.is_empty() {
+            write!(f, " (in {})", outer.join(" > "))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "bounded-static")]
+impl ToBoundedStatic for DecodeFailure {
+    type Static = Self;
+
+    fn to_static(&self) -> Self::Static {
+        self.clone()
+    }
+}
+
+#[cfg(feature = "bounded-static")]
+impl IntoBoundedStatic for DecodeFailure {
+    type Static = Self;
+
+    fn into_static(self) -> Self::Static {
+        self
+    }
+}
+
 /// Error during greeting decoding.
 #[cfg_attr(feature = "bounded-static", derive(ToStatic))]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -133,7 +323,7 @@ pub enum GreetingDecodeError {
     Incomplete,
 
     /// Decoding failed.
-    Failed,
+    Failed(DecodeFailure),
 }
 
 /// Error during command decoding.
@@ -193,7 +383,33 @@ pub enum CommandDecodeError<'a> {
     },
 
     /// Decoding failed.
-    Failed,
+    ///
+    /// A server should reply with `<tag> BAD ...` (or `* BAD ...` when no tag was found) and
+    /// skip `line_length` bytes to resynchronize with the client.
+    Failed {
+        /// The tag of the command, if one could be parsed.
+        tag: Option<Tag<'a>>,
+
+        /// Length of the offending line (including the line ending), measured from the start
+        /// of the input.
+        ///
+        /// The data of non-synchronizing literals (and of synchronizing literals the parser got
+        /// past, i.e., that were already accepted) is included. The data of a synchronizing
+        /// literal that wasn't accepted isn't, because the client only sends it after a command
+        /// continuation request.
+        ///
+        /// `None` when the input doesn't contain a line ending (yet). In this case, more data
+        /// needs to be read (and skipped) until the next line ending.
+        ///
+        /// Note: When a non-synchronizing literal was rejected, e.g., due to
+        /// [`DecodeLimits::max_literal_length`], and its data wasn't received completely,
+        /// `line_length` exceeds the input. The server must then read and discard the missing
+        /// data or close the connection (see RFC 7888, section 4).
+        line_length: Option<usize>,
+
+        /// Details about the failure.
+        failure: DecodeFailure,
+    },
 }
 
 /// Error during authenticate data line decoding.
@@ -228,7 +444,7 @@ pub enum ResponseDecodeError {
     },
 
     /// Decoding failed.
-    Failed,
+    Failed(DecodeFailure),
 }
 
 /// Error during idle done decoding.
@@ -252,11 +468,13 @@ impl Decoder for GreetingCodec {
         &self,
         input: &'a [u8],
     ) -> Result<(&'a [u8], Self::Message<'a>), Self::Error<'static>> {
-        match greeting(input) {
+        with_quirks(self.quirks, || match context("greeting", greeting)(input) {
             Ok((rem, grt)) => Ok((rem, grt)),
             Err(nom::Err::Incomplete(_)) => Err(GreetingDecodeError::Incomplete),
-            Err(nom::Err::Failure(_)) | Err(nom::Err::Error(_)) => Err(GreetingDecodeError::Failed),
-        }
+            Err(nom::Err::Failure(error) | nom::Err::Error(error)) => Err(
+                GreetingDecodeError::Failed(DecodeFailure::new(input, error)),
+            ),
+        })
     }
 }
 
@@ -268,26 +486,120 @@ impl Decoder for CommandCodec {
         &self,
         input: &'a [u8],
     ) -> Result<(&'a [u8], Self::Message<'a>), Self::Error<'a>> {
-        match command(input) {
-            Ok((rem, cmd)) => Ok((rem, cmd)),
-            Err(nom::Err::Incomplete(_)) => Err(CommandDecodeError::Incomplete),
-            Err(nom::Err::Failure(error)) => match error {
-                IMAPParseError {
-                    input: _,
-                    kind: IMAPErrorKind::Literal { tag, length, mode },
-                } => Err(CommandDecodeError::LiteralFound {
-                    // Unwrap: We *must* receive a `tag` during command parsing.
-                    tag: tag.expect("Expected `Some(tag)` in `IMAPErrorKind::Literal`, got `None`"),
-                    length,
-                    mode,
-                }),
-                _ => Err(CommandDecodeError::Failed),
-            },
-            Err(nom::Err::Error(_)) => Err(CommandDecodeError::Failed),
+        with_quirks(self.quirks, || {
+            with_limits(self.limits, || match context("command", command)(input) {
+                Ok((rem, cmd)) => Ok((rem, cmd)),
+                Err(nom::Err::Incomplete(_)) => Err(CommandDecodeError::Incomplete),
+                Err(nom::Err::Failure(error)) => match error {
+                    IMAPParseError {
+                        kind: IMAPErrorKind::Literal { tag, length, mode },
+                        ..
+                    } => Err(CommandDecodeError::LiteralFound {
+                        // Unwrap: We *must* receive a `tag` during command parsing.
+                        tag: tag
+                            .expect("Expected `Some(tag)` in `IMAPErrorKind::Literal`, got `None`"),
+                        length,
+                        mode,
+                    }),
+                    error => Err(CommandDecodeError::failed(input, error)),
+                },
+                Err(nom::Err::Error(error)) => Err(CommandDecodeError::failed(input, error)),
+            })
+        })
+    }
+}
+
+impl<'a> CommandDecodeError<'a> {
+    fn failed(input: &'a [u8], error: IMAPParseError<&'a [u8]>) -> Self {
+        let failure = DecodeFailure::new(input, error);
+
+        // Try to recover the tag so that the server can reply with a tagged BAD.
+        let tag = tag_imap(input).ok().map(|(_, tag)| tag);
+
+        let line_length = match failure.kind {
+            DecodeFailureKind::LimitExceeded(Limit::LiteralLength) => {
+                rejected_literal_line_length(input, failure.offset)
+            }
+            _ => command_line_length(input, failure.offset),
+        };
+
+        Self::Failed {
+            tag,
+            line_length,
+            failure,
+        }
+    }
+}
+
+/// Find the length of a command whose literal (announced at `offset`) was rejected.
+///
+/// The data of a synchronizing literal is not expected to follow, i.e., the command ends with
+/// the announcement. The data of a non-synchronizing literal is sent anyway and must be skipped.
+/// If the command is not complete yet, the result exceeds the input up to the end of the literal
+/// data.
+fn rejected_literal_line_length(input: &[u8], offset: usize) -> Option<usize> {
+    let end = offset + input[offset..].iter().position(|byte| *byte == b'\n')? + 1;
+
+    match literal_announcement(&input[offset..end]) {
+        Some((length, LiteralMode::NonSync)) => {
+            command_line_length(input, offset).or(Some(end + length as usize))
+        }
+        _ => Some(end),
+    }
+}
+
+/// Find the length of the first command in `input`, including its line ending.
+///
+/// Literals announced at the end of a line (`{<n>}` or `{<n>+}`) are skipped, so that the
+/// result points to the beginning of the next command. A synchronizing literal is only skipped
+/// when the parser failed after its announcement (at `offset`). Otherwise, no continuation
+/// request was sent and the client won't send the literal data. Returns `None` if the command
+/// is not complete yet.
+fn command_line_length(input: &[u8], offset: usize) -> Option<usize> {
+    let mut start = 0;
+
+    loop {
+        let end = start + input[start..].iter().position(|byte| *byte == b'\n')? + 1;
+
+        match literal_announcement(&input[start..end]) {
+            Some((_, LiteralMode::Sync)) if offset < end => return Some(end),
+            Some((length, _)) => start = end + length as usize,
+            None => return Some(end),
+        }
+
+        if start > input.len() {
+            return None;
         }
     }
 }
 
+/// Return the length and mode of the literal announced at the end of `line` (if any).
+///
+/// Note: `line` must include the line ending. This function only looks at the syntax, i.e.,
+/// `{<n>}` or `{<n>+}` right before the line ending, and doesn't know whether the announcement
+/// is part of, e.g., a `text`.
+pub(crate) fn literal_announcement(line: &[u8]) -> Option<(u32, LiteralMode)> {
+    let line = line.strip_suffix(b"\n")?;
+    let line = line.strip_suffix(b"\r").unwrap_or(line);
+    let line = line.strip_suffix(b"}")?;
+    let (line, mode) = match line.strip_suffix(b"+") {
+        Some(line) => (line, LiteralMode::NonSync),
+        None => (line, LiteralMode::Sync),
+    };
+
+    let position = line.iter().rposition(|byte| *byte == b'{')?;
+    let digits = &line[position + 1..];
+
+    if digits.is_empty() || !digits.iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+
+    // Unwrap: `digits` only contains ASCII digits.
+    let length = std::str::from_utf8(digits).unwrap().parse().ok()?;
+
+    Some((length, mode))
+}
+
 impl Decoder for ResponseCodec {
     type Message<'a> = Response<'a>;
     type Error<'a> = ResponseDecodeError;
@@ -296,17 +608,21 @@ impl Decoder for ResponseCodec {
         &self,
         input: &'a [u8],
     ) -> Result<(&'a [u8], Self::Message<'a>), Self::Error<'static>> {
-        match response(input) {
-            Ok((rem, rsp)) => Ok((rem, rsp)),
-            Err(nom::Err::Incomplete(_)) => Err(ResponseDecodeError::Incomplete),
-            Err(nom::Err::Error(error) | nom::Err::Failure(error)) => match error {
-                IMAPParseError {
-                    kind: IMAPErrorKind::Literal { length, .. },
-                    ..
-                } => Err(ResponseDecodeError::LiteralFound { length }),
-                _ => Err(ResponseDecodeError::Failed),
-            },
-        }
+        with_quirks(self.quirks, || {
+            with_limits(self.limits, || match context("response", response)(input) {
+                Ok((rem, rsp)) => Ok((rem, rsp)),
+                Err(nom::Err::Incomplete(_)) => Err(ResponseDecodeError::Incomplete),
+                Err(nom::Err::Error(error) | nom::Err::Failure(error)) => match error {
+                    IMAPParseError {
+                        kind: IMAPErrorKind::Literal { length, .. },
+                        ..
+                    } => Err(ResponseDecodeError::LiteralFound { length }),
+                    error => Err(ResponseDecodeError::Failed(DecodeFailure::new(
+                        input, error,
+                    ))),
+                },
+            })
+        })
     }
 }
 
@@ -318,13 +634,13 @@ impl Decoder for AuthenticateDataCodec {
         &self,
         input: &'a [u8],
     ) -> Result<(&'a [u8], Self::Message<'a>), Self::Error<'static>> {
-        match authenticate_data(input) {
+        with_quirks(self.quirks, || match authenticate_data(input) {
             Ok((rem, rsp)) => Ok((rem, rsp)),
             Err(nom::Err::Incomplete(_)) => Err(AuthenticateDataDecodeError::Incomplete),
             Err(nom::Err::Failure(_)) | Err(nom::Err::Error(_)) => {
                 Err(AuthenticateDataDecodeError::Failed)
             }
-        }
+        })
     }
 }
 
@@ -336,11 +652,11 @@ impl Decoder for IdleDoneCodec {
         &self,
         input: &'a [u8],
     ) -> Result<(&'a [u8], Self::Message<'a>), Self::Error<'static>> {
-        match idle_done(input) {
+        with_quirks(self.quirks, || match idle_done(input) {
             Ok((rem, rsp)) => Ok((rem, rsp)),
             Err(nom::Err::Incomplete(_)) => Err(IdleDoneDecodeError::Incomplete),
             Err(nom::Err::Failure(_)) | Err(nom::Err::Error(_)) => Err(IdleDoneDecodeError::Failed),
-        }
+        })
     }
 }
 
@@ -394,8 +710,22 @@ mod tests {
             (b"* OK .".as_ref(), Err(GreetingDecodeError::Incomplete)),
             (b"* OK .\r".as_ref(), Err(GreetingDecodeError::Incomplete)),
             // Failed
-            (b"**".as_ref(), Err(GreetingDecodeError::Failed)),
-            (b"* NO x\r\n".as_ref(), Err(GreetingDecodeError::Failed)),
+            (
+                b"**".as_ref(),
+                Err(GreetingDecodeError::Failed(DecodeFailure {
+                    offset: 1,
+                    kind: DecodeFailureKind::Syntax(ErrorKind::Satisfy),
+                    context: vec!["greeting"],
+                })),
+            ),
+            (
+                b"* NO x\r\n".as_ref(),
+                Err(GreetingDecodeError::Failed(DecodeFailure {
+                    offset: 2,
+                    kind: DecodeFailureKind::Syntax(ErrorKind::Tag),
+                    context: vec!["greeting", "resp-cond-auth / resp-cond-bye"],
+                })),
+            ),
         ];
 
         for (test, expected) in tests {
@@ -475,8 +805,103 @@ mod tests {
                 Err(CommandDecodeError::Incomplete),
             ),
             // Failed
-            (b"* noop\r\n".as_ref(), Err(CommandDecodeError::Failed)),
-            (b"A  noop\r\n".as_ref(), Err(CommandDecodeError::Failed)),
+            (
+                b"* noop\r\n".as_ref(),
+                Err(CommandDecodeError::Failed {
+                    tag: None,
+                    line_length: Some(8),
+                    failure: DecodeFailure {
+                        offset: 0,
+                        kind: DecodeFailureKind::Syntax(ErrorKind::TakeWhile1),
+                        context: vec!["command", "tag"],
+                    },
+                }),
+            ),
+            (
+                b"A  noop\r\n".as_ref(),
+                Err(CommandDecodeError::Failed {
+                    tag: Some(Tag::try_from("A").unwrap()),
+                    line_length: Some(9),
+                    failure: DecodeFailure {
+                        offset: 2,
+                        kind: DecodeFailureKind::Syntax(ErrorKind::Tag),
+                        context: vec!["command", "command body"],
+                    },
+                }),
+            ),
+            (
+                b"A noop \r\nB noop\r\n".as_ref(),
+                Err(CommandDecodeError::Failed {
+                    tag: Some(Tag::try_from("A").unwrap()),
+                    line_length: Some(9),
+                    failure: DecodeFailure {
+                        offset: 6,
+                        kind: DecodeFailureKind::Syntax(ErrorKind::Satisfy),
+                        context: vec!["command", "CRLF"],
+                    },
+                }),
+            ),
+            (
+                b"A1 FETCH 1 (FLAGS {3}\r\nxyz)\r\n".as_ref(),
+                Err(CommandDecodeError::Failed {
+                    tag: Some(Tag::try_from("A1").unwrap()),
+                    // The client doesn't send `xyz` without a continuation request.
+                    line_length: Some(23),
+                    failure: DecodeFailure {
+                        offset: 17,
+                        kind: DecodeFailureKind::Syntax(ErrorKind::Tag),
+                        context: vec!["command", "command body", "fetch-att", "fetch-att list"],
+                    },
+                }),
+            ),
+            (
+                b"A XYZ {5}\r\nB NOOP\r\n".as_ref(),
+                Err(CommandDecodeError::Failed {
+                    tag: Some(Tag::try_from("A").unwrap()),
+                    line_length: Some(11),
+                    failure: DecodeFailure {
+                        offset: 2,
+                        kind: DecodeFailureKind::Syntax(ErrorKind::Tag),
+                        context: vec!["command", "command body"],
+                    },
+                }),
+            ),
+            (
+                b"A XYZ {5+}\r\nhello\r\nB NOOP\r\n".as_ref(),
+                Err(CommandDecodeError::Failed {
+                    tag: Some(Tag::try_from("A").unwrap()),
+                    line_length: Some(19),
+                    failure: DecodeFailure {
+                        offset: 2,
+                        kind: DecodeFailureKind::Syntax(ErrorKind::Tag),
+                        context: vec!["command", "command body"],
+                    },
+                }),
+            ),
+            (
+                b"A LOGIN {5}\r\nalice\r\nB NOOP\r\n".as_ref(),
+                Err(CommandDecodeError::Failed {
+                    tag: Some(Tag::try_from("A").unwrap()),
+                    line_length: Some(20),
+                    failure: DecodeFailure {
+                        offset: 18,
+                        kind: DecodeFailureKind::Syntax(ErrorKind::Satisfy),
+                        context: vec!["command", "command body", "SP"],
+                    },
+                }),
+            ),
+            (
+                b"A1 XYZ".as_ref(),
+                Err(CommandDecodeError::Failed {
+                    tag: Some(Tag::try_from("A1").unwrap()),
+                    line_length: None,
+                    failure: DecodeFailure {
+                        offset: 3,
+                        kind: DecodeFailureKind::Syntax(ErrorKind::Tag),
+                        context: vec!["command", "command body"],
+                    },
+                }),
+            ),
         ];
 
         for (test, expected) in tests {
@@ -665,9 +1090,20 @@ mod tests {
             // Failed
             (
                 b"*  search 1 2 3\r\n".as_ref(),
-                Err(ResponseDecodeError::Failed),
+                Err(ResponseDecodeError::Failed(DecodeFailure {
+                    offset: 2,
+                    kind: DecodeFailureKind::Syntax(ErrorKind::Tag),
+                    context: vec!["response", "response-data"],
+                })),
+            ),
+            (
+                b"A search\r\n".as_ref(),
+                Err(ResponseDecodeError::Failed(DecodeFailure {
+                    offset: 2,
+                    kind: DecodeFailureKind::Syntax(ErrorKind::Tag),
+                    context: vec!["response", "resp-cond-state"],
+                })),
             ),
-            (b"A search\r\n".as_ref(), Err(ResponseDecodeError::Failed)),
         ];
 
         for (test, expected) in tests {
@@ -682,4 +1118,119 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_decode_failure_display() {
+        let tests = [
+            (
+                b"A SELECT \"INBOX\r\n".as_ref(),
+                "expected mailbox at byte 15 (in command > command body)",
+            ),
+            (
+                b"A noop \r\n".as_ref(),
+                "expected CRLF at byte 6 (in command)",
+            ),
+            (
+                b"A APPEND INBOX \"31-Feb-2023 10:00:00 +0000\" {1}\r\nx\r\n".as_ref(),
+                "bad date-time at byte 15 (in command > command body)",
+            ),
+            (
+                b"A1 SELECT\r\n".as_ref(),
+                "expected SP at byte 9 (in command > command body)",
+            ),
+            (
+                b"A1 FETCH 1 (FLAGS XYZ)\r\n".as_ref(),
+                "expected fetch-att list at byte 17 (in command > command body > fetch-att)",
+            ),
+            (
+                b"A1 FETCH 1 XYZ\r\n".as_ref(),
+                "expected fetch-att at byte 11 (in command > command body)",
+            ),
+            (
+                b"A1 FETCH x FLAGS\r\n".as_ref(),
+                "expected sequence-set at byte 9 (in command > command body)",
+            ),
+            (
+                b"A1 LIST \"\" \r\n".as_ref(),
+                "expected list-mailbox at byte 11 (in command > command body)",
+            ),
+            (
+                b"A1 STORE 1 FOO\r\n".as_ref(),
+                "expected store-att-flags at byte 11 (in command > command body)",
+            ),
+        ];
+
+        for (test, expected) in tests {
+            match CommandCodec::default().decode(test) {
+                Err(CommandDecodeError::Failed { failure, .. }) => {
+                    assert_eq!(failure.to_string(), expected)
+                }
+                got => panic!("Expected `Failed`, got {got:?}"),
+            }
+        }
+
+        match ResponseCodec::default().decode(b"* 1 FETCH (UID 0)\r\n") {
+            Err(ResponseDecodeError::Failed(failure)) => {
+                assert_eq!(failure.offset(), 15);
+                assert_eq!(
+                    failure.context(),
+                    ["response", "response-data", "msg-att", "uniqueid"]
+                );
+                assert_eq!(failure.expected(), Some("uniqueid"));
+            }
+            got => panic!("Expected `Failed`, got {got:?}"),
+        }
+
+        let tests = [
+            (
+                b"*  search\r\n".as_ref(),
+                "expected response-data at byte 2 (in response)",
+            ),
+            (
+                b"A OK\r\n".as_ref(),
+                "expected resp-cond-state at byte 4 (in response)",
+            ),
+            (
+                b"* OK [ALERT hello\r\n".as_ref(),
+                "expected resp-text at byte 17 (in response > response-data)",
+            ),
+            (
+                b"* 1 FETCH (ENVELOPE (NIL))\r\n".as_ref(),
+                "expected envelope at byte 24 (in response > response-data > msg-att)",
+            ),
+            (
+                b"* 1 FETCH (BODY (\"TEXT\" \"PLAIN\" NIL NIL NIL \"7BIT\" x 1))\r\n".as_ref(),
+                "expected body at byte 51 (in response > response-data > msg-att)",
+            ),
+        ];
+
+        for (test, expected) in tests {
+            match ResponseCodec::default().decode(test) {
+                Err(ResponseDecodeError::Failed(failure)) => {
+                    assert_eq!(failure.to_string(), expected)
+                }
+                got => panic!("Expected `Failed`, got {got:?}"),
+            }
+        }
+
+        let tests = [
+            (
+                b"* HELLO\r\n".as_ref(),
+                "expected resp-cond-auth / resp-cond-bye at byte 2 (in greeting)",
+            ),
+            (
+                b"* OK [ALERT hello\r\n".as_ref(),
+                "expected resp-text at byte 17 (in greeting > resp-cond-auth / resp-cond-bye)",
+            ),
+        ];
+
+        for (test, expected) in tests {
+            match GreetingCodec::default().decode(test) {
+                Err(GreetingDecodeError::Failed(failure)) => {
+                    assert_eq!(failure.to_string(), expected)
+                }
+                got => panic!("Expected `Failed`, got {got:?}"),
+            }
+        }
+    }
 }
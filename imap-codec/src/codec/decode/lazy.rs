@@ -0,0 +1,622 @@
+use std::fmt::{Debug, Formatter};
+
+use abnf_core::streaming::sp;
+use imap_types::{
+    command::{Command, CommandBody},
+    core::{Charset, NonEmptyVec, Tag},
+    fetch::{Macro, MacroOrMessageDataItemNames, MessageDataItemName},
+    flag::{Flag, StoreAttribute, StoreResponse, StoreType},
+    search::SearchKey,
+    sequence::{Sequence, SequenceSet},
+};
+use nom::{
+    branch::alt,
+    bytes::streaming::{tag, tag_no_case},
+    character::streaming::char,
+    combinator::{map, opt, value},
+    multi::many0_count,
+    sequence::{delimited, preceded, terminated, tuple},
+};
+
+use super::{
+    limit_exceeded, limits, quirks, with_limits, with_quirks, CommandDecodeError, DecodeLimits,
+    DecodeQuirks, Decoder, IMAPResult, Limit,
+};
+use crate::{
+    command::{store_response, store_type},
+    core::{charset, tag_imap},
+    decode::crlf,
+    fetch::fetch_att,
+    flag::flag,
+    search::search_key,
+    sequence::sequence,
+    CommandCodec,
+};
+
+type Item<'a, T> = fn(&'a [u8]) -> IMAPResult<'a, &'a [u8], T>;
+
+/// A command whose lists are decoded on demand.
+///
+/// Create one through [`CommandCodec::decode_lazy`].
+#[derive(Clone, Debug)]
+pub struct LazyCommand<'a> {
+    /// Tag.
+    pub tag: Tag<'a>,
+    /// Body.
+    pub body: LazyCommandBody<'a>,
+}
+
+impl<'a> LazyCommand<'a> {
+    /// Decode all lists and return the (regular) command.
+    pub fn into_command(self) -> Command<'a> {
+        let body = match self.body {
+            LazyCommandBody::Fetch {
+                sequence_set,
+                macro_or_item_names,
+                uid,
+            } => CommandBody::Fetch {
+                sequence_set: sequence_set.into(),
+                macro_or_item_names: match macro_or_item_names {
+                    LazyMacroOrMessageDataItemNames::Macro(r#macro) => {
+                        MacroOrMessageDataItemNames::Macro(r#macro)
+                    }
+                    LazyMacroOrMessageDataItemNames::MessageDataItemNames(item_names) => {
+                        MacroOrMessageDataItemNames::MessageDataItemNames(item_names.collect())
+                    }
+                },
+                modifiers: Vec::new(),
+                uid,
+            },
+            LazyCommandBody::Store {
+                sequence_set,
+                kind,
+                response,
+                flags,
+                uid,
+            } => CommandBody::Store {
+                sequence_set: sequence_set.into(),
+                kind,
+                response,
+                attribute: StoreAttribute::Flags(flags.collect()),
+                uid,
+            },
+            LazyCommandBody::Search {
+                charset,
+                mut criteria,
+                uid,
+            } => CommandBody::Search {
+                charset,
+                criteria: match criteria.len() {
+                    // Unwrap: A search program has at least one search key.
+                    1 => criteria.next().unwrap(),
+                    _ => SearchKey::And(NonEmptyVec::unvalidated(criteria.collect())),
+                },
+                return_options: Vec::new(),
+                uid,
+            },
+            LazyCommandBody::Other(body) => body,
+        };
+
+        Command {
+            tag: self.tag,
+            body,
+        }
+    }
+}
+
+/// The body of a [`LazyCommand`].
+///
+/// Only the common forms of `FETCH`, `STORE`, and `SEARCH` (and their `UID` variants) are
+/// decoded lazily. All other commands are decoded as usual and returned as
+/// [`LazyCommandBody::Other`].
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub enum LazyCommandBody<'a> {
+    /// `FETCH` without modifiers.
+    Fetch {
+        sequence_set: LazyList<'a, Sequence>,
+        macro_or_item_names: LazyMacroOrMessageDataItemNames<'a>,
+        uid: bool,
+    },
+    /// `STORE` of `FLAGS`.
+    Store {
+        sequence_set: LazyList<'a, Sequence>,
+        kind: StoreType,
+        response: StoreResponse,
+        flags: LazyList<'a, Flag<'a>>,
+        uid: bool,
+    },
+    /// `SEARCH` without return options.
+    ///
+    /// Note: Multiple search keys are not combined into a single `SearchKey::And`.
+    Search {
+        charset: Option<Charset<'a>>,
+        criteria: LazyList<'a, SearchKey<'a>>,
+        uid: bool,
+    },
+    /// Any other command.
+    Other(CommandBody<'a>),
+}
+
+/// Either a macro or a lazy list of message data item names.
+#[derive(Clone, Debug)]
+pub enum LazyMacroOrMessageDataItemNames<'a> {
+    Macro(Macro),
+    MessageDataItemNames(LazyList<'a, MessageDataItemName<'a>>),
+}
+
+/// A list that was validated during decoding but whose items are decoded on iteration.
+///
+/// Iterating a list doesn't allocate (apart from what an item itself requires). The list can be
+/// cloned to iterate it again.
+pub struct LazyList<'a, T> {
+    input: &'a [u8],
+    length: usize,
+    item: Item<'a, T>,
+    limits: DecodeLimits,
+    quirks: DecodeQuirks,
+}
+
+impl<'a, T> LazyList<'a, T> {
+    /// Create a list of `length` (already validated) items, separated by a single byte.
+    ///
+    /// Note: `input` must extend beyond the last item because the parsers are streaming.
+    fn new(input: &'a [u8], length: usize, item: Item<'a, T>) -> Self {
+        Self {
+            input,
+            length,
+            item,
+            limits: limits(),
+            quirks: quirks(),
+        }
+    }
+}
+
+impl<'a, T> Clone for LazyList<'a, T> {
+    fn clone(&self) -> Self {
+        Self {
+            input: self.input,
+            length: self.length,
+            item: self.item,
+            limits: self.limits,
+            quirks: self.quirks,
+        }
+    }
+}
+
+impl<'a, T: Debug> Debug for LazyList<'a, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+impl<'a, T> Iterator for LazyList<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.length == 0 {
+            return None;
+        }
+
+        let result = with_quirks(self.quirks, || {
+            with_limits(self.limits, || (self.item)(self.input))
+        });
+
+        match result {
+            Ok((remaining, item)) => {
+                self.length -= 1;
+                // Skip the separator.
+                self.input = remaining.get(1..).unwrap_or_default();
+
+                Some(item)
+            }
+            // Note: Can't happen because the list was validated.
+            Err(_) => {
+                self.length = 0;
+
+                None
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.length, Some(self.length))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for LazyList<'a, T> {}
+
+impl<'a> From<LazyList<'a, Sequence>> for SequenceSet {
+    fn from(list: LazyList<'a, Sequence>) -> Self {
+        SequenceSet(NonEmptyVec::unvalidated(list.collect()))
+    }
+}
+
+impl CommandCodec {
+    /// Decode a command without allocating for its lists.
+    ///
+    /// The sequence set, `FETCH` items, `STORE` flags, and `SEARCH` keys are validated but only
+    /// decoded when iterated (see [`LazyList`]). This avoids heap allocations on hot paths.
+    /// Commands that can't be decoded lazily are decoded as usual (see
+    /// [`LazyCommandBody::Other`]). Errors are the same as with [`Decoder::decode`].
+    ///
+    /// Note: Items are parsed twice, i.e., during validation and when iterated. Items that
+    /// require allocations themselves, e.g., `NOT <search-key>`, still allocate.
+    ///
+    /// ```rust
+    /// # use imap_codec::{decode::LazyCommandBody, imap_types::sequence::Sequence, CommandCodec};
+    /// let (_, command) = CommandCodec::new()
+    ///     .decode_lazy(b"A UID FETCH 1:3,7 (UID FLAGS)\r\n")
+    ///     .unwrap();
+    ///
+    /// match command.body {
+    ///     LazyCommandBody::Fetch { sequence_set, .. } => {
+    ///         for sequence in sequence_set {
+    ///             // Do something with the sequence ...
+    ///             # let _: Sequence = sequence;
+    ///         }
+    ///     }
+    ///     _ => unreachable!(),
+    /// }
+    /// ```
+    pub fn decode_lazy<'a>(
+        &self,
+        input: &'a [u8],
+    ) -> Result<(&'a [u8], LazyCommand<'a>), CommandDecodeError<'a>> {
+        let result = with_quirks(self.quirks, || {
+            with_limits(self.limits, || lazy_command(input))
+        });
+
+        match result {
+            Ok((remaining, command)) => Ok((remaining, command)),
+            // Note: The regular parser produces the canonical result, e.g., `LiteralFound`.
+            Err(_) => {
+                let (remaining, command) = self.decode(input)?;
+
+                Ok((
+                    remaining,
+                    LazyCommand {
+                        tag: command.tag,
+                        body: LazyCommandBody::Other(command.body),
+                    },
+                ))
+            }
+        }
+    }
+}
+
+/// `tag SP ["UID" SP] (fetch / store / search) CRLF`
+fn lazy_command(input: &[u8]) -> IMAPResult<&[u8], LazyCommand> {
+    let mut parser = tuple((
+        terminated(tag_imap, sp),
+        opt(terminated(tag_no_case(b"UID"), sp)),
+        alt((lazy_fetch, lazy_store, lazy_search)),
+        crlf,
+    ));
+
+    let (remaining, (tag, uid_prefix, mut body, _)) = parser(input)?;
+
+    match body {
+        LazyCommandBody::Fetch { ref mut uid, .. }
+        | LazyCommandBody::Store { ref mut uid, .. }
+        | LazyCommandBody::Search { ref mut uid, .. } => *uid = uid_prefix.is_some(),
+        LazyCommandBody::Other(_) => unreachable!(),
+    }
+
+    Ok((remaining, LazyCommand { tag, body }))
+}
+
+/// `"FETCH" SP sequence-set SP ("ALL" / "FULL" / "FAST" / fetch-att / "(" fetch-att *(SP fetch-att) ")")`
+fn lazy_fetch(input: &[u8]) -> IMAPResult<&[u8], LazyCommandBody> {
+    let mut parser = tuple((
+        tag_no_case(b"FETCH"),
+        sp,
+        lazy_sequence_set,
+        sp,
+        alt((
+            value(
+                LazyMacroOrMessageDataItemNames::Macro(Macro::All),
+                tag_no_case(b"ALL"),
+            ),
+            value(
+                LazyMacroOrMessageDataItemNames::Macro(Macro::Fast),
+                tag_no_case(b"FAST"),
+            ),
+            value(
+                LazyMacroOrMessageDataItemNames::Macro(Macro::Full),
+                tag_no_case(b"FULL"),
+            ),
+            map(
+                alt((
+                    lazy_single(fetch_att),
+                    delimited(
+                        tag(b"("),
+                        alt((
+                            lazy_list(fetch_att, b' ', max_list_length, Limit::ListLength),
+                            lazy_empty(fetch_att),
+                        )),
+                        tag(b")"),
+                    ),
+                )),
+                LazyMacroOrMessageDataItemNames::MessageDataItemNames,
+            ),
+        )),
+    ));
+
+    let (remaining, (_, _, sequence_set, _, macro_or_item_names)) = parser(input)?;
+
+    Ok((
+        remaining,
+        LazyCommandBody::Fetch {
+            sequence_set,
+            macro_or_item_names,
+            uid: false,
+        },
+    ))
+}
+
+/// `"STORE" SP sequence-set SP ["+" / "-"] "FLAGS" [".SILENT"] SP (flag-list / (flag *(SP flag)))`
+fn lazy_store(input: &[u8]) -> IMAPResult<&[u8], LazyCommandBody> {
+    let mut parser = tuple((
+        tag_no_case(b"STORE"),
+        sp,
+        lazy_sequence_set,
+        sp,
+        store_type,
+        tag_no_case(b"FLAGS"),
+        store_response,
+        sp,
+        alt((
+            delimited(
+                tag(b"("),
+                alt((
+                    lazy_list(flag, b' ', max_list_length, Limit::ListLength),
+                    lazy_empty(flag),
+                )),
+                tag(b")"),
+            ),
+            lazy_list(flag, b' ', max_list_length, Limit::ListLength),
+        )),
+    ));
+
+    let (remaining, (_, _, sequence_set, _, kind, _, response, _, flags)) = parser(input)?;
+
+    Ok((
+        remaining,
+        LazyCommandBody::Store {
+            sequence_set,
+            kind,
+            response,
+            flags,
+            uid: false,
+        },
+    ))
+}
+
+/// `"SEARCH" [SP "CHARSET" SP charset] 1*(SP search-key)`
+fn lazy_search(input: &[u8]) -> IMAPResult<&[u8], LazyCommandBody> {
+    let mut parser = tuple((
+        tag_no_case(b"SEARCH"),
+        opt(preceded(tuple((sp, tag_no_case(b"CHARSET"), sp)), charset)),
+        sp,
+        lazy_list(search_key_item, b' ', max_list_length, Limit::ListLength),
+    ));
+
+    let (remaining, (_, charset, _, criteria)) = parser(input)?;
+
+    Ok((
+        remaining,
+        LazyCommandBody::Search {
+            charset,
+            criteria,
+            uid: false,
+        },
+    ))
+}
+
+fn lazy_sequence_set(input: &[u8]) -> IMAPResult<&[u8], LazyList<Sequence>> {
+    lazy_list(
+        sequence,
+        b',',
+        |limits| limits.max_sequence_set_length,
+        Limit::SequenceSetLength,
+    )(input)
+}
+
+fn search_key_item(input: &[u8]) -> IMAPResult<&[u8], SearchKey> {
+    search_key(limits().max_search_key_nesting)(input)
+}
+
+fn max_list_length(limits: &DecodeLimits) -> usize {
+    limits.max_list_length
+}
+
+/// `item *(separator item)`
+///
+/// Items are only counted, i.e., not collected.
+fn lazy_list<'a, T>(
+    item: Item<'a, T>,
+    separator: u8,
+    max: impl Fn(&DecodeLimits) -> usize,
+    limit: Limit,
+) -> impl FnMut(&'a [u8]) -> IMAPResult<&'a [u8], LazyList<'a, T>> {
+    move |input: &'a [u8]| {
+        let (remaining, (_, count)) = tuple((
+            item,
+            many0_count(preceded(char(char::from(separator)), item)),
+        ))(input)?;
+
+        let length = count + 1;
+        if length > max(&limits()) {
+            return Err(limit_exceeded(input, limit));
+        }
+
+        Ok((remaining, LazyList::new(input, length, item)))
+    }
+}
+
+/// `item`
+fn lazy_single<'a, T>(
+    item: Item<'a, T>,
+) -> impl FnMut(&'a [u8]) -> IMAPResult<&'a [u8], LazyList<'a, T>> {
+    move |input: &'a [u8]| {
+        let (remaining, _) = item(input)?;
+
+        Ok((remaining, LazyList::new(input, 1, item)))
+    }
+}
+
+/// An empty list.
+fn lazy_empty<'a, T>(
+    item: Item<'a, T>,
+) -> impl FnMut(&'a [u8]) -> IMAPResult<&'a [u8], LazyList<'a, T>> {
+    move |input: &'a [u8]| Ok((input, LazyList::new(input, 0, item)))
+}
+
+#[cfg(test)]
+mod tests {
+    use imap_types::{fetch::MessageDataItemName, flag::Flag};
+
+    use super::*;
+    use crate::decode::DecodeLimits;
+
+    #[test]
+    fn test_decode_lazy_matches_decode() {
+        let tests: &[&[u8]] = &[
+            b"A FETCH 1:* ALL\r\n",
+            b"A fetch 1 uid\r\n",
+            b"A UID FETCH 1:3,7,9:* (UID FLAGS BODY.PEEK[HEADER.FIELDS (From Subject)])\r\n",
+            b"A FETCH 1 ()\r\n",
+            b"A STORE 1:2 +FLAGS.SILENT (\\Seen \\Deleted $Junk)\r\n",
+            b"A uid store 5 -flags \\Flagged\r\n",
+            b"A STORE 1 FLAGS ()\r\n",
+            b"A SEARCH ALL\r\n",
+            b"A UID SEARCH CHARSET UTF-8 UNSEEN FROM \"alice\" OR SEEN (DELETED)\r\n",
+        ];
+
+        let codec = CommandCodec::new();
+
+        for test in tests {
+            let (remaining, lazy) = codec.decode_lazy(test).unwrap();
+            assert!(remaining.is_empty());
+            assert!(!matches!(lazy.body, LazyCommandBody::Other(_)));
+
+            let (_, expected) = codec.decode(test).unwrap();
+            assert_eq!(lazy.into_command(), expected);
+        }
+    }
+
+    #[test]
+    fn test_decode_lazy_iterate() {
+        let (remaining, command) = CommandCodec::new()
+            .decode_lazy(b"A UID STORE 1,2:4 +FLAGS (\\Seen foo)\r\nB NOOP\r\n")
+            .unwrap();
+        assert_eq!(remaining, b"B NOOP\r\n");
+
+        match command.body {
+            LazyCommandBody::Store {
+                sequence_set,
+                kind,
+                response,
+                flags,
+                uid,
+            } => {
+                assert_eq!(sequence_set.len(), 2);
+                assert_eq!(
+                    sequence_set.collect::<Vec<_>>(),
+                    vec![
+                        Sequence::try_from(1).unwrap(),
+                        Sequence::try_from("2:4").unwrap()
+                    ]
+                );
+                assert_eq!(kind, StoreType::Add);
+                assert_eq!(response, StoreResponse::Answer);
+                assert_eq!(
+                    flags.collect::<Vec<_>>(),
+                    vec![Flag::Seen, Flag::try_from("foo").unwrap()]
+                );
+                assert!(uid);
+            }
+            _ => unreachable!(),
+        }
+
+        let (_, command) = CommandCodec::new()
+            .decode_lazy(b"A FETCH 1 (UID ENVELOPE)\r\n")
+            .unwrap();
+
+        match command.body {
+            LazyCommandBody::Fetch {
+                macro_or_item_names: LazyMacroOrMessageDataItemNames::MessageDataItemNames(names),
+                ..
+            } => {
+                // Lists can be iterated more than once.
+                assert_eq!(names.clone().count(), 2);
+                assert_eq!(
+                    names.collect::<Vec<_>>(),
+                    vec![MessageDataItemName::Uid, MessageDataItemName::Envelope]
+                );
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_decode_lazy_fallback() {
+        let codec = CommandCodec::new();
+
+        // Commands that aren't decoded lazily.
+        for test in [
+            b"A NOOP\r\n".as_ref(),
+            b"A FETCH 1 (UID) (PARTIAL 1:10)\r\n",
+            b"A STORE 1 X-GM-LABELS (foo)\r\n",
+            b"A STORE 1 ANNOTATION (\"/comment\" (\"value.priv\" \"x\"))\r\n",
+            b"A SEARCH RETURN (MIN) ALL\r\n",
+            b"A UID COPY 1:* INBOX\r\n",
+        ] {
+            let (_, lazy) = codec.decode_lazy(test).unwrap();
+            let (_, expected) = codec.decode(test).unwrap();
+            assert!(matches!(lazy.body, LazyCommandBody::Other(_)));
+            assert_eq!(lazy.into_command(), expected);
+        }
+
+        // Errors are the same as with `decode`.
+        assert!(matches!(
+            codec.decode_lazy(b"A FETCH 1:"),
+            Err(CommandDecodeError::Incomplete)
+        ));
+        assert!(matches!(
+            codec.decode_lazy(b"A SEARCH SUBJECT {3}\r\n"),
+            Err(CommandDecodeError::LiteralFound { length: 3, .. })
+        ));
+        assert!(matches!(
+            codec.decode_lazy(b"A STORE 1 FLAGS (\\Seen"),
+            Err(CommandDecodeError::Incomplete)
+        ));
+        assert!(matches!(
+            codec.decode_lazy(b"A FETCH 0 ALL\r\n"),
+            Err(CommandDecodeError::Failed { .. })
+        ));
+    }
+
+    #[test]
+    fn test_decode_lazy_limits() {
+        let codec = CommandCodec::new().with_limits(DecodeLimits {
+            max_sequence_set_length: 2,
+            ..DecodeLimits::default()
+        });
+
+        assert!(codec.decode_lazy(b"A FETCH 1,2 ALL\r\n").is_ok());
+        assert!(matches!(
+            codec.decode_lazy(b"A FETCH 1,2,3 ALL\r\n"),
+            Err(CommandDecodeError::Failed { .. })
+        ));
+
+        let codec = CommandCodec::new().with_limits(DecodeLimits {
+            max_atom_length: 3,
+            ..DecodeLimits::default()
+        });
+        let (_, command) = codec.decode_lazy(b"A STORE 1 FLAGS (foo)\r\n").unwrap();
+        assert!(matches!(command.body, LazyCommandBody::Store { .. }));
+        assert!(codec.decode_lazy(b"A STORE 1 FLAGS (fooo)\r\n").is_err());
+    }
+}
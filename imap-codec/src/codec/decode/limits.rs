@@ -0,0 +1,385 @@
+use std::cell::Cell;
+
+use nom::Parser;
+
+use super::{IMAPErrorKind, IMAPParseError, IMAPResult};
+
+/// Limits that are enforced during decoding.
+///
+/// Use limits to protect a server (or client) from excessive resource usage, e.g., when it is
+/// exposed to the internet. Exceeding a limit makes decoding fail with
+/// [`DecodeFailureKind::LimitExceeded`](super::DecodeFailureKind::LimitExceeded).
+///
+/// The default limits only bound the nesting of recursive structures and are otherwise
+/// unlimited.
+///
+/// ```rust
+/// # use imap_codec::{decode::DecodeLimits, CommandCodec};
+/// let mut limits = DecodeLimits::default();
+/// limits.max_literal_length = 64 * 1024 * 1024;
+/// limits.max_list_length = 1024;
+///
+/// let codec = CommandCodec::new().with_limits(limits);
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub struct DecodeLimits {
+    /// Maximum length of a literal.
+    ///
+    /// A literal is rejected when it is announced, i.e., before its data is received. This also
+    /// applies to the `StreamingDecoder` and the `LiteralSinkDecoder`.
+    pub max_literal_length: u32,
+    /// Maximum number of elements in a list, e.g., flags or message data items.
+    pub max_list_length: usize,
+    /// Maximum nesting of search keys, i.e., parenthesized search keys.
+    pub max_search_key_nesting: usize,
+    /// Maximum nesting of body structures (and body extensions).
+    pub max_body_structure_nesting: usize,
+    /// Maximum number of sequence numbers and ranges in a sequence set.
+    pub max_sequence_set_length: usize,
+    /// Maximum length of a tag.
+    pub max_tag_length: usize,
+    /// Maximum length of an atom, e.g., a keyword, or an unquoted mailbox name.
+    pub max_atom_length: usize,
+}
+
+impl Default for DecodeLimits {
+    fn default() -> Self {
+        Self {
+            max_literal_length: u32::MAX,
+            max_list_length: usize::MAX,
+            max_search_key_nesting: 9,
+            max_body_structure_nesting: 8,
+            max_sequence_set_length: usize::MAX,
+            max_tag_length: usize::MAX,
+            max_atom_length: usize::MAX,
+        }
+    }
+}
+
+/// A limit of [`DecodeLimits`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum Limit {
+    /// See [`DecodeLimits::max_literal_length`].
+    LiteralLength,
+    /// See [`DecodeLimits::max_list_length`].
+    ListLength,
+    /// See [`DecodeLimits::max_search_key_nesting`].
+    SearchKeyNesting,
+    /// See [`DecodeLimits::max_body_structure_nesting`].
+    BodyStructureNesting,
+    /// See [`DecodeLimits::max_sequence_set_length`].
+    SequenceSetLength,
+    /// See [`DecodeLimits::max_tag_length`].
+    TagLength,
+    /// See [`DecodeLimits::max_atom_length`].
+    AtomLength,
+}
+
+impl Limit {
+    pub(crate) fn describe(&self) -> &'static str {
+        match self {
+            Self::LiteralLength => "literal length",
+            Self::ListLength => "list length",
+            Self::SearchKeyNesting => "search key nesting",
+            Self::BodyStructureNesting => "body structure nesting",
+            Self::SequenceSetLength => "sequence set length",
+            Self::TagLength => "tag length",
+            Self::AtomLength => "atom length",
+        }
+    }
+}
+
+thread_local! {
+    /// Limits of the decoder that is currently running on this thread.
+    ///
+    /// Note: The parsers are plain functions. Thus, we make the limits of a codec available to
+    /// them for the duration of a `decode` call (see `with_limits`).
+    static LIMITS: Cell<DecodeLimits> = Cell::new(DecodeLimits::default());
+}
+
+/// Run `f` with `limits` in place.
+pub(crate) fn with_limits<T>(limits: DecodeLimits, f: impl FnOnce() -> T) -> T {
+    struct Restore(DecodeLimits);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            LIMITS.with(|cell| cell.set(self.0));
+        }
+    }
+
+    let _restore = Restore(LIMITS.with(|cell| cell.replace(limits)));
+
+    f()
+}
+
+/// Return the limits of the decoder that is currently running.
+pub(crate) fn limits() -> DecodeLimits {
+    LIMITS.with(Cell::get)
+}
+
+pub(crate) fn limit_exceeded(input: &[u8], limit: Limit) -> nom::Err<IMAPParseError<&[u8]>> {
+    nom::Err::Failure(IMAPParseError {
+        input,
+        context: Vec::new(),
+        kind: IMAPErrorKind::LimitExceeded(limit),
+    })
+}
+
+/// Fail when `input` starts with more than `max` bytes that satisfy `predicate`.
+///
+/// Note: This also triggers when the input is incomplete, so that a peer can't make us buffer
+/// an unbounded amount of data.
+pub(crate) fn check_length(
+    input: &[u8],
+    predicate: impl Fn(u8) -> bool,
+    max: usize,
+    limit: Limit,
+) -> Result<(), nom::Err<IMAPParseError<&[u8]>>> {
+    if input.iter().take_while(|byte| predicate(**byte)).count() > max {
+        return Err(limit_exceeded(input, limit));
+    }
+
+    Ok(())
+}
+
+// ----- Limited versions of `nom::multi` -----------------------------------------------------------
+//
+// These parsers behave like their counterparts in `nom::multi` but fail with
+// `LimitExceeded(Limit::ListLength)` when a list has more than `max_list_length` elements.
+
+/// Count the elements parsed by `f` and fail when there are more than `max`.
+fn counted<'a, O, F>(
+    mut f: F,
+    max: usize,
+    limit: Limit,
+) -> impl FnMut(&'a [u8]) -> IMAPResult<&'a [u8], O>
+where
+    F: Parser<&'a [u8], O, IMAPParseError<'a, &'a [u8]>>,
+{
+    let mut count = 0usize;
+
+    move |input: &'a [u8]| {
+        let (remaining, output) = f.parse(input)?;
+
+        count += 1;
+        if count > max {
+            return Err(limit_exceeded(input, limit));
+        }
+
+        Ok((remaining, output))
+    }
+}
+
+pub(crate) fn many0<'a, O, F>(mut f: F) -> impl FnMut(&'a [u8]) -> IMAPResult<&'a [u8], Vec<O>>
+where
+    F: Parser<&'a [u8], O, IMAPParseError<'a, &'a [u8]>>,
+{
+    move |input: &'a [u8]| {
+        nom::multi::many0(counted(
+            |input| f.parse(input),
+            limits().max_list_length,
+            Limit::ListLength,
+        ))(input)
+    }
+}
+
+pub(crate) fn many1<'a, O, F>(mut f: F) -> impl FnMut(&'a [u8]) -> IMAPResult<&'a [u8], Vec<O>>
+where
+    F: Parser<&'a [u8], O, IMAPParseError<'a, &'a [u8]>>,
+{
+    move |input: &'a [u8]| {
+        nom::multi::many1(counted(
+            |input| f.parse(input),
+            limits().max_list_length,
+            Limit::ListLength,
+        ))(input)
+    }
+}
+
+pub(crate) fn separated_list0<'a, O, O2, F, G>(
+    mut sep: G,
+    mut f: F,
+) -> impl FnMut(&'a [u8]) -> IMAPResult<&'a [u8], Vec<O>>
+where
+    F: Parser<&'a [u8], O, IMAPParseError<'a, &'a [u8]>>,
+    G: Parser<&'a [u8], O2, IMAPParseError<'a, &'a [u8]>>,
+{
+    move |input: &'a [u8]| {
+        nom::multi::separated_list0(
+            |input| sep.parse(input),
+            counted(
+                |input| f.parse(input),
+                limits().max_list_length,
+                Limit::ListLength,
+            ),
+        )(input)
+    }
+}
+
+pub(crate) fn separated_list1<'a, O, O2, F, G>(
+    sep: G,
+    f: F,
+) -> impl FnMut(&'a [u8]) -> IMAPResult<&'a [u8], Vec<O>>
+where
+    F: Parser<&'a [u8], O, IMAPParseError<'a, &'a [u8]>>,
+    G: Parser<&'a [u8], O2, IMAPParseError<'a, &'a [u8]>>,
+{
+    separated_list1_limited(sep, f, |limits| limits.max_list_length, Limit::ListLength)
+}
+
+/// Like [`separated_list1`] but with a custom limit.
+pub(crate) fn separated_list1_limited<'a, O, O2, F, G>(
+    mut sep: G,
+    mut f: F,
+    max: fn(&DecodeLimits) -> usize,
+    limit: Limit,
+) -> impl FnMut(&'a [u8]) -> IMAPResult<&'a [u8], Vec<O>>
+where
+    F: Parser<&'a [u8], O, IMAPParseError<'a, &'a [u8]>>,
+    G: Parser<&'a [u8], O2, IMAPParseError<'a, &'a [u8]>>,
+{
+    move |input: &'a [u8]| {
+        nom::multi::separated_list1(
+            |input| sep.parse(input),
+            counted(|input| f.parse(input), max(&limits()), limit),
+        )(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use imap_types::core::Tag;
+
+    use super::*;
+    use crate::{
+        decode::{CommandDecodeError, DecodeFailureKind, Decoder, ResponseDecodeError},
+        CommandCodec, ResponseCodec,
+    };
+
+    fn command_limit(limits: DecodeLimits, input: &[u8]) -> Option<Limit> {
+        match CommandCodec::new().with_limits(limits).decode(input) {
+            Err(CommandDecodeError::Failed { failure, .. }) => match failure.kind() {
+                DecodeFailureKind::LimitExceeded(limit) => Some(limit),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_command_limits() {
+        let limits = DecodeLimits {
+            max_literal_length: 10,
+            max_list_length: 2,
+            max_search_key_nesting: 2,
+            max_body_structure_nesting: 8,
+            max_sequence_set_length: 2,
+            max_tag_length: 4,
+            max_atom_length: 4,
+        };
+
+        let tests = [
+            (b"A LOGIN {11}\r\n".as_ref(), Some(Limit::LiteralLength)),
+            (b"A LOGIN {10}\r\n".as_ref(), None),
+            (
+                b"A STORE 1 +FLAGS (\\Seen \\Seen \\Seen)\r\n",
+                Some(Limit::ListLength),
+            ),
+            (b"A STORE 1 +FLAGS (\\Seen \\Seen)\r\n", None),
+            (b"A SEARCH ((ALL))\r\n", Some(Limit::SearchKeyNesting)),
+            (b"A SEARCH (ALL)\r\n", None),
+            (b"A FETCH 1,2:3,4 FAST\r\n", Some(Limit::SequenceSetLength)),
+            (b"A FETCH 1,2:3 FAST\r\n", None),
+            // Note: The limit triggers even when the tag is not complete yet.
+            (b"ABCDE", Some(Limit::TagLength)),
+            (b"ABCD NOOP\r\n", None),
+            (b"A SELECT Drafts\r\n", Some(Limit::AtomLength)),
+            (b"A SELECT Sent\r\n", None),
+        ];
+
+        for (test, expected) in tests {
+            assert_eq!(command_limit(limits, test), expected, "{test:?}");
+        }
+
+        // The limits only apply to the codec they were configured for.
+        assert!(CommandCodec::new().decode(b"A SELECT Drafts\r\n").is_ok());
+    }
+
+    #[test]
+    fn test_command_literal_limit_resync() {
+        let limits = DecodeLimits {
+            max_literal_length: 1024,
+            ..DecodeLimits::default()
+        };
+
+        // The rejected literal is not skipped as the client won't send it.
+        match CommandCodec::new()
+            .with_limits(limits)
+            .decode(b"A APPEND INBOX {4096}\r\nB NOOP\r\n")
+        {
+            Err(CommandDecodeError::Failed {
+                tag,
+                line_length,
+                failure,
+            }) => {
+                assert_eq!(tag, Some(Tag::try_from("A").unwrap()));
+                assert_eq!(line_length, Some(23));
+                assert_eq!(
+                    failure.to_string(),
+                    "literal length limit exceeded at byte 15 (in command > command body > literal)"
+                );
+            }
+            got => panic!("Expected `Failed`, got {got:?}"),
+        }
+
+        // The data of a rejected non-synchronizing literal is sent anyway and must be skipped.
+        let limits = DecodeLimits {
+            max_literal_length: 4,
+            ..DecodeLimits::default()
+        };
+
+        let tests = [
+            (
+                b"A APPEND INBOX {10+}\r\n0123456789\r\nB NOOP\r\n".as_ref(),
+                34,
+            ),
+            // Not complete yet, i.e., `line_length` exceeds the input.
+            (b"A APPEND INBOX {10+}\r\n0123".as_ref(), 32),
+        ];
+
+        for (test, expected) in tests {
+            match CommandCodec::new().with_limits(limits).decode(test) {
+                Err(CommandDecodeError::Failed {
+                    tag, line_length, ..
+                }) => {
+                    assert_eq!(tag, Some(Tag::try_from("A").unwrap()));
+                    assert_eq!(line_length, Some(expected));
+                }
+                got => panic!("Expected `Failed`, got {got:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_response_body_structure_limit() {
+        let input =
+            b"* 1 FETCH (BODYSTRUCTURE ((\"TEXT\" \"PLAIN\" NIL NIL NIL \"7BIT\" 1 1) \"MIXED\"))\r\n";
+
+        assert!(ResponseCodec::new().decode(input).is_ok());
+
+        let limits = DecodeLimits {
+            max_body_structure_nesting: 1,
+            ..DecodeLimits::default()
+        };
+
+        match ResponseCodec::new().with_limits(limits).decode(input) {
+            Err(ResponseDecodeError::Failed(failure)) => assert_eq!(
+                failure.kind(),
+                DecodeFailureKind::LimitExceeded(Limit::BodyStructureNesting)
+            ),
+            got => panic!("Expected `Failed`, got {got:?}"),
+        }
+    }
+}
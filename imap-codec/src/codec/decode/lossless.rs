@@ -0,0 +1,176 @@
+use std::ops::Deref;
+
+/// A decoded message that remembers its source.
+///
+/// Decoding normalizes a message, e.g., `nOOP` is decoded to [`CommandBody::Noop`], and the
+/// encoder chooses its own representation, e.g., a quoted string instead of a literal. A
+/// `Lossless` message keeps a reference to the exact bytes it was decoded from, so that
+/// [`Encoder::encode_lossless`] can re-emit them byte-for-byte as long as the message wasn't
+/// modified.
+///
+/// Create one through [`Decoder::decode_lossless`].
+///
+/// # Modification
+///
+/// Read access is provided through [`Deref`]. Use [`Lossless::message_mut`] to modify the
+/// message. This marks the message as modified (even when it's changed back later), i.e., it will
+/// be encoded normally from then on.
+///
+/// ```rust
+/// # use imap_codec::{
+/// #     decode::Decoder,
+/// #     encode::Encoder,
+/// #     imap_types::{command::CommandBody, core::Tag},
+/// #     CommandCodec,
+/// # };
+/// let codec = CommandCodec::new();
+///
+/// let (_, mut command) = codec.decode_lossless(b"a1 nOOP\r\n").unwrap();
+/// assert_eq!(command.body, CommandBody::Noop);
+/// assert_eq!(codec.encode_lossless(&command).dump(), b"a1 nOOP\r\n");
+///
+/// command.message_mut().tag = Tag::try_from("a2").unwrap();
+/// assert_eq!(codec.encode_lossless(&command).dump(), b"a2 NOOP\r\n");
+/// ```
+///
+/// [`CommandBody::Noop`]: imap_types::command::CommandBody::Noop
+/// [`Decoder::decode_lossless`]: super::Decoder::decode_lossless
+/// [`Encoder::encode_lossless`]: crate::encode::Encoder::encode_lossless
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Lossless<'a, M> {
+    message: M,
+    source: &'a [u8],
+    modified: bool,
+}
+
+impl<'a, M> Lossless<'a, M> {
+    pub(crate) fn new(message: M, source: &'a [u8]) -> Self {
+        Self {
+            message,
+            source,
+            modified: false,
+        }
+    }
+
+    /// Return the decoded message.
+    pub fn message(&self) -> &M {
+        &self.message
+    }
+
+    /// Return the decoded message for modification and mark it as modified.
+    pub fn message_mut(&mut self) -> &mut M {
+        self.modified = true;
+        &mut self.message
+    }
+
+    /// Return the bytes the message was decoded from (including the line ending).
+    pub fn source(&self) -> &'a [u8] {
+        self.source
+    }
+
+    /// Whether the message was (potentially) modified since decoding.
+    pub fn is_modified(&self) -> bool {
+        self.modified
+    }
+
+    /// Return the decoded message and drop the source.
+    pub fn into_message(self) -> M {
+        self.message
+    }
+}
+
+impl<'a, M> Deref for Lossless<'a, M> {
+    type Target = M;
+
+    fn deref(&self) -> &Self::Target {
+        &self.message
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use imap_types::{
+        command::{Command, CommandBody},
+        core::{LiteralMode, Tag},
+        response::{Data, Response},
+    };
+
+    use crate::{
+        decode::Decoder,
+        encode::{Encoder, Fragment},
+        CommandCodec, ResponseCodec,
+    };
+
+    #[test]
+    fn test_lossless_command() {
+        let codec = CommandCodec::new();
+        let input = b"a1 login {5}\r\nalice \"pass\"\r\nB NOOP\r\n".as_ref();
+
+        let (remaining, command) = codec.decode_lossless(input).unwrap();
+        assert_eq!(remaining, b"B NOOP\r\n");
+        assert_eq!(command.source(), b"a1 login {5}\r\nalice \"pass\"\r\n");
+        assert!(matches!(command.body, CommandBody::Login { .. }));
+
+        // The literal is still announced as such.
+        assert_eq!(
+            codec.encode_lossless(&command).collect::<Vec<_>>(),
+            vec![
+                Fragment::Line {
+                    data: b"a1 login {5}\r\n".to_vec()
+                },
+                Fragment::Literal {
+                    data: b"alice".to_vec(),
+                    mode: LiteralMode::Sync
+                },
+                Fragment::Line {
+                    data: b" \"pass\"\r\n".to_vec()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lossless_text_with_literal_announcement() {
+        let codec = ResponseCodec::new();
+
+        for input in [
+            b"* OK hello {5}\r\n".as_ref(),
+            b"* OK hello {0}\r\n".as_ref(),
+        ] {
+            let (remaining, response) = codec.decode_lossless(input).unwrap();
+            assert!(remaining.is_empty());
+
+            // `{n}` is part of the text, not a literal announcement.
+            assert_eq!(
+                codec.encode_lossless(&response).collect::<Vec<_>>(),
+                vec![Fragment::Line {
+                    data: input.to_vec()
+                }]
+            );
+        }
+    }
+
+    #[test]
+    fn test_lossless_modified() {
+        let codec = ResponseCodec::new();
+
+        let (_, mut response) = codec.decode_lossless(b"* 6 exists\r\n").unwrap();
+        assert!(!response.is_modified());
+        assert_eq!(codec.encode_lossless(&response).dump(), b"* 6 exists\r\n");
+
+        *response.message_mut() = Response::Data(Data::Exists(7));
+        assert!(response.is_modified());
+        assert_eq!(codec.encode_lossless(&response).dump(), b"* 7 EXISTS\r\n");
+
+        let (_, mut command) = CommandCodec::new().decode_lossless(b"A noop\r\n").unwrap();
+        command.message_mut();
+        assert_eq!(
+            CommandCodec::new().encode_lossless(&command).dump(),
+            b"A NOOP\r\n"
+        );
+        assert_eq!(
+            command.into_message(),
+            Command::new(Tag::try_from("A").unwrap(), CommandBody::Noop).unwrap()
+        );
+    }
+}
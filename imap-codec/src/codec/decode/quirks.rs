@@ -0,0 +1,157 @@
+use std::cell::Cell;
+
+use abnf_core::streaming::{crlf as crlf_strict, crlf_relaxed};
+use nom::{bytes::streaming::take_while, combinator::recognize};
+
+use super::IMAPResult;
+
+/// Quirks that are tolerated during decoding.
+///
+/// Quirks are used to cope with existing interoperability issues (see [crate#quirks]). Quirks
+/// that have a corresponding `quirk_*` feature default to whether the feature is enabled. Thus,
+/// the features still work as before, but a single binary can be strict toward some peers and
+/// lenient toward others. All other quirks are opt-in.
+///
+/// ```rust
+/// # use imap_codec::{decode::DecodeQuirks, ResponseCodec};
+/// let mut quirks = DecodeQuirks::default();
+/// quirks.crlf_relaxed = true;
+/// quirks.missing_text = true;
+///
+/// let codec = ResponseCodec::new().with_quirks(quirks);
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub struct DecodeQuirks {
+    /// Make `\r` in `\r\n` optional (feature `quirk_crlf_relaxed`).
+    pub crlf_relaxed: bool,
+    /// Rectify (invalid) numbers (feature `quirk_rectify_numbers`).
+    ///
+    /// Observed in Dovecot (`-1`).
+    pub rectify_numbers: bool,
+    /// Rectify a missing `text` element (feature `quirk_missing_text`).
+    ///
+    /// Observed in Gmail (`* OK [HIGHESTMODSEQ <n>]\r\n`).
+    pub missing_text: bool,
+    /// Accept unquoted strings (that may contain spaces) in the date, subject, in-reply-to, and
+    /// message-id fields of an `ENVELOPE`.
+    ///
+    /// Such a string ends before the next field, i.e., before `SP` followed by `"`, `(`, `{`, or
+    /// `NIL`, or before the closing `)` for the message-id.
+    pub unquoted_envelope_strings: bool,
+    /// Accept `NIL` where `BODYSTRUCTURE` requires a parenthesized list.
+    ///
+    /// This covers the envelope and the body of a `MESSAGE/RFC822` part and the bodies of a
+    /// multipart. An envelope is rectified to an envelope with all fields `NIL`, a body to an
+    /// empty `TEXT/PLAIN` body.
+    pub nil_body_structure_lists: bool,
+    /// Accept a leading day-of-week (in any case), e.g., `"Tue, 17-Oct-2023 ..."`, and `SP`
+    /// instead of `-` between day, month, and year in a `date-time`, e.g., `INTERNALDATE`.
+    pub relaxed_date_time: bool,
+    /// Accept trailing spaces before `CRLF`.
+    pub trailing_spaces: bool,
+    /// Accept 8-bit bytes in `text`, e.g., in the human-readable part of a status response.
+    ///
+    /// Every non-ASCII character (or invalid UTF-8 sequence) is rectified to `?`.
+    pub eight_bit_text: bool,
+}
+
+impl DecodeQuirks {
+    /// Tolerate no quirks, regardless of the enabled features.
+    pub fn strict() -> Self {
+        Self {
+            crlf_relaxed: false,
+            rectify_numbers: false,
+            missing_text: false,
+            unquoted_envelope_strings: false,
+            nil_body_structure_lists: false,
+            relaxed_date_time: false,
+            trailing_spaces: false,
+            eight_bit_text: false,
+        }
+    }
+
+    /// Tolerate all quirks, regardless of the enabled features.
+    pub fn lenient() -> Self {
+        Self {
+            crlf_relaxed: true,
+            rectify_numbers: true,
+            missing_text: true,
+            unquoted_envelope_strings: true,
+            nil_body_structure_lists: true,
+            relaxed_date_time: true,
+            trailing_spaces: true,
+            eight_bit_text: true,
+        }
+    }
+}
+
+// Note: Can't be derived because the defaults depend on the enabled features.
+#[allow(clippy::derivable_impls)]
+impl Default for DecodeQuirks {
+    fn default() -> Self {
+        Self {
+            crlf_relaxed: cfg!(feature = "quirk_crlf_relaxed"),
+            rectify_numbers: cfg!(feature = "quirk_rectify_numbers"),
+            missing_text: cfg!(feature = "quirk_missing_text"),
+            unquoted_envelope_strings: false,
+            nil_body_structure_lists: false,
+            relaxed_date_time: false,
+            trailing_spaces: false,
+            eight_bit_text: false,
+        }
+    }
+}
+
+thread_local! {
+    /// Quirks of the decoder that is currently running on this thread (see `LIMITS`).
+    static QUIRKS: Cell<DecodeQuirks> = Cell::new(DecodeQuirks::default());
+}
+
+/// Run `f` with `quirks` in place.
+pub(crate) fn with_quirks<T>(quirks: DecodeQuirks, f: impl FnOnce() -> T) -> T {
+    struct Restore(DecodeQuirks);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            QUIRKS.with(|cell| cell.set(self.0));
+        }
+    }
+
+    let _restore = Restore(QUIRKS.with(|cell| cell.replace(quirks)));
+
+    f()
+}
+
+/// Return the quirks of the decoder that is currently running.
+pub(crate) fn quirks() -> DecodeQuirks {
+    QUIRKS.with(Cell::get)
+}
+
+/// `CRLF = CR LF`
+///
+/// # Quirks
+///
+/// * `\r` is optional with [`DecodeQuirks::crlf_relaxed`].
+/// * Spaces are skipped before the line ending with [`DecodeQuirks::trailing_spaces`].
+pub(crate) fn crlf(input: &[u8]) -> IMAPResult<&[u8], &[u8]> {
+    let quirks = quirks();
+
+    let input = if quirks.trailing_spaces {
+        let (remaining, spaces) = take_while(|byte| byte == b' ')(input)?;
+
+        if !spaces.is_empty() {
+            log::warn!("Skipped trailing spaces before CRLF");
+        }
+
+        remaining
+    } else {
+        input
+    };
+
+    if quirks.crlf_relaxed {
+        recognize(crlf_relaxed)(input)
+    } else {
+        recognize(crlf_strict)(input)
+    }
+}
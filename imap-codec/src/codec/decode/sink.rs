@@ -0,0 +1,504 @@
+use std::{collections::VecDeque, io::Write};
+
+use imap_types::response::Response;
+
+use super::{
+    literal_announcement, with_limits, with_quirks, Decoder, IMAPErrorKind, IMAPParseError,
+    ResponseDecodeError,
+};
+use crate::{fetch::MESSAGE_CONTENT, response::response, ResponseCodec};
+
+/// Location of a literal that was written to a sink instead of being buffered.
+///
+/// The literal occupies the bytes `offset..offset + length` of all data written to the sink.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct LiteralHandle {
+    offset: u64,
+    length: u32,
+}
+
+impl LiteralHandle {
+    /// Position of the first byte of the literal in the sink.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Length of the literal.
+    pub fn length(&self) -> u32 {
+        self.length
+    }
+}
+
+/// Decoder that writes message content to a sink instead of buffering it in memory.
+///
+/// The literal content of the `BODY[<section>]<<origin>>`, `RFC822`, `RFC822.HEADER`, and
+/// `RFC822.TEXT` data items in `FETCH` responses is written to a user-supplied [`Write`] as soon
+/// as it is received. The rest of the response is parsed as usual. In the parsed response, the
+/// affected data items contain an empty literal, and [`LiteralSinkDecoder::decode`] returns a
+/// [`LiteralHandle`] for each of them (in the order of appearance).
+///
+/// Create one through [`ResponseCodec::stream_literals`].
+///
+/// ```rust
+/// # use imap_codec::{decode::ResponseDecodeError, ResponseCodec};
+/// let mut decoder = ResponseCodec::new().stream_literals(Vec::new());
+///
+/// decoder.feed(b"* 1 FETCH (UID 42 BODY[] {5}\r\nHel").unwrap();
+/// assert_eq!(decoder.decode().unwrap_err(), ResponseDecodeError::Incomplete);
+///
+/// decoder.feed(b"lo)\r\n").unwrap();
+/// let (response, literals) = decoder.decode().unwrap();
+/// # let _ = response;
+/// assert_eq!(literals[0].offset(), 0);
+/// assert_eq!(literals[0].length(), 5);
+///
+/// assert_eq!(decoder.sink(), b"Hello");
+/// ```
+#[derive(Debug)]
+pub struct LiteralSinkDecoder<W> {
+    codec: ResponseCodec,
+    sink: W,
+    /// Number of bytes written to the sink.
+    written: u64,
+    /// Received data (without diverted literals).
+    buffer: Vec<u8>,
+    /// Number of bytes at the beginning of `buffer` that belong to already returned responses.
+    consumed: usize,
+    /// Start of the current message in `buffer`.
+    message_start: usize,
+    /// Start of the current line in `buffer`.
+    line_start: usize,
+    /// End of each complete message in `buffer`.
+    message_ends: VecDeque<usize>,
+    state: State,
+    /// Diverted literals and the position of their (rewritten) announcement in `buffer`.
+    literals: VecDeque<(usize, LiteralHandle)>,
+}
+
+/// What the parser found at a literal announcement.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Announcement {
+    /// The content of a `BODY[<section>]<<origin>>`, `RFC822`, `RFC822.HEADER`, or
+    /// `RFC822.TEXT` data item.
+    MessageContent,
+    /// Any other literal.
+    Literal,
+    /// Not a literal, e.g., a `text` that ends with `{<n>}`, or a rejected literal.
+    None,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum State {
+    Line,
+    /// Copy the given number of bytes into the buffer.
+    Buffer(u32),
+    /// Write the given number of bytes into the sink.
+    Divert(u32),
+}
+
+impl ResponseCodec {
+    /// Create a [`LiteralSinkDecoder`] that writes message content to `sink`.
+    pub fn stream_literals<W: Write>(self, sink: W) -> LiteralSinkDecoder<W> {
+        LiteralSinkDecoder {
+            codec: self,
+            sink,
+            written: 0,
+            buffer: Vec::new(),
+            consumed: 0,
+            message_start: 0,
+            line_start: 0,
+            message_ends: VecDeque::new(),
+            state: State::Line,
+            literals: VecDeque::new(),
+        }
+    }
+}
+
+impl<W: Write> LiteralSinkDecoder<W> {
+    /// Feed received data into the decoder.
+    ///
+    /// Message content is written to the sink right away. Errors of the sink are returned as-is.
+    pub fn feed(&mut self, mut data: &[u8]) -> std::io::Result<()> {
+        while !data.is_empty() {
+            match self.state {
+                State::Line => match data.iter().position(|byte| *byte == b'\n') {
+                    Some(position) => {
+                        self.buffer.extend_from_slice(&data[..=position]);
+                        data = &data[position + 1..];
+
+                        self.finish_line();
+                    }
+                    None => {
+                        self.buffer.extend_from_slice(data);
+                        data = &[];
+                    }
+                },
+                State::Buffer(length) => {
+                    let count = data.len().min(length as usize);
+
+                    self.buffer.extend_from_slice(&data[..count]);
+                    data = &data[count..];
+
+                    // Note: `count` is at most `length`.
+                    self.state = match length - count as u32 {
+                        0 => State::Line,
+                        length => State::Buffer(length),
+                    };
+                }
+                State::Divert(length) => {
+                    let count = data.len().min(length as usize);
+
+                    self.sink.write_all(&data[..count])?;
+                    self.written += count as u64;
+                    data = &data[count..];
+
+                    // Note: `count` is at most `length`.
+                    self.state = match length - count as u32 {
+                        0 => State::Line,
+                        length => State::Divert(length),
+                    };
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Decode the next response.
+    ///
+    /// Returns the response and the handles of all literals that were written to the sink.
+    ///
+    /// See [`ResponseCodec`] for a description of the errors. After a `Failed` error, the
+    /// offending response is discarded (including the handles of its literals), and decoding
+    /// continues with the next response.
+    pub fn decode(&mut self) -> Result<(Response<'_>, Vec<LiteralHandle>), ResponseDecodeError> {
+        self.compact();
+
+        let end = match self.message_ends.pop_front() {
+            Some(end) => end,
+            None => return Err(ResponseDecodeError::Incomplete),
+        };
+
+        // Note: The data is removed on the next call (see `compact`).
+        self.consumed = end;
+
+        let mut literals = Vec::new();
+        while let Some((position, handle)) = self.literals.front() {
+            if *position >= end {
+                break;
+            }
+
+            literals.push(*handle);
+            self.literals.pop_front();
+        }
+
+        self.codec
+            .decode(&self.buffer[..end])
+            .map(|(_, response)| (response, literals))
+    }
+
+    /// Return the sink.
+    pub fn sink(&self) -> &W {
+        &self.sink
+    }
+
+    /// Return the sink mutably, e.g., to flush it.
+    pub fn sink_mut(&mut self) -> &mut W {
+        &mut self.sink
+    }
+
+    /// Return the sink and drop the decoder.
+    pub fn into_sink(self) -> W {
+        self.sink
+    }
+
+    /// A line was completed at the end of `buffer`.
+    fn finish_line(&mut self) {
+        let line = &self.buffer[self.line_start..];
+
+        let (length, announcement) = match literal_announcement(line) {
+            Some((length, _)) => (length, self.classify_announcement()),
+            None => (0, Announcement::None),
+        };
+
+        match announcement {
+            Announcement::MessageContent => {
+                // Rewrite the announcement to an empty literal ...
+                let open = self.line_start
+                    + line
+                        .iter()
+                        .rposition(|byte| *byte == b'{')
+                        .expect("announcement contains `{`");
+                self.buffer.truncate(open);
+                self.buffer.extend_from_slice(b"{0}\r\n");
+
+                // ... and remember where the content went.
+                self.literals.push_back((
+                    open,
+                    LiteralHandle {
+                        offset: self.written,
+                        length,
+                    },
+                ));
+
+                self.line_start = self.buffer.len();
+                if length > 0 {
+                    self.state = State::Divert(length);
+                }
+            }
+            Announcement::Literal => {
+                self.line_start = self.buffer.len() + length as usize;
+                if length > 0 {
+                    self.state = State::Buffer(length);
+                }
+            }
+            Announcement::None => {
+                self.line_start = self.buffer.len();
+                self.message_start = self.buffer.len();
+                self.message_ends.push_back(self.buffer.len());
+            }
+        }
+    }
+
+    /// Ask the parser what the literal announcement at the end of `buffer` belongs to.
+    ///
+    /// Note: A rejected literal, e.g., one that exceeds `DecodeLimits::max_literal_length`, ends
+    /// the message, so that `decode` reports the failure before the literal data is received.
+    fn classify_announcement(&self) -> Announcement {
+        let message = &self.buffer[self.message_start..];
+
+        let result = with_quirks(self.codec.quirks, || {
+            with_limits(self.codec.limits, || response(message))
+        });
+
+        match result {
+            Err(nom::Err::Failure(IMAPParseError {
+                kind: IMAPErrorKind::Literal { .. },
+                context,
+                ..
+            })) => {
+                if context.contains(&MESSAGE_CONTENT) {
+                    Announcement::MessageContent
+                } else {
+                    Announcement::Literal
+                }
+            }
+            Err(nom::Err::Incomplete(_)) => Announcement::Literal,
+            Ok(_) | Err(nom::Err::Error(_) | nom::Err::Failure(_)) => Announcement::None,
+        }
+    }
+
+    /// Remove the data of already returned responses from `buffer`.
+    fn compact(&mut self) {
+        if self.consumed == 0 {
+            return;
+        }
+
+        self.buffer.drain(..self.consumed);
+        self.message_start -= self.consumed;
+        self.line_start -= self.consumed;
+        for end in self.message_ends.iter_mut() {
+            *end -= self.consumed;
+        }
+        for (position, _) in self.literals.iter_mut() {
+            *position -= self.consumed;
+        }
+
+        self.consumed = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use imap_types::{
+        core::{IString, Literal, NString},
+        fetch::MessageDataItem,
+        response::{Code, Data, Status},
+    };
+
+    use super::*;
+    use crate::decode::{DecodeFailure, DecodeFailureKind, DecodeLimits, Limit};
+
+    #[test]
+    fn test_literal_sink_decoder() {
+        let mut decoder = ResponseCodec::new().stream_literals(Vec::new());
+
+        let trace = b"* 1 FETCH (BODY[HEADER.FIELDS (FROM)]<0> {6}\r\nFrom:\n BODY[] {5}\r\nHello)\r\n* 2 FETCH (ENVELOPE (NIL {3}\r\nabc NIL NIL NIL NIL NIL NIL NIL NIL) RFC822 {0}\r\n)\r\n* 3 EXISTS\r\n";
+
+        // Feed the trace byte by byte.
+        for byte in trace.iter() {
+            decoder.feed(&[*byte]).unwrap();
+        }
+
+        let (response, literals) = decoder.decode().unwrap();
+        match response {
+            Response::Data(Data::Fetch { items, .. }) => {
+                for item in items.as_ref() {
+                    assert!(matches!(
+                        item,
+                        MessageDataItem::BodyExt {
+                            data: NString(Some(IString::Literal(literal))),
+                            ..
+                        } if literal.as_ref().is_empty()
+                    ));
+                }
+            }
+            _ => panic!("Expected FETCH response, got {response:?}"),
+        }
+        assert_eq!(
+            literals,
+            vec![
+                LiteralHandle {
+                    offset: 0,
+                    length: 6
+                },
+                LiteralHandle {
+                    offset: 6,
+                    length: 5
+                }
+            ]
+        );
+
+        // Literals of other data items are buffered.
+        let (response, literals) = decoder.decode().unwrap();
+        match response {
+            Response::Data(Data::Fetch { items, .. }) => {
+                assert_eq!(
+                    items.as_ref()[1],
+                    MessageDataItem::Rfc822(NString(Some(IString::Literal(
+                        Literal::try_from("").unwrap()
+                    ))))
+                );
+            }
+            _ => panic!("Expected FETCH response, got {response:?}"),
+        }
+        assert_eq!(
+            literals,
+            vec![LiteralHandle {
+                offset: 11,
+                length: 0
+            }]
+        );
+
+        let (response, literals) = decoder.decode().unwrap();
+        assert_eq!(response, Response::Data(Data::Exists(3)));
+        assert!(literals.is_empty());
+
+        assert_eq!(
+            decoder.decode().unwrap_err(),
+            ResponseDecodeError::Incomplete
+        );
+        assert_eq!(decoder.into_sink(), b"From:\nHello");
+    }
+
+    #[test]
+    fn test_literal_sink_decoder_failed() {
+        let mut decoder = ResponseCodec::new().stream_literals(Vec::new());
+
+        decoder
+            .feed(b"* 1 FETCH (BODY[] {3}\r\nabc) xxx\r\n* 2 EXISTS\r\n")
+            .unwrap();
+        assert!(matches!(
+            decoder.decode(),
+            Err(ResponseDecodeError::Failed(_))
+        ));
+
+        // Only the offending response was discarded.
+        let (response, literals) = decoder.decode().unwrap();
+        assert_eq!(response, Response::Data(Data::Exists(2)));
+        assert!(literals.is_empty());
+
+        // The literal of a later response is still diverted.
+        decoder
+            .feed(b"* 1 FETCH (UID x)\r\n* 2 FETCH (BODY[] {11}\r\nabc")
+            .unwrap();
+        assert!(matches!(
+            decoder.decode(),
+            Err(ResponseDecodeError::Failed(_))
+        ));
+        assert_eq!(
+            decoder.decode().unwrap_err(),
+            ResponseDecodeError::Incomplete
+        );
+
+        decoder.feed(b"de\r\nfghi)\r\n* 3 EXISTS\r\n").unwrap();
+        let (response, literals) = decoder.decode().unwrap();
+        assert!(matches!(response, Response::Data(Data::Fetch { .. })));
+        assert_eq!(
+            literals,
+            vec![LiteralHandle {
+                offset: 3,
+                length: 11
+            }]
+        );
+
+        let (response, _) = decoder.decode().unwrap();
+        assert_eq!(response, Response::Data(Data::Exists(3)));
+        assert_eq!(
+            decoder.decode().unwrap_err(),
+            ResponseDecodeError::Incomplete
+        );
+        assert_eq!(decoder.into_sink(), b"abcabcde\r\nfghi");
+    }
+
+    #[test]
+    fn test_literal_sink_decoder_literal_limit() {
+        let limits = DecodeLimits {
+            max_literal_length: 1024,
+            ..DecodeLimits::default()
+        };
+
+        // The literal is rejected as soon as it is announced, i.e., neither written to the sink
+        // nor buffered.
+        for trace in [
+            b"* 1 FETCH (BODY[] {4000000000}\r\nxyz".as_ref(),
+            b"* 1 FETCH (ENVELOPE ({4000000000}\r\nxyz",
+        ] {
+            let mut decoder = ResponseCodec::new()
+                .with_limits(limits)
+                .stream_literals(Vec::new());
+
+            decoder.feed(trace).unwrap();
+            assert!(matches!(
+                decoder.decode(),
+                Err(ResponseDecodeError::Failed(DecodeFailure {
+                    kind: DecodeFailureKind::LimitExceeded(Limit::LiteralLength),
+                    ..
+                }))
+            ));
+            assert_eq!(decoder.into_sink(), b"");
+        }
+    }
+
+    #[test]
+    fn test_literal_sink_decoder_lookalike() {
+        let mut decoder = ResponseCodec::new().stream_literals(Vec::new());
+
+        // Not a literal, but a `text` that happens to end with `BODY[] {5}`.
+        decoder
+            .feed(b"* OK [ALERT] try FETCH 1 BODY[] {5}\r\n* 2 EXISTS\r\n")
+            .unwrap();
+
+        let (response, literals) = decoder.decode().unwrap();
+        assert_eq!(
+            response,
+            Response::Status(
+                Status::ok(None, Some(Code::Alert), "try FETCH 1 BODY[] {5}").unwrap()
+            )
+        );
+        assert!(literals.is_empty());
+
+        let (response, _) = decoder.decode().unwrap();
+        assert_eq!(response, Response::Data(Data::Exists(2)));
+
+        // A literal that isn't message content, but follows something that looks like it.
+        decoder
+            .feed(b"* 3 FETCH (X-GM-LABELS (BODY[] {3}\r\nabc))\r\n")
+            .unwrap();
+
+        let (response, literals) = decoder.decode().unwrap();
+        assert!(matches!(response, Response::Data(Data::Fetch { .. })));
+        assert!(literals.is_empty());
+        assert_eq!(decoder.into_sink(), b"");
+    }
+}
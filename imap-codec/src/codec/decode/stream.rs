@@ -0,0 +1,370 @@
+use imap_types::{command::Command, response::Response};
+
+use super::{literal_announcement, CommandDecodeError, Decoder, ResponseDecodeError};
+use crate::{CommandCodec, ResponseCodec};
+
+/// Stateful decoder that remembers its progress across calls.
+///
+/// A [`Decoder`] parses its input from the start on every call. When a message is received in
+/// many chunks, e.g., a `FETCH` response with a large `BODY[]` literal, this makes decoding
+/// quadratic. A `StreamingDecoder` only examines the bytes that were appended since the last call
+/// to find the end of the current message (skipping over literals) and only invokes the parser
+/// when the message is complete or a literal was announced.
+///
+/// Create one through [`CommandCodec::stream`] or [`ResponseCodec::stream`].
+///
+/// # Usage
+///
+/// Every call to `decode` must pass the same input as the previous call plus (optionally) newly
+/// received bytes. When a message (or an error other than `Incomplete`/`LiteralFound`) is
+/// returned, the decoder starts over, i.e., the next call must pass the remaining input.
+/// Use [`StreamingDecoder::reset`] when the input is modified in any other way.
+///
+/// ```rust
+/// # use imap_codec::{decode::ResponseDecodeError, ResponseCodec};
+/// let mut decoder = ResponseCodec::new().stream();
+/// let mut buffer = Vec::new();
+///
+/// for chunk in [b"* 1 FETCH (RFC822 {5}\r\n".as_ref(), b"Hello", b")\r\n* 2 EXISTS\r\n"] {
+///     buffer.extend_from_slice(chunk);
+///
+///     match decoder.decode(&buffer) {
+///         Ok((remaining, response)) => {
+///             // Do something with the response ...
+///             # assert_eq!(remaining, b"* 2 EXISTS\r\n");
+///             # let _ = response;
+///
+///             // ... and proceed with the remaining data.
+///             buffer = remaining.to_vec();
+///         }
+///         Err(ResponseDecodeError::Incomplete | ResponseDecodeError::LiteralFound { .. }) => {
+///             // Read more data.
+///         }
+///         Err(ResponseDecodeError::Failed(failure)) => panic!("{failure}"),
+///     }
+/// }
+/// ```
+#[derive(Debug)]
+pub struct StreamingDecoder<C> {
+    codec: C,
+    framing: Framing,
+}
+
+impl<C> StreamingDecoder<C> {
+    /// Create a streaming decoder for the given codec.
+    pub fn new(codec: C) -> Self {
+        Self {
+            codec,
+            framing: Framing::default(),
+        }
+    }
+
+    /// Forget the progress made so far.
+    pub fn reset(&mut self) {
+        self.framing = Framing::default();
+    }
+
+    /// Return the underlying codec.
+    pub fn codec(&self) -> &C {
+        &self.codec
+    }
+}
+
+impl CommandCodec {
+    /// Create a [`StreamingDecoder`] for commands.
+    pub fn stream(self) -> StreamingDecoder<Self> {
+        StreamingDecoder::new(self)
+    }
+}
+
+impl ResponseCodec {
+    /// Create a [`StreamingDecoder`] for responses.
+    pub fn stream(self) -> StreamingDecoder<Self> {
+        StreamingDecoder::new(self)
+    }
+}
+
+impl StreamingDecoder<CommandCodec> {
+    /// Decode the first command in `input`.
+    ///
+    /// See [`CommandCodec`] for a description of the results.
+    pub fn decode<'a>(
+        &mut self,
+        input: &'a [u8],
+    ) -> Result<(&'a [u8], Command<'a>), CommandDecodeError<'a>> {
+        if !self.framing.advance(input) {
+            return Err(CommandDecodeError::Incomplete);
+        }
+
+        match self.codec.decode(input) {
+            Err(error @ CommandDecodeError::Incomplete)
+            | Err(error @ CommandDecodeError::LiteralFound { .. }) => Err(error),
+            result => {
+                self.reset();
+                result
+            }
+        }
+    }
+}
+
+impl StreamingDecoder<ResponseCodec> {
+    /// Decode the first response in `input`.
+    ///
+    /// See [`ResponseCodec`] for a description of the results.
+    pub fn decode<'a>(
+        &mut self,
+        input: &'a [u8],
+    ) -> Result<(&'a [u8], Response<'a>), ResponseDecodeError> {
+        if !self.framing.advance(input) {
+            return Err(ResponseDecodeError::Incomplete);
+        }
+
+        match self.codec.decode(input) {
+            Err(error @ ResponseDecodeError::Incomplete)
+            | Err(error @ ResponseDecodeError::LiteralFound { .. }) => Err(error),
+            result => {
+                self.reset();
+                result
+            }
+        }
+    }
+}
+
+/// Tracks the end of the current message.
+///
+/// All interactions transmitted by client and server are in the form of lines, that is,
+/// strings that end with a CRLF. A line can announce a literal, i.e., a sequence of octets with
+/// a known count followed by (the rest of) a line.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+struct Framing {
+    /// Start of the current line. Might point past the input while a literal is received.
+    line_start: usize,
+    /// Number of bytes that were already searched for a line ending.
+    scanned: usize,
+}
+
+impl Framing {
+    /// Consume newly received bytes and return whether the parser should be invoked.
+    ///
+    /// This is the case when a line was completed. A line that ends with a literal announcement
+    /// is passed to the parser, too, because only the parser knows whether it's a literal (and
+    /// not, e.g., a `text` that ends with `{<n>}`) and whether its length is acceptable. If the
+    /// parser reports `Incomplete` or `LiteralFound`, the announcement was a literal and the
+    /// framing skips its data.
+    fn advance(&mut self, input: &[u8]) -> bool {
+        // We are still receiving a literal.
+        if input.len() < self.line_start {
+            return false;
+        }
+
+        let scan_from = self.scanned.max(self.line_start);
+
+        let line_end = match input[scan_from..].iter().position(|byte| *byte == b'\n') {
+            Some(position) => scan_from + position + 1,
+            None => {
+                self.scanned = input.len();
+                return false;
+            }
+        };
+
+        self.line_start = match literal_announcement(&input[self.line_start..line_end]) {
+            Some((length, _)) => line_end + length as usize,
+            None => line_end,
+        };
+        self.scanned = self.line_start;
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroU32;
+
+    use imap_types::{
+        command::CommandBody,
+        core::{IString, Literal, LiteralMode, NString, NonEmptyVec, Tag},
+        fetch::MessageDataItem,
+        response::{Data, Status},
+    };
+
+    use super::*;
+    use crate::decode::{DecodeFailure, DecodeFailureKind, DecodeLimits, Limit};
+
+    #[test]
+    fn test_streaming_response_with_literal() {
+        let mut decoder = ResponseCodec::new().stream();
+        let mut buffer = Vec::new();
+
+        let tests: [(&[u8], Result<_, _>); 6] = [
+            (b"* 1 FETCH (RFC822", Err(ResponseDecodeError::Incomplete)),
+            (
+                b" {5}\r\n",
+                Err(ResponseDecodeError::LiteralFound { length: 5 }),
+            ),
+            (b"Hel", Err(ResponseDecodeError::Incomplete)),
+            (b"lo", Err(ResponseDecodeError::Incomplete)),
+            (b")\r", Err(ResponseDecodeError::Incomplete)),
+            (
+                b"\n* 2",
+                Ok((
+                    b"* 2".as_ref(),
+                    Response::Data(Data::Fetch {
+                        seq: NonZeroU32::new(1).unwrap(),
+                        items: NonEmptyVec::from(MessageDataItem::Rfc822(NString(Some(
+                            IString::Literal(Literal::try_from("Hello").unwrap()),
+                        )))),
+                    }),
+                )),
+            ),
+        ];
+
+        for (chunk, expected) in tests {
+            buffer.extend_from_slice(chunk);
+            assert_eq!(decoder.decode(&buffer), expected);
+        }
+    }
+
+    #[test]
+    fn test_streaming_response_literal_in_single_chunk() {
+        let mut decoder = ResponseCodec::new().stream();
+        let input = b"* 1 FETCH (RFC822 {6}\r\nA\r\nB\r\n)\r\n* 2 EXISTS\r\n".as_ref();
+
+        let (remaining, _) = decoder.decode(input).unwrap();
+        assert_eq!(remaining, b"* 2 EXISTS\r\n");
+
+        let (remaining, response) = decoder.decode(remaining).unwrap();
+        assert_eq!(remaining, b"");
+        assert_eq!(response, Response::Data(Data::Exists(2)));
+    }
+
+    #[test]
+    fn test_streaming_response_literal_lookalike() {
+        // Not a literal, but a `text` that happens to end with `{5}`.
+        let mut decoder = ResponseCodec::new().stream();
+
+        assert_eq!(
+            decoder.decode(b"* OK hello {5}\r\n"),
+            Ok((
+                b"".as_ref(),
+                Response::Status(Status::ok(None, None, "hello {5}").unwrap())
+            ))
+        );
+    }
+
+    #[test]
+    fn test_streaming_response_literal_lookalike_with_tail() {
+        let mut decoder = ResponseCodec::new().stream();
+
+        assert_eq!(
+            decoder.decode(b"* OK hello {5}\r\n* 2"),
+            Ok((
+                b"* 2".as_ref(),
+                Response::Status(Status::ok(None, None, "hello {5}").unwrap())
+            ))
+        );
+    }
+
+    #[test]
+    fn test_streaming_command() {
+        let mut decoder = CommandCodec::new().stream();
+        let mut buffer = Vec::new();
+
+        buffer.extend_from_slice(b"A LOGIN {5}\r\n");
+        assert_eq!(
+            decoder.decode(&buffer),
+            Err(CommandDecodeError::LiteralFound {
+                tag: Tag::try_from("A").unwrap(),
+                length: 5,
+                mode: LiteralMode::Sync,
+            })
+        );
+
+        buffer.extend_from_slice(b"alice {3+}\r\nxyz\r\nB NOOP\r\n");
+        let (remaining, command) = decoder.decode(&buffer).unwrap();
+        assert_eq!(remaining, b"B NOOP\r\n");
+        assert_eq!(
+            command.body,
+            CommandBody::login(
+                Literal::try_from("alice").unwrap(),
+                Literal::try_from("xyz").unwrap().into_non_sync(),
+            )
+            .unwrap()
+        );
+
+        let (remaining, command) = decoder.decode(remaining).unwrap();
+        assert_eq!(remaining, b"");
+        assert_eq!(command.body, CommandBody::Noop);
+    }
+
+    #[test]
+    fn test_streaming_literal_limit() {
+        let limits = DecodeLimits {
+            max_literal_length: 1024,
+            ..DecodeLimits::default()
+        };
+
+        // The literal is rejected as soon as it is announced, even when data follows.
+        let mut decoder = CommandCodec::new().with_limits(limits).stream();
+        assert!(matches!(
+            decoder.decode(b"A APPEND INBOX {4000000000+}\r\nxyz"),
+            Err(CommandDecodeError::Failed {
+                failure: DecodeFailure {
+                    kind: DecodeFailureKind::LimitExceeded(Limit::LiteralLength),
+                    ..
+                },
+                ..
+            })
+        ));
+
+        let mut decoder = ResponseCodec::new().with_limits(limits).stream();
+        assert!(matches!(
+            decoder.decode(b"* 1 FETCH (BODY[] {4000000000}\r\nxyz"),
+            Err(ResponseDecodeError::Failed(DecodeFailure {
+                kind: DecodeFailureKind::LimitExceeded(Limit::LiteralLength),
+                ..
+            }))
+        ));
+    }
+
+    #[test]
+    fn test_streaming_command_failed() {
+        let mut decoder = CommandCodec::new().stream();
+
+        assert_eq!(
+            decoder.decode(b"A NOOP"),
+            Err(CommandDecodeError::Incomplete)
+        );
+        assert_eq!(
+            decoder.decode(b"A NOOP \r\nB NOOP\r\n"),
+            Err(CommandDecodeError::Failed {
+                tag: Some(Tag::try_from("A").unwrap()),
+                line_length: Some(9),
+                failure: DecodeFailure {
+                    offset: 6,
+                    kind: DecodeFailureKind::Syntax(nom::error::ErrorKind::Satisfy),
+                    context: vec!["command", "CRLF"],
+                },
+            })
+        );
+
+        // The decoder starts over after a failure.
+        let (remaining, command) = decoder.decode(b"B NOOP\r\n").unwrap();
+        assert_eq!(remaining, b"");
+        assert_eq!(command.body, CommandBody::Noop);
+    }
+
+    #[test]
+    fn test_framing() {
+        let mut framing = Framing::default();
+
+        assert!(!framing.advance(b"* 1 FETCH (BODY[] {1"));
+        assert_eq!(framing.scanned, 20);
+        assert!(framing.advance(b"* 1 FETCH (BODY[] {10}\r\n"));
+        assert_eq!(framing.line_start, 34);
+        assert!(!framing.advance(b"* 1 FETCH (BODY[] {10}\r\n0123456789"));
+        assert!(!framing.advance(b"* 1 FETCH (BODY[] {10}\r\n0123456789)"));
+        assert!(framing.advance(b"* 1 FETCH (BODY[] {10}\r\n0123456789)\r\n"));
+        assert_eq!(framing.line_start, 37);
+    }
+}
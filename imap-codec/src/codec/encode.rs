@@ -64,9 +64,10 @@ use imap_types::{
     envelope::{Address, Envelope},
     extensions::idle::IdleDone,
     fetch::{
-        Macro, MacroOrMessageDataItemNames, MessageDataItem, MessageDataItemName, Part, Section,
+        FetchModifier, Macro, MacroOrMessageDataItemNames, MessageDataItem, MessageDataItemName,
+        Part, Section,
     },
-    flag::{Flag, FlagFetch, FlagNameAttribute, FlagPerm, StoreResponse, StoreType},
+    flag::{Flag, FlagFetch, FlagNameAttribute, FlagPerm, StoreAttribute},
     mailbox::{ListCharString, ListMailbox, Mailbox, MailboxOther},
     response::{
         Capability, Code, CodeOther, CommandContinuationRequest, Data, Greeting, GreetingKind,
@@ -77,9 +78,20 @@ use imap_types::{
     status::{StatusDataItem, StatusDataItemName},
     utils::escape_quoted,
 };
-use utils::{join_serializable, List1AttributeValueOrNil, List1OrNil};
+use utils::{
+    encode_append_message, encode_store_att, join_serializable, List1AttributeValueOrNil,
+    List1OrNil,
+};
+
+use crate::{
+    decode::{literal_announcement, Lossless},
+    extensions::annotate::encode_one_or_more_match,
+    AuthenticateDataCodec, CommandCodec, GreetingCodec, IdleDoneCodec, ResponseCodec,
+};
+
+mod stream;
 
-use crate::{AuthenticateDataCodec, CommandCodec, GreetingCodec, IdleDoneCodec, ResponseCodec};
+pub use stream::{LiteralReader, StreamedEncoded, StreamedFragment};
 
 /// Encoder.
 ///
@@ -91,6 +103,17 @@ pub trait Encoder {
     ///
     /// This will return an [`Encoded`] message.
     fn encode(&self, message: &Self::Message<'_>) -> Encoded;
+
+    /// Encode this message and re-emit its source byte-for-byte if it wasn't modified.
+    ///
+    /// See [`Lossless`].
+    fn encode_lossless(&self, message: &Lossless<'_, Self::Message<'_>>) -> Encoded {
+        if message.is_modified() {
+            self.encode(message.message())
+        } else {
+            Encoded::verbatim(message.source())
+        }
+    }
 }
 
 /// An encoded message.
@@ -139,6 +162,44 @@ impl Encoded {
     }
 }
 
+impl Encoded {
+    /// Split `source` into fragments, i.e., lines and the literals announced at their ends.
+    ///
+    /// Note: A message always ends with a line, so `{n}` is only a literal announcement when the
+    /// announced data is followed by more of the message. Otherwise, it's part of, e.g., a `text`.
+    fn verbatim(mut source: &[u8]) -> Self {
+        let mut items = Vec::new();
+
+        while !source.is_empty() {
+            let end = source
+                .iter()
+                .position(|byte| *byte == b'\n')
+                .map_or(source.len(), |position| position + 1);
+            let (line, rest) = source.split_at(end);
+
+            items.push(Fragment::Line {
+                data: line.to_vec(),
+            });
+
+            source = match literal_announcement(line) {
+                Some((length, mode)) if (length as usize) < rest.len() => {
+                    let (data, rest) = rest.split_at(length as usize);
+
+                    items.push(Fragment::Literal {
+                        data: data.to_vec(),
+                        mode,
+                    });
+
+                    rest
+                }
+                _ => rest,
+            };
+        }
+
+        Self { items }
+    }
+}
+
 impl Iterator for Encoded {
     type Item = Fragment;
 
@@ -331,6 +392,7 @@ impl<'a> EncodeIntoContext for CommandBody<'a> {
                 mailbox.encode_ctx(ctx)
             }
             CommandBody::Unselect => ctx.write_all(b"UNSELECT"),
+            CommandBody::Unauthenticate => ctx.write_all(b"UNAUTHENTICATE"),
             CommandBody::Examine { mailbox } => {
                 ctx.write_all(b"EXAMINE")?;
                 ctx.write_all(b" ")?;
@@ -386,6 +448,26 @@ impl<'a> EncodeIntoContext for CommandBody<'a> {
                 ctx.write_all(b" ")?;
                 mailbox_wildcard.encode_ctx(ctx)
             }
+            #[cfg(feature = "ext_mailbox_referrals")]
+            CommandBody::RList {
+                reference,
+                mailbox_wildcard,
+            } => {
+                ctx.write_all(b"RLIST ")?;
+                reference.encode_ctx(ctx)?;
+                ctx.write_all(b" ")?;
+                mailbox_wildcard.encode_ctx(ctx)
+            }
+            #[cfg(feature = "ext_mailbox_referrals")]
+            CommandBody::RLsub {
+                reference,
+                mailbox_wildcard,
+            } => {
+                ctx.write_all(b"RLSUB ")?;
+                reference.encode_ctx(ctx)?;
+                ctx.write_all(b" ")?;
+                mailbox_wildcard.encode_ctx(ctx)
+            }
             CommandBody::Status {
                 mailbox,
                 item_names,
@@ -407,21 +489,7 @@ impl<'a> EncodeIntoContext for CommandBody<'a> {
                 ctx.write_all(b"APPEND")?;
                 ctx.write_all(b" ")?;
                 mailbox.encode_ctx(ctx)?;
-
-                if !flags.is_empty() {
-                    ctx.write_all(b" ")?;
-                    ctx.write_all(b"(")?;
-                    join_serializable(flags, b" ", ctx)?;
-                    ctx.write_all(b")")?;
-                }
-
-                if let Some(date) = date {
-                    ctx.write_all(b" ")?;
-                    date.encode_ctx(ctx)?;
-                }
-
-                ctx.write_all(b" ")?;
-                message.encode_ctx(ctx)
+                encode_append_message(flags, date, message, ctx)
             }
             CommandBody::Check => ctx.write_all(b"CHECK"),
             CommandBody::Close => ctx.write_all(b"CLOSE"),
@@ -429,6 +497,7 @@ impl<'a> EncodeIntoContext for CommandBody<'a> {
             CommandBody::Search {
                 charset,
                 criteria,
+                return_options,
                 uid,
             } => {
                 if *uid {
@@ -436,6 +505,11 @@ impl<'a> EncodeIntoContext for CommandBody<'a> {
                 } else {
                     ctx.write_all(b"SEARCH")?;
                 }
+                if !return_options.is_empty() {
+                    ctx.write_all(b" RETURN (")?;
+                    join_serializable(return_options, b" ", ctx)?;
+                    ctx.write_all(b")")?;
+                }
                 if let Some(charset) = charset {
                     ctx.write_all(b" CHARSET ")?;
                     charset.encode_ctx(ctx)?;
@@ -446,6 +520,7 @@ impl<'a> EncodeIntoContext for CommandBody<'a> {
             CommandBody::Fetch {
                 sequence_set,
                 macro_or_item_names,
+                modifiers,
                 uid,
             } => {
                 if *uid {
@@ -456,13 +531,21 @@ impl<'a> EncodeIntoContext for CommandBody<'a> {
 
                 sequence_set.encode_ctx(ctx)?;
                 ctx.write_all(b" ")?;
-                macro_or_item_names.encode_ctx(ctx)
+                macro_or_item_names.encode_ctx(ctx)?;
+
+                if !modifiers.is_empty() {
+                    ctx.write_all(b" (")?;
+                    join_serializable(modifiers, b" ", ctx)?;
+                    ctx.write_all(b")")?;
+                }
+
+                Ok(())
             }
             CommandBody::Store {
                 sequence_set,
                 kind,
                 response,
-                flags,
+                attribute,
                 uid,
             } => {
                 if *uid {
@@ -474,21 +557,32 @@ impl<'a> EncodeIntoContext for CommandBody<'a> {
                 sequence_set.encode_ctx(ctx)?;
                 ctx.write_all(b" ")?;
 
-                match kind {
-                    StoreType::Add => ctx.write_all(b"+")?,
-                    StoreType::Remove => ctx.write_all(b"-")?,
-                    StoreType::Replace => {}
+                match attribute {
+                    StoreAttribute::Flags(flags) => {
+                        encode_store_att(*kind, b"FLAGS", *response, ctx)?;
+                        join_serializable(flags, b" ", ctx)?;
+                    }
+                    StoreAttribute::GmailLabels(labels) => {
+                        encode_store_att(*kind, b"X-GM-LABELS", *response, ctx)?;
+                        join_serializable(labels, b" ", ctx)?;
+                    }
                 }
-
-                ctx.write_all(b"FLAGS")?;
-
-                match response {
-                    StoreResponse::Answer => {}
-                    StoreResponse::Silent => ctx.write_all(b".SILENT")?,
+                ctx.write_all(b")")
+            }
+            CommandBody::StoreAnnotation {
+                sequence_set,
+                entries,
+                uid,
+            } => {
+                if *uid {
+                    ctx.write_all(b"UID STORE ")?;
+                } else {
+                    ctx.write_all(b"STORE ")?;
                 }
 
-                ctx.write_all(b" (")?;
-                join_serializable(flags, b" ", ctx)?;
+                sequence_set.encode_ctx(ctx)?;
+                ctx.write_all(b" ANNOTATION (")?;
+                join_serializable(entries.as_ref(), b" ", ctx)?;
                 ctx.write_all(b")")
             }
             CommandBody::Copy {
@@ -543,6 +637,89 @@ impl<'a> EncodeIntoContext for CommandBody<'a> {
                 ctx.write_all(b" ")?;
                 mailbox.encode_ctx(ctx)
             }
+            CommandBody::Replace {
+                sequence_number_or_uid,
+                mailbox,
+                flags,
+                date,
+                message,
+                uid,
+            } => {
+                if *uid {
+                    ctx.write_all(b"UID REPLACE ")?;
+                } else {
+                    ctx.write_all(b"REPLACE ")?;
+                }
+
+                sequence_number_or_uid.encode_ctx(ctx)?;
+                ctx.write_all(b" ")?;
+                mailbox.encode_ctx(ctx)?;
+                encode_append_message(flags, date, message, ctx)
+            }
+            CommandBody::Language { ranges } => {
+                ctx.write_all(b"LANGUAGE")?;
+                for range in ranges {
+                    ctx.write_all(b" ")?;
+                    range.encode_ctx(ctx)?;
+                }
+                Ok(())
+            }
+            CommandBody::Convert {
+                sequence_set,
+                items,
+                uid,
+            } => {
+                if *uid {
+                    ctx.write_all(b"UID CONVERT ")?;
+                } else {
+                    ctx.write_all(b"CONVERT ")?;
+                }
+                sequence_set.encode_ctx(ctx)?;
+                ctx.write_all(b" (")?;
+                join_serializable(items.as_ref(), b" ", ctx)?;
+                ctx.write_all(b")")
+            }
+            CommandBody::ESearch {
+                source_options,
+                return_options,
+                charset,
+                criteria,
+            } => {
+                ctx.write_all(b"ESEARCH")?;
+                if !source_options.is_empty() {
+                    ctx.write_all(b" IN (")?;
+                    join_serializable(source_options, b" ", ctx)?;
+                    ctx.write_all(b")")?;
+                }
+                if !return_options.is_empty() {
+                    ctx.write_all(b" RETURN (")?;
+                    join_serializable(return_options, b" ", ctx)?;
+                    ctx.write_all(b")")?;
+                }
+                if let Some(charset) = charset {
+                    ctx.write_all(b" CHARSET ")?;
+                    charset.encode_ctx(ctx)?;
+                }
+                ctx.write_all(b" ")?;
+                criteria.encode_ctx(ctx)
+            }
+            CommandBody::CancelUpdate { tags } => {
+                ctx.write_all(b"CANCELUPDATE")?;
+                for tag in tags.as_ref() {
+                    ctx.write_all(b" \"")?;
+                    tag.encode_ctx(ctx)?;
+                    ctx.write_all(b"\"")?;
+                }
+                Ok(())
+            }
+            CommandBody::Comparator { comparators } => {
+                ctx.write_all(b"COMPARATOR")?;
+                for comparator in comparators {
+                    ctx.write_all(b" ")?;
+                    comparator.encode_ctx(ctx)?;
+                }
+                Ok(())
+            }
         }
     }
 }
@@ -652,6 +829,8 @@ impl EncodeIntoContext for StatusDataItemName {
             Self::Unseen => ctx.write_all(b"UNSEEN"),
             Self::Deleted => ctx.write_all(b"DELETED"),
             Self::DeletedStorage => ctx.write_all(b"DELETED-STORAGE"),
+            Self::Size => ctx.write_all(b"SIZE"),
+            Self::AppendLimit => ctx.write_all(b"APPENDLIMIT"),
             #[cfg(feature = "ext_condstore_qresync")]
             Self::HighestModSeq => ctx.write_all(b"HIGHESTMODSEQ"),
         }
@@ -760,6 +939,22 @@ impl<'a> EncodeIntoContext for SearchKey<'a> {
                 flag_keyword.encode_ctx(ctx)
             }
             SearchKey::Unseen => ctx.write_all(b"UNSEEN"),
+            SearchKey::Annotation {
+                entry,
+                attribute,
+                value,
+            } => {
+                ctx.write_all(b"ANNOTATION ")?;
+                entry.encode_ctx(ctx)?;
+                ctx.write_all(b" ")?;
+                attribute.encode_ctx(ctx)?;
+                ctx.write_all(b" ")?;
+                value.encode_ctx(ctx)
+            }
+            SearchKey::Filter(filter_name) => {
+                ctx.write_all(b"FILTER ")?;
+                filter_name.encode_ctx(ctx)
+            }
             SearchKey::Draft => ctx.write_all(b"DRAFT"),
             SearchKey::Header(header_fld_name, astring) => {
                 ctx.write_all(b"HEADER ")?;
@@ -840,6 +1035,17 @@ impl EncodeIntoContext for NaiveDate {
     }
 }
 
+impl EncodeIntoContext for FetchModifier {
+    fn encode_ctx(&self, ctx: &mut EncodeContext) -> std::io::Result<()> {
+        match self {
+            FetchModifier::Partial(range) => {
+                ctx.write_all(b"PARTIAL ")?;
+                range.encode_ctx(ctx)
+            }
+        }
+    }
+}
+
 impl<'a> EncodeIntoContext for MacroOrMessageDataItemNames<'a> {
     fn encode_ctx(&self, ctx: &mut EncodeContext) -> std::io::Result<()> {
         match self {
@@ -896,6 +1102,27 @@ impl<'a> EncodeIntoContext for MessageDataItemName<'a> {
             Self::Rfc822Size => ctx.write_all(b"RFC822.SIZE"),
             Self::Rfc822Text => ctx.write_all(b"RFC822.TEXT"),
             Self::Uid => ctx.write_all(b"UID"),
+            Self::SaveDate => ctx.write_all(b"SAVEDATE"),
+            Self::Preview { lazy } => {
+                if *lazy {
+                    ctx.write_all(b"PREVIEW (LAZY)")
+                } else {
+                    ctx.write_all(b"PREVIEW")
+                }
+            }
+            Self::GmailMessageId => ctx.write_all(b"X-GM-MSGID"),
+            Self::GmailThreadId => ctx.write_all(b"X-GM-THRID"),
+            Self::GmailLabels => ctx.write_all(b"X-GM-LABELS"),
+            Self::Annotation {
+                entries,
+                attributes,
+            } => {
+                ctx.write_all(b"ANNOTATION (")?;
+                encode_one_or_more_match(entries, ctx)?;
+                ctx.write_all(b" ")?;
+                encode_one_or_more_match(attributes, ctx)?;
+                ctx.write_all(b")")
+            }
         }
     }
 }
@@ -1081,13 +1308,54 @@ impl<'a> EncodeIntoContext for Code<'a> {
             }
             // RFC 2221
             #[cfg(any(feature = "ext_login_referrals", feature = "ext_mailbox_referrals"))]
-            Code::Referral(url) => {
-                ctx.write_all(b"REFERRAL ")?;
-                ctx.write_all(url.as_bytes())
+            Code::Referral(urls) => {
+                ctx.write_all(b"REFERRAL")?;
+                for url in urls.as_ref() {
+                    write!(ctx, " {url}")?;
+                }
+                Ok(())
             }
             Code::CompressionActive => ctx.write_all(b"COMPRESSIONACTIVE"),
             Code::OverQuota => ctx.write_all(b"OVERQUOTA"),
             Code::TooBig => ctx.write_all(b"TOOBIG"),
+            Code::BadComparator => ctx.write_all(b"BADCOMPARATOR"),
+            Code::UnknownCvt => ctx.write_all(b"UNKNOWN-CVT"),
+            Code::BadParams => ctx.write_all(b"BADPARAMS"),
+            Code::AnnotateTooBig => ctx.write_all(b"ANNOTATE TOOBIG"),
+            Code::AnnotateTooMany => ctx.write_all(b"ANNOTATE TOOMANY"),
+            Code::NoUpdate { tag } => {
+                ctx.write_all(b"NOUPDATE \"")?;
+                tag.encode_ctx(ctx)?;
+                ctx.write_all(b"\"")
+            }
+            Code::InProgress { tag, current, goal } => {
+                ctx.write_all(b"INPROGRESS")?;
+
+                if tag.is_some() || current.is_some() || goal.is_some() {
+                    ctx.write_all(b" (")?;
+                    match tag {
+                        Some(tag) => {
+                            ctx.write_all(b"\"")?;
+                            tag.encode_ctx(ctx)?;
+                            ctx.write_all(b"\"")?;
+                        }
+                        None => ctx.write_all(b"NIL")?,
+                    }
+                    ctx.write_all(b" ")?;
+                    match current {
+                        Some(current) => current.encode_ctx(ctx)?,
+                        None => ctx.write_all(b"NIL")?,
+                    }
+                    ctx.write_all(b" ")?;
+                    match goal {
+                        Some(goal) => goal.encode_ctx(ctx)?,
+                        None => ctx.write_all(b"NIL")?,
+                    }
+                    ctx.write_all(b")")?;
+                }
+
+                Ok(())
+            }
             Code::Other(unknown) => unknown.encode_ctx(ctx),
         }
     }
@@ -1201,6 +1469,53 @@ impl<'a> EncodeIntoContext for Data<'a> {
                     root.encode_ctx(ctx)?;
                 }
             }
+            Data::Language { tags } => {
+                ctx.write_all(b"* LANGUAGE (")?;
+                join_serializable(tags.as_ref(), b" ", ctx)?;
+                ctx.write_all(b")")?;
+            }
+            Data::Comparator { selected, matching } => {
+                ctx.write_all(b"* COMPARATOR ")?;
+                selected.encode_ctx(ctx)?;
+                if !matching.is_empty() {
+                    ctx.write_all(b" (")?;
+                    join_serializable(matching, b" ", ctx)?;
+                    ctx.write_all(b")")?;
+                }
+            }
+            Data::ESearch {
+                correlator,
+                uid,
+                items,
+            } => {
+                ctx.write_all(b"* ESEARCH")?;
+                if let Some(correlator) = correlator {
+                    ctx.write_all(b" ")?;
+                    correlator.encode_ctx(ctx)?;
+                }
+                if *uid {
+                    ctx.write_all(b" UID")?;
+                }
+                for item in items {
+                    ctx.write_all(b" ")?;
+                    item.encode_ctx(ctx)?;
+                }
+            }
+            Data::Converted {
+                seq,
+                correlator,
+                uid,
+                items,
+            } => {
+                write!(ctx, "* {seq} CONVERTED ")?;
+                correlator.encode_ctx(ctx)?;
+                if let Some(uid) = uid {
+                    write!(ctx, " UID {uid}")?;
+                }
+                ctx.write_all(b" (")?;
+                join_serializable(items.as_ref(), b" ", ctx)?;
+                ctx.write_all(b")")?;
+            }
         }
 
         ctx.write_all(b"\r\n")
@@ -1254,6 +1569,17 @@ impl EncodeIntoContext for StatusDataItem {
                 ctx.write_all(b"DELETED-STORAGE ")?;
                 count.encode_ctx(ctx)
             }
+            Self::Size(size) => {
+                ctx.write_all(b"SIZE ")?;
+                size.encode_ctx(ctx)
+            }
+            Self::AppendLimit(limit) => {
+                ctx.write_all(b"APPENDLIMIT ")?;
+                match limit {
+                    Some(limit) => limit.encode_ctx(ctx),
+                    None => ctx.write_all(b"NIL"),
+                }
+            }
         }
     }
 }
@@ -1313,6 +1639,28 @@ impl<'a> EncodeIntoContext for MessageDataItem<'a> {
                 nstring.encode_ctx(ctx)
             }
             Self::Uid(uid) => write!(ctx, "UID {uid}"),
+            Self::SaveDate(datetime) => {
+                ctx.write_all(b"SAVEDATE ")?;
+                match datetime {
+                    Some(datetime) => datetime.encode_ctx(ctx),
+                    None => ctx.write_all(b"NIL"),
+                }
+            }
+            Self::Preview(nstring) => {
+                ctx.write_all(b"PREVIEW ")?;
+                nstring.encode_ctx(ctx)
+            }
+            Self::GmailMessageId(id) => write!(ctx, "X-GM-MSGID {id}"),
+            Self::GmailThreadId(id) => write!(ctx, "X-GM-THRID {id}"),
+            Self::GmailLabels(labels) => {
+                ctx.write_all(b"X-GM-LABELS (")?;
+                join_serializable(labels, b" ", ctx)?;
+                ctx.write_all(b")")
+            }
+            Self::Annotation(data) => {
+                ctx.write_all(b"ANNOTATION ")?;
+                data.encode_ctx(ctx)
+            }
         }
     }
 }
@@ -1579,15 +1927,77 @@ impl<'a> EncodeIntoContext for CommandContinuationRequest<'a> {
     }
 }
 
-mod utils {
+pub(crate) mod utils {
     use std::io::Write;
 
+    use imap_types::{
+        core::Literal,
+        datetime::DateTime,
+        flag::{Flag, StoreResponse, StoreType},
+    };
+
     use super::{EncodeContext, EncodeIntoContext};
 
     pub struct List1OrNil<'a, T>(pub &'a Vec<T>, pub &'a [u8]);
 
     pub struct List1AttributeValueOrNil<'a, T>(pub &'a Vec<(T, T)>);
 
+    /// Encodes `[SP flag-list] [SP date-time] SP literal` as used by APPEND and REPLACE.
+    pub(crate) fn encode_append_message(
+        flags: &[Flag],
+        date: &Option<DateTime>,
+        message: &Literal,
+        ctx: &mut EncodeContext,
+    ) -> std::io::Result<()> {
+        encode_append_attributes(flags, date.as_ref(), ctx)?;
+        ctx.write_all(b" ")?;
+        message.encode_ctx(ctx)
+    }
+
+    /// Encodes `[SP flag-list] [SP date-time]` as used by APPEND and REPLACE.
+    pub(crate) fn encode_append_attributes(
+        flags: &[Flag],
+        date: Option<&DateTime>,
+        ctx: &mut EncodeContext,
+    ) -> std::io::Result<()> {
+        if !flags.is_empty() {
+            ctx.write_all(b" ")?;
+            ctx.write_all(b"(")?;
+            join_serializable(flags, b" ", ctx)?;
+            ctx.write_all(b")")?;
+        }
+
+        if let Some(date) = date {
+            ctx.write_all(b" ")?;
+            date.encode_ctx(ctx)?;
+        }
+
+        Ok(())
+    }
+
+    /// Encodes `["+" / "-"] <name> [".SILENT"] SP "("`.
+    pub(crate) fn encode_store_att(
+        kind: StoreType,
+        name: &[u8],
+        response: StoreResponse,
+        ctx: &mut EncodeContext,
+    ) -> std::io::Result<()> {
+        match kind {
+            StoreType::Add => ctx.write_all(b"+")?,
+            StoreType::Remove => ctx.write_all(b"-")?,
+            StoreType::Replace => {}
+        }
+
+        ctx.write_all(name)?;
+
+        match response {
+            StoreResponse::Answer => {}
+            StoreResponse::Silent => ctx.write_all(b".SILENT")?,
+        }
+
+        ctx.write_all(b" (")
+    }
+
     pub(crate) fn join_serializable<I: EncodeIntoContext>(
         elements: &[I],
         sep: &[u8],
@@ -0,0 +1,423 @@
+use std::{
+    io::{Read, Write},
+    num::NonZeroU32,
+};
+
+use imap_types::{
+    core::{LiteralMode, Tag},
+    datetime::DateTime,
+    fetch::{MessageDataItem, Section},
+    flag::Flag,
+    mailbox::Mailbox,
+    sequence::SeqOrUid,
+};
+
+use super::{
+    utils::{encode_append_attributes, join_serializable},
+    EncodeContext, EncodeIntoContext, Encoded, Fragment,
+};
+use crate::{CommandCodec, ResponseCodec};
+
+/// A literal whose payload is provided by a [`Read`] source with a known length.
+///
+/// Use this instead of [`Literal`](imap_types::core::Literal) when the payload is too large to
+/// hold in memory, e.g., a multi-gigabyte message.
+///
+/// Note: The payload is not validated. Literals must not contain NUL bytes.
+#[derive(Debug)]
+pub struct LiteralReader<R> {
+    reader: R,
+    length: u32,
+    mode: LiteralMode,
+}
+
+impl<R> LiteralReader<R> {
+    /// Create a synchronizing literal that reads `length` bytes from `reader`.
+    pub fn new(reader: R, length: u32) -> Self {
+        Self {
+            reader,
+            length,
+            mode: LiteralMode::Sync,
+        }
+    }
+
+    /// Turn the literal into a non-synchronizing literal.
+    pub fn into_non_sync(mut self) -> Self {
+        self.mode = LiteralMode::NonSync;
+        self
+    }
+
+    /// Length of the payload.
+    pub fn length(&self) -> u32 {
+        self.length
+    }
+
+    /// Specifies whether this is a synchronizing or non-synchronizing literal.
+    pub fn mode(&self) -> LiteralMode {
+        self.mode
+    }
+
+    /// Return the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: Read> LiteralReader<R> {
+    /// Copy exactly `length` bytes from the reader into `writer`.
+    ///
+    /// Fails with [`std::io::ErrorKind::UnexpectedEof`] when the reader ends early.
+    pub fn copy_to<W: Write + ?Sized>(self, writer: &mut W) -> std::io::Result<()> {
+        let length = u64::from(self.length);
+        let copied = std::io::copy(&mut self.reader.take(length), writer)?;
+
+        if copied != length {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                format!("literal source ended after {copied} of {length} bytes"),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// An encoded message whose literal payload is provided by a [`Read`] source.
+///
+/// Works like [`Encoded`] but yields a [`StreamedFragment::Payload`] where the payload belongs.
+/// The literal header (`{<length>}\r\n`) is part of the preceding [`Fragment::Line`], so the
+/// caller handles the literal mode (e.g., waits for a continuation request) and copies the
+/// payload afterwards.
+///
+/// # Example
+///
+/// ```rust
+/// use imap_codec::{
+///     encode::{LiteralReader, StreamedFragment},
+///     imap_types::{core::Tag, mailbox::Mailbox},
+///     CommandCodec,
+/// };
+///
+/// let message = b"From: alice@example.org\r\n\r\nHello!\r\n";
+/// let reader = LiteralReader::new(message.as_ref(), message.len() as u32);
+///
+/// let encoded = CommandCodec::default().encode_append(
+///     &Tag::try_from("A1").unwrap(),
+///     &Mailbox::Inbox,
+///     &[],
+///     None,
+///     reader,
+/// );
+///
+/// let mut out = Vec::new();
+///
+/// for fragment in encoded {
+///     match fragment {
+///         StreamedFragment::Fragment(fragment) => {
+///             // Handle the fragment as usual ...
+///             # match fragment {
+///             #     imap_codec::encode::Fragment::Line { data }
+///             #     | imap_codec::encode::Fragment::Literal { data, .. } => out.extend(data),
+///             # }
+///         }
+///         StreamedFragment::Payload(payload) => {
+///             // ... and copy the payload, e.g., into a `TcpStream`.
+///             payload.copy_to(&mut out).unwrap();
+///         }
+///     }
+/// }
+///
+/// assert!(out.starts_with(b"A1 APPEND INBOX {35}\r\nFrom: "));
+/// ```
+#[derive(Debug)]
+pub struct StreamedEncoded<R> {
+    head: Encoded,
+    payload: Option<LiteralReader<R>>,
+    tail: Encoded,
+}
+
+impl<R: Read> StreamedEncoded<R> {
+    /// Write the (remaining) encoded message without being guided by [`StreamedFragment`]s.
+    pub fn write_to<W: Write + ?Sized>(self, writer: &mut W) -> std::io::Result<()> {
+        for fragment in self {
+            match fragment {
+                StreamedFragment::Fragment(Fragment::Line { data })
+                | StreamedFragment::Fragment(Fragment::Literal { data, .. }) => {
+                    writer.write_all(&data)?
+                }
+                StreamedFragment::Payload(payload) => payload.copy_to(writer)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<R> Iterator for StreamedEncoded<R> {
+    type Item = StreamedFragment<R>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(fragment) = self.head.next() {
+            return Some(StreamedFragment::Fragment(fragment));
+        }
+
+        if let Some(payload) = self.payload.take() {
+            return Some(StreamedFragment::Payload(payload));
+        }
+
+        self.tail.next().map(StreamedFragment::Fragment)
+    }
+}
+
+/// The intended action of a client or server (see [`StreamedEncoded`]).
+#[derive(Debug)]
+pub enum StreamedFragment<R> {
+    /// A fragment that is handled as usual.
+    Fragment(Fragment),
+
+    /// A literal payload that should be copied from its reader.
+    ///
+    /// The literal header was already yielded with the preceding line.
+    Payload(LiteralReader<R>),
+}
+
+impl CommandCodec {
+    /// Encode an `APPEND` command whose message is provided by a [`Read`] source.
+    pub fn encode_append<R: Read>(
+        &self,
+        tag: &Tag,
+        mailbox: &Mailbox,
+        flags: &[Flag],
+        date: Option<&DateTime>,
+        message: LiteralReader<R>,
+    ) -> StreamedEncoded<R> {
+        encode_streamed(
+            |ctx| {
+                tag.encode_ctx(ctx)?;
+                ctx.write_all(b" APPEND ")?;
+                mailbox.encode_ctx(ctx)?;
+                encode_append_attributes(flags, date, ctx)?;
+                ctx.write_all(b" ")
+            },
+            message,
+            b"\r\n",
+        )
+    }
+
+    /// Encode a `REPLACE` command (see RFC 8508) whose message is provided by a [`Read`] source.
+    #[allow(clippy::too_many_arguments)]
+    pub fn encode_replace<R: Read>(
+        &self,
+        tag: &Tag,
+        sequence_number_or_uid: SeqOrUid,
+        mailbox: &Mailbox,
+        flags: &[Flag],
+        date: Option<&DateTime>,
+        message: LiteralReader<R>,
+        uid: bool,
+    ) -> StreamedEncoded<R> {
+        encode_streamed(
+            |ctx| {
+                tag.encode_ctx(ctx)?;
+                if uid {
+                    ctx.write_all(b" UID REPLACE ")?;
+                } else {
+                    ctx.write_all(b" REPLACE ")?;
+                }
+                sequence_number_or_uid.encode_ctx(ctx)?;
+                ctx.write_all(b" ")?;
+                mailbox.encode_ctx(ctx)?;
+                encode_append_attributes(flags, date, ctx)?;
+                ctx.write_all(b" ")
+            },
+            message,
+            b"\r\n",
+        )
+    }
+}
+
+impl ResponseCodec {
+    /// Encode a `FETCH` response whose `BODY[<section>]<<origin>>` data is provided by a [`Read`]
+    /// source.
+    ///
+    /// The body is preceded by `items` (which may be empty), e.g., `UID` or `FLAGS`.
+    pub fn encode_fetch_body<R: Read>(
+        &self,
+        seq: NonZeroU32,
+        items: &[MessageDataItem],
+        section: Option<&Section>,
+        origin: Option<u32>,
+        body: LiteralReader<R>,
+    ) -> StreamedEncoded<R> {
+        encode_streamed(
+            |ctx| {
+                write!(ctx, "* {seq} FETCH (")?;
+                if !items.is_empty() {
+                    join_serializable(items, b" ", ctx)?;
+                    ctx.write_all(b" ")?;
+                }
+                ctx.write_all(b"BODY[")?;
+                if let Some(section) = section {
+                    section.encode_ctx(ctx)?;
+                }
+                ctx.write_all(b"]")?;
+                if let Some(origin) = origin {
+                    write!(ctx, "<{origin}>")?;
+                }
+                ctx.write_all(b" ")
+            },
+            body,
+            b")\r\n",
+        )
+    }
+}
+
+/// Encode everything `head` writes followed by the header of `payload`, and `tail`.
+fn encode_streamed<R>(
+    head: impl FnOnce(&mut EncodeContext) -> std::io::Result<()>,
+    payload: LiteralReader<R>,
+    tail: &[u8],
+) -> StreamedEncoded<R> {
+    let mut ctx = EncodeContext::new();
+    // Unwrap: Writing into an `EncodeContext` doesn't fail.
+    head(&mut ctx).unwrap();
+    match payload.mode {
+        LiteralMode::Sync => write!(ctx, "{{{}}}\r\n", payload.length).unwrap(),
+        LiteralMode::NonSync => write!(ctx, "{{{}+}}\r\n", payload.length).unwrap(),
+    }
+
+    let mut tail_ctx = EncodeContext::new();
+    tail_ctx.write_all(tail).unwrap();
+
+    StreamedEncoded {
+        head: Encoded {
+            items: ctx.into_items(),
+        },
+        payload: Some(payload),
+        tail: Encoded {
+            items: tail_ctx.into_items(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use imap_types::{
+        core::{IString, Literal, NString, NonEmptyVec},
+        flag::Flag,
+        response::{Data, Response},
+    };
+
+    use super::*;
+    use crate::{
+        decode::{CommandDecodeError, Decoder},
+        encode::Encoder,
+    };
+
+    #[test]
+    fn test_encode_append() {
+        let message = b"Hello, World!\r\n".as_ref();
+
+        let fragments: Vec<_> = CommandCodec::default()
+            .encode_append(
+                &Tag::try_from("A").unwrap(),
+                &Mailbox::try_from("Drafts").unwrap(),
+                &[Flag::Seen, Flag::Draft],
+                None,
+                LiteralReader::new(message, 15),
+            )
+            .collect();
+
+        assert_eq!(fragments.len(), 3);
+        assert!(matches!(
+            &fragments[0],
+            StreamedFragment::Fragment(Fragment::Line { data })
+                if data == b"A APPEND Drafts (\\Seen \\Draft) {15}\r\n"
+        ));
+        assert!(matches!(
+            &fragments[1],
+            StreamedFragment::Payload(payload) if payload.length() == 15
+        ));
+        assert!(matches!(
+            &fragments[2],
+            StreamedFragment::Fragment(Fragment::Line { data }) if data == b"\r\n"
+        ));
+    }
+
+    #[test]
+    fn test_encode_replace_write_to() {
+        let message = b"Hello".as_ref();
+
+        let mut out = Vec::new();
+        CommandCodec::default()
+            .encode_replace(
+                &Tag::try_from("A").unwrap(),
+                SeqOrUid::Value(NonZeroU32::new(42).unwrap()),
+                &Mailbox::Inbox,
+                &[],
+                None,
+                LiteralReader::new(message, 5).into_non_sync(),
+                true,
+            )
+            .write_to(&mut out)
+            .unwrap();
+
+        assert_eq!(out, b"A UID REPLACE 42 INBOX {5+}\r\nHello\r\n");
+        assert!(CommandCodec::default().decode(&out).is_ok());
+    }
+
+    #[test]
+    fn test_encode_fetch_body_matches_encoder() {
+        let body = b"ABCDE".as_ref();
+
+        let mut out = Vec::new();
+        ResponseCodec::default()
+            .encode_fetch_body(
+                NonZeroU32::new(1).unwrap(),
+                &[MessageDataItem::Uid(NonZeroU32::new(7).unwrap())],
+                None,
+                Some(0),
+                LiteralReader::new(body, 5),
+            )
+            .write_to(&mut out)
+            .unwrap();
+
+        let expected = ResponseCodec::default()
+            .encode(&Response::Data(Data::Fetch {
+                seq: NonZeroU32::new(1).unwrap(),
+                items: NonEmptyVec::try_from(vec![
+                    MessageDataItem::Uid(NonZeroU32::new(7).unwrap()),
+                    MessageDataItem::BodyExt {
+                        section: None,
+                        origin: Some(0),
+                        data: NString(Some(IString::Literal(Literal::try_from(body).unwrap()))),
+                    },
+                ])
+                .unwrap(),
+            }))
+            .dump();
+
+        assert_eq!(out, expected);
+    }
+
+    #[test]
+    fn test_literal_reader_too_short() {
+        let mut out = Vec::new();
+        let error = CommandCodec::default()
+            .encode_append(
+                &Tag::try_from("A").unwrap(),
+                &Mailbox::Inbox,
+                &[],
+                None,
+                LiteralReader::new(b"abc".as_ref(), 4).into_non_sync(),
+            )
+            .write_to(&mut out)
+            .unwrap_err();
+
+        assert_eq!(error.kind(), std::io::ErrorKind::UnexpectedEof);
+        assert_eq!(
+            CommandCodec::default().decode(&out),
+            Err(CommandDecodeError::Incomplete)
+        );
+    }
+}
@@ -1,39 +1,45 @@
 use std::borrow::Cow;
 
-#[cfg(not(feature = "quirk_crlf_relaxed"))]
-use abnf_core::streaming::crlf;
-#[cfg(feature = "quirk_crlf_relaxed")]
-use abnf_core::streaming::crlf_relaxed as crlf;
 use abnf_core::streaming::sp;
 use imap_types::{
     auth::AuthMechanism,
     command::{Command, CommandBody},
-    core::AString,
+    core::{AString, Literal},
+    datetime::DateTime,
     fetch::{Macro, MacroOrMessageDataItemNames},
-    flag::{Flag, StoreResponse, StoreType},
+    flag::{Flag, StoreAttribute, StoreResponse, StoreType},
     secret::Secret,
 };
 use nom::{
     branch::alt,
     bytes::streaming::{tag, tag_no_case},
     combinator::{map, opt, value},
-    multi::{separated_list0, separated_list1},
+    error::context,
     sequence::{delimited, preceded, terminated, tuple},
 };
 
+#[cfg(feature = "ext_mailbox_referrals")]
+use crate::extensions::referrals::{rlist, rlsub};
 use crate::{
     auth::auth_type,
     core::{astring, base64, literal, tag_imap},
     datetime::date_time,
-    decode::{IMAPErrorKind, IMAPResult},
+    decode::{crlf, separated_list0, separated_list1, IMAPErrorKind, IMAPResult},
     extensions::{
+        annotate::entry_atts,
         compress::compress,
+        context::cancelupdate,
+        convert::convert,
         enable::enable,
+        gmail::{gmail_label, gmail_label_list},
         idle::idle,
+        language::{comparator, language},
+        multisearch::esearch,
         quota::{getquota, getquotaroot, setquota},
         r#move::r#move,
+        replace::replace,
     },
-    fetch::fetch_att,
+    fetch::{fetch_att, fetch_modifiers},
     flag::{flag, flag_list},
     mailbox::{list_mailbox, mailbox},
     search::search,
@@ -48,10 +54,13 @@ use crate::{
 ///                     command-select
 ///                   ) CRLF`
 pub(crate) fn command(input: &[u8]) -> IMAPResult<&[u8], Command> {
-    let mut parser_tag = terminated(tag_imap, sp);
+    let mut parser_tag = terminated(context("tag", tag_imap), context("SP after tag", sp));
     let mut parser_body = terminated(
-        alt((command_any, command_auth, command_nonauth, command_select)),
-        crlf,
+        context(
+            "command body",
+            alt((command_any, command_auth, command_nonauth, command_select)),
+        ),
+        context("CRLF", crlf),
     );
 
     let (remaining, obtained_tag) = parser_tag(input)?;
@@ -81,12 +90,16 @@ pub(crate) fn command(input: &[u8]) -> IMAPResult<&[u8], Command> {
 
 /// `command-any = "CAPABILITY" / "LOGOUT" / "NOOP" / x-command`
 ///
+/// `command-any =/ language-cmd / comparator-cmd` (RFC 5255)
+///
 /// Note: Valid in all states
 pub(crate) fn command_any(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
     alt((
         value(CommandBody::Capability, tag_no_case(b"CAPABILITY")),
         value(CommandBody::Logout, tag_no_case(b"LOGOUT")),
         value(CommandBody::Noop, tag_no_case(b"NOOP")),
+        language,
+        comparator,
         // x-command = "X" atom <experimental command arguments>
     ))(input)
 }
@@ -108,6 +121,10 @@ pub(crate) fn command_any(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
 ///                 enable ; RFC 5161
 ///                 compress ; RFC 4978`
 ///
+/// `command-auth =/ "UNAUTHENTICATE"` (RFC 8437)
+///
+/// `command-auth =/ esearch` (RFC 7377)
+///
 /// Note: Valid only in Authenticated or Selected state
 pub(crate) fn command_auth(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
     alt((
@@ -117,6 +134,10 @@ pub(crate) fn command_auth(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
         examine,
         list,
         lsub,
+        #[cfg(feature = "ext_mailbox_referrals")]
+        rlist,
+        #[cfg(feature = "ext_mailbox_referrals")]
+        rlsub,
         rename,
         select,
         status,
@@ -128,6 +149,8 @@ pub(crate) fn command_auth(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
         getquota,
         getquotaroot,
         setquota,
+        value(CommandBody::Unauthenticate, tag_no_case(b"UNAUTHENTICATE")),
+        esearch,
     ))(input)
 }
 
@@ -135,32 +158,48 @@ pub(crate) fn command_auth(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
 pub(crate) fn append(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
     let mut parser = tuple((
         tag_no_case(b"APPEND"),
-        sp,
+        context("SP", sp),
         mailbox,
-        opt(preceded(sp, flag_list)),
-        opt(preceded(sp, date_time)),
-        sp,
-        literal,
+        append_message,
     ));
 
-    let (remaining, (_, _, mailbox, flags, date, _, message)) = parser(input)?;
+    let (remaining, (_, _, mailbox, (flags, date, message))) = parser(input)?;
 
     Ok((
         remaining,
         CommandBody::Append {
             mailbox,
-            flags: flags.unwrap_or_default(),
+            flags,
             date,
             message,
         },
     ))
 }
 
+/// `[SP flag-list] [SP date-time] SP literal`
+///
+/// Note: This is the common part of `append` and `replace` (RFC 8508).
+#[allow(clippy::type_complexity)]
+pub(crate) fn append_message(
+    input: &[u8],
+) -> IMAPResult<&[u8], (Vec<Flag>, Option<DateTime>, Literal)> {
+    let mut parser = tuple((
+        opt(preceded(sp, flag_list)),
+        opt(preceded(sp, date_time)),
+        context("SP", sp),
+        context("literal", literal),
+    ));
+
+    let (remaining, (flags, date, _, message)) = parser(input)?;
+
+    Ok((remaining, (flags.unwrap_or_default(), date, message)))
+}
+
 /// `create = "CREATE" SP mailbox`
 ///
 /// Note: Use of INBOX gives a NO error
 pub(crate) fn create(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
-    let mut parser = tuple((tag_no_case(b"CREATE"), sp, mailbox));
+    let mut parser = tuple((tag_no_case(b"CREATE"), context("SP", sp), mailbox));
 
     let (remaining, (_, _, mailbox)) = parser(input)?;
 
@@ -171,7 +210,7 @@ pub(crate) fn create(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
 ///
 /// Note: Use of INBOX gives a NO error
 pub(crate) fn delete(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
-    let mut parser = tuple((tag_no_case(b"DELETE"), sp, mailbox));
+    let mut parser = tuple((tag_no_case(b"DELETE"), context("SP", sp), mailbox));
 
     let (remaining, (_, _, mailbox)) = parser(input)?;
 
@@ -180,7 +219,7 @@ pub(crate) fn delete(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
 
 /// `examine = "EXAMINE" SP mailbox`
 pub(crate) fn examine(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
-    let mut parser = tuple((tag_no_case(b"EXAMINE"), sp, mailbox));
+    let mut parser = tuple((tag_no_case(b"EXAMINE"), context("SP", sp), mailbox));
 
     let (remaining, (_, _, mailbox)) = parser(input)?;
 
@@ -189,7 +228,13 @@ pub(crate) fn examine(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
 
 /// `list = "LIST" SP mailbox SP list-mailbox`
 pub(crate) fn list(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
-    let mut parser = tuple((tag_no_case(b"LIST"), sp, mailbox, sp, list_mailbox));
+    let mut parser = tuple((
+        tag_no_case(b"LIST"),
+        context("SP", sp),
+        mailbox,
+        context("SP", sp),
+        context("list-mailbox", list_mailbox),
+    ));
 
     let (remaining, (_, _, reference, _, mailbox_wildcard)) = parser(input)?;
 
@@ -204,7 +249,13 @@ pub(crate) fn list(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
 
 /// `lsub = "LSUB" SP mailbox SP list-mailbox`
 pub(crate) fn lsub(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
-    let mut parser = tuple((tag_no_case(b"LSUB"), sp, mailbox, sp, list_mailbox));
+    let mut parser = tuple((
+        tag_no_case(b"LSUB"),
+        context("SP", sp),
+        mailbox,
+        context("SP", sp),
+        context("list-mailbox", list_mailbox),
+    ));
 
     let (remaining, (_, _, reference, _, mailbox_wildcard)) = parser(input)?;
 
@@ -221,7 +272,13 @@ pub(crate) fn lsub(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
 ///
 /// Note: Use of INBOX as a destination gives a NO error
 pub(crate) fn rename(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
-    let mut parser = tuple((tag_no_case(b"RENAME"), sp, mailbox, sp, mailbox));
+    let mut parser = tuple((
+        tag_no_case(b"RENAME"),
+        context("SP", sp),
+        mailbox,
+        context("SP", sp),
+        mailbox,
+    ));
 
     let (remaining, (_, _, mailbox, _, new_mailbox)) = parser(input)?;
 
@@ -236,7 +293,7 @@ pub(crate) fn rename(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
 
 /// `select = "SELECT" SP mailbox`
 pub(crate) fn select(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
-    let mut parser = tuple((tag_no_case(b"SELECT"), sp, mailbox));
+    let mut parser = tuple((tag_no_case(b"SELECT"), context("SP", sp), mailbox));
 
     let (remaining, (_, _, mailbox)) = parser(input)?;
 
@@ -247,10 +304,13 @@ pub(crate) fn select(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
 pub(crate) fn status(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
     let mut parser = tuple((
         tag_no_case(b"STATUS"),
-        sp,
+        context("SP", sp),
         mailbox,
-        sp,
-        delimited(tag(b"("), separated_list0(sp, status_att), tag(b")")),
+        context("SP", sp),
+        context(
+            "status-att list",
+            delimited(tag(b"("), separated_list0(sp, status_att), tag(b")")),
+        ),
     ));
 
     let (remaining, (_, _, mailbox, _, item_names)) = parser(input)?;
@@ -266,7 +326,7 @@ pub(crate) fn status(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
 
 /// `subscribe = "SUBSCRIBE" SP mailbox`
 pub(crate) fn subscribe(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
-    let mut parser = tuple((tag_no_case(b"SUBSCRIBE"), sp, mailbox));
+    let mut parser = tuple((tag_no_case(b"SUBSCRIBE"), context("SP", sp), mailbox));
 
     let (remaining, (_, _, mailbox)) = parser(input)?;
 
@@ -275,7 +335,7 @@ pub(crate) fn subscribe(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
 
 /// `unsubscribe = "UNSUBSCRIBE" SP mailbox`
 pub(crate) fn unsubscribe(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
-    let mut parser = tuple((tag_no_case(b"UNSUBSCRIBE"), sp, mailbox));
+    let mut parser = tuple((tag_no_case(b"UNSUBSCRIBE"), context("SP", sp), mailbox));
 
     let (remaining, (_, _, mailbox)) = parser(input)?;
 
@@ -307,7 +367,13 @@ pub(crate) fn command_nonauth(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
 
 /// `login = "LOGIN" SP userid SP password`
 pub(crate) fn login(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
-    let mut parser = tuple((tag_no_case(b"LOGIN"), sp, userid, sp, password));
+    let mut parser = tuple((
+        tag_no_case(b"LOGIN"),
+        context("SP", sp),
+        context("userid", userid),
+        context("SP", sp),
+        context("password", password),
+    ));
 
     let (remaining, (_, _, username, _, password)) = parser(input)?;
 
@@ -378,6 +444,8 @@ pub(crate) fn authenticate(
 ///                   uid /
 ///                   search`
 ///
+/// `command-select =/ "UNSELECT" / move / replace / cancelupdate / convert`
+///
 /// Note: Valid only when in Selected state
 pub(crate) fn command_select(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
     alt((
@@ -391,12 +459,21 @@ pub(crate) fn command_select(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
         search,
         value(CommandBody::Unselect, tag_no_case(b"UNSELECT")),
         r#move,
+        replace,
+        cancelupdate,
+        convert,
     ))(input)
 }
 
 /// `copy = "COPY" SP sequence-set SP mailbox`
 pub(crate) fn copy(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
-    let mut parser = tuple((tag_no_case(b"COPY"), sp, sequence_set, sp, mailbox));
+    let mut parser = tuple((
+        tag_no_case(b"COPY"),
+        context("SP", sp),
+        sequence_set,
+        context("SP", sp),
+        mailbox,
+    ));
 
     let (remaining, (_, _, sequence_set, _, mailbox)) = parser(input)?;
 
@@ -414,104 +491,184 @@ pub(crate) fn copy(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
 ///                                      "FULL" /
 ///                                      "FAST" /
 ///                                      fetch-att / "(" fetch-att *(SP fetch-att) ")")`
+///
+/// `fetch =/ "FETCH" SP sequence-set SP ("ALL" / "FULL" / "FAST" / fetch-att /
+///                                       "(" fetch-att *(SP fetch-att) ")") [fetch-modifiers]` (RFC 4466)
 pub(crate) fn fetch(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
     let mut parser = tuple((
         tag_no_case(b"FETCH"),
-        sp,
+        context("SP", sp),
         sequence_set,
-        sp,
-        alt((
-            value(
-                MacroOrMessageDataItemNames::Macro(Macro::All),
-                tag_no_case(b"ALL"),
-            ),
-            value(
-                MacroOrMessageDataItemNames::Macro(Macro::Fast),
-                tag_no_case(b"FAST"),
-            ),
-            value(
-                MacroOrMessageDataItemNames::Macro(Macro::Full),
-                tag_no_case(b"FULL"),
-            ),
-            map(fetch_att, |fetch_att| {
-                MacroOrMessageDataItemNames::MessageDataItemNames(vec![fetch_att])
-            }),
-            map(
-                delimited(tag(b"("), separated_list0(sp, fetch_att), tag(b")")),
-                MacroOrMessageDataItemNames::MessageDataItemNames,
-            ),
-        )),
+        context("SP", sp),
+        context(
+            "fetch-att",
+            alt((
+                value(
+                    MacroOrMessageDataItemNames::Macro(Macro::All),
+                    tag_no_case(b"ALL"),
+                ),
+                value(
+                    MacroOrMessageDataItemNames::Macro(Macro::Fast),
+                    tag_no_case(b"FAST"),
+                ),
+                value(
+                    MacroOrMessageDataItemNames::Macro(Macro::Full),
+                    tag_no_case(b"FULL"),
+                ),
+                map(fetch_att, |fetch_att| {
+                    MacroOrMessageDataItemNames::MessageDataItemNames(vec![fetch_att])
+                }),
+                map(
+                    context(
+                        "fetch-att list",
+                        delimited(
+                            tag(b"("),
+                            separated_list0(sp, context("fetch-att", fetch_att)),
+                            tag(b")"),
+                        ),
+                    ),
+                    MacroOrMessageDataItemNames::MessageDataItemNames,
+                ),
+            )),
+        ),
+        opt(fetch_modifiers),
     ));
 
-    let (remaining, (_, _, sequence_set, _, macro_or_item_names)) = parser(input)?;
+    let (remaining, (_, _, sequence_set, _, macro_or_item_names, modifiers)) = parser(input)?;
 
     Ok((
         remaining,
         CommandBody::Fetch {
             sequence_set,
             macro_or_item_names,
+            modifiers: modifiers.unwrap_or_default(),
             uid: false,
         },
     ))
 }
 
-/// `store = "STORE" SP sequence-set SP store-att-flags`
+/// ```abnf
+/// store = "STORE" SP sequence-set SP store-att-flags
+///
+/// store =/ "STORE" SP sequence-set SP att-annotate ; RFC 5257
+///
+/// att-annotate = "ANNOTATION" SP "(" entry-att *(SP entry-att) ")"
+/// ```
+///
+/// Note: `att-annotate` has neither a `+`/`-` prefix nor a `.SILENT` suffix.
 pub(crate) fn store(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
-    let mut parser = tuple((tag_no_case(b"STORE"), sp, sequence_set, sp, store_att_flags));
+    // A helper enum to postpone the construction of `CommandBody` in the `alt` combinator below.
+    enum Either<A, B> {
+        Annotation(A),
+        Flags(B),
+    }
 
-    let (remaining, (_, _, sequence_set, _, (kind, response, flags))) = parser(input)?;
+    let mut parser = tuple((
+        tag_no_case(b"STORE"),
+        context("SP", sp),
+        sequence_set,
+        context("SP", sp),
+        context(
+            "store-att-flags",
+            alt((
+                map(
+                    tuple((tag_no_case(b"ANNOTATION"), sp, entry_atts)),
+                    |(_, _, entries)| Either::Annotation(entries),
+                ),
+                map(store_att_flags, Either::Flags),
+            )),
+        ),
+    ));
 
-    Ok((
-        remaining,
-        CommandBody::Store {
+    let (remaining, (_, _, sequence_set, _, either)) = parser(input)?;
+
+    let command_body = match either {
+        Either::Annotation(entries) => CommandBody::StoreAnnotation {
+            sequence_set,
+            entries,
+            uid: false,
+        },
+        Either::Flags((kind, response, attribute)) => CommandBody::Store {
             sequence_set,
             kind,
             response,
-            flags,
+            attribute,
             uid: false,
         },
-    ))
+    };
+
+    Ok((remaining, command_body))
 }
 
-/// `store-att-flags = (["+" / "-"] "FLAGS" [".SILENT"]) SP (flag-list / (flag *(SP flag)))`
+/// ```abnf
+/// store-att-flags = (["+" / "-"] "FLAGS" [".SILENT"]) SP (flag-list / (flag *(SP flag)))
+///
+/// store-att-flags =/ (["+" / "-"] "X-GM-LABELS" [".SILENT"]) SP (label-list / (label *(SP label)))
+/// ```
 pub(crate) fn store_att_flags(
     input: &[u8],
-) -> IMAPResult<&[u8], (StoreType, StoreResponse, Vec<Flag>)> {
+) -> IMAPResult<&[u8], (StoreType, StoreResponse, StoreAttribute)> {
     let mut parser = tuple((
-        tuple((
+        store_type,
+        alt((
+            map(
+                tuple((
+                    tag_no_case(b"FLAGS"),
+                    store_response,
+                    sp,
+                    alt((flag_list, separated_list1(sp, flag))),
+                )),
+                |(_, store_response, _, flags)| (store_response, StoreAttribute::Flags(flags)),
+            ),
             map(
-                opt(alt((
-                    value(StoreType::Add, tag(b"+")),
-                    value(StoreType::Remove, tag(b"-")),
-                ))),
-                |type_| match type_ {
-                    Some(type_) => type_,
-                    None => StoreType::Replace,
+                tuple((
+                    tag_no_case(b"X-GM-LABELS"),
+                    store_response,
+                    sp,
+                    alt((gmail_label_list, separated_list1(sp, gmail_label))),
+                )),
+                |(_, store_response, _, labels)| {
+                    (store_response, StoreAttribute::GmailLabels(labels))
                 },
             ),
-            tag_no_case(b"FLAGS"),
-            map(opt(tag_no_case(b".SILENT")), |x| match x {
-                Some(_) => StoreResponse::Silent,
-                None => StoreResponse::Answer,
-            }),
         )),
-        sp,
-        alt((flag_list, separated_list1(sp, flag))),
     ));
 
-    let (remaining, ((store_type, _, store_response), _, flag_list)) = parser(input)?;
+    let (remaining, (store_type, (store_response, attribute))) = parser(input)?;
 
-    Ok((remaining, (store_type, store_response, flag_list)))
+    Ok((remaining, (store_type, store_response, attribute)))
 }
 
-/// `uid = "UID" SP (copy / fetch / search / store)`
+/// `["+" / "-"]`
+pub(crate) fn store_type(input: &[u8]) -> IMAPResult<&[u8], StoreType> {
+    map(
+        opt(alt((
+            value(StoreType::Add, tag(b"+")),
+            value(StoreType::Remove, tag(b"-")),
+        ))),
+        |type_| match type_ {
+            Some(type_) => type_,
+            None => StoreType::Replace,
+        },
+    )(input)
+}
+
+/// `[".SILENT"]`
+pub(crate) fn store_response(input: &[u8]) -> IMAPResult<&[u8], StoreResponse> {
+    map(opt(tag_no_case(b".SILENT")), |x| match x {
+        Some(_) => StoreResponse::Silent,
+        None => StoreResponse::Answer,
+    })(input)
+}
+
+/// `uid = "UID" SP (copy / fetch / search / store / move / replace / convert)`
 ///
 /// Note: Unique identifiers used instead of message sequence numbers
 pub(crate) fn uid(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
     let mut parser = tuple((
         tag_no_case(b"UID"),
-        sp,
-        alt((copy, fetch, search, store, r#move)),
+        context("SP", sp),
+        alt((copy, fetch, search, store, r#move, replace, convert)),
     ));
 
     let (remaining, (_, _, mut cmd)) = parser(input)?;
@@ -521,7 +678,10 @@ pub(crate) fn uid(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
         | CommandBody::Fetch { ref mut uid, .. }
         | CommandBody::Search { ref mut uid, .. }
         | CommandBody::Store { ref mut uid, .. }
-        | CommandBody::Move { ref mut uid, .. } => *uid = true,
+        | CommandBody::StoreAnnotation { ref mut uid, .. }
+        | CommandBody::Move { ref mut uid, .. }
+        | CommandBody::Replace { ref mut uid, .. }
+        | CommandBody::Convert { ref mut uid, .. } => *uid = true,
         _ => unreachable!(),
     }
 
@@ -590,6 +750,15 @@ mod tests {
                 },
                 "BODY.PEEK[TEXT]<42.1337>???",
             ),
+            (MessageDataItemName::Preview { lazy: false }, "PREVIEW???"),
+            (
+                MessageDataItemName::Preview { lazy: true },
+                "PREVIEW (LAZY)???",
+            ),
+            (
+                MessageDataItemName::Preview { lazy: true },
+                "preview (lazy)???",
+            ),
         ];
 
         let expected_remainder = "???".as_bytes();
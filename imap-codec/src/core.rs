@@ -1,9 +1,5 @@
 use std::{borrow::Cow, num::NonZeroU32, str::from_utf8};
 
-#[cfg(not(feature = "quirk_crlf_relaxed"))]
-use abnf_core::streaming::crlf;
-#[cfg(feature = "quirk_crlf_relaxed")]
-use abnf_core::streaming::crlf_relaxed as crlf;
 use abnf_core::{is_alpha, is_digit, streaming::dquote};
 use base64::{engine::general_purpose::STANDARD as _base64, Engine};
 use imap_types::{
@@ -24,7 +20,10 @@ use nom::{
     sequence::{delimited, terminated, tuple},
 };
 
-use crate::decode::{IMAPErrorKind, IMAPParseError, IMAPResult};
+use crate::decode::{
+    check_length, crlf, limit_exceeded, limits, quirks, IMAPErrorKind, IMAPParseError, IMAPResult,
+    Limit,
+};
 
 // ----- number -----
 
@@ -155,11 +154,17 @@ pub(crate) fn literal(input: &[u8]) -> IMAPResult<&[u8], Literal> {
         crlf,
     )(input)?;
 
+    // Reject the literal before the data is announced or received.
+    if length > limits().max_literal_length {
+        return Err(limit_exceeded(input, Limit::LiteralLength));
+    }
+
     // Signal that an continuation request could be required.
     // Note: This doesn't trigger when there is data following the literal prefix.
     if remaining.is_empty() {
         return Err(nom::Err::Failure(IMAPParseError {
             input,
+            context: Vec::new(),
             kind: IMAPErrorKind::Literal {
                 // We don't know the tag here and rely on an upper parser, e.g., `command` to fill this in.
                 tag: None,
@@ -179,6 +184,7 @@ pub(crate) fn literal(input: &[u8]) -> IMAPResult<&[u8], Literal> {
         }
         Err(_) => Err(nom::Err::Failure(IMAPParseError {
             input,
+            context: Vec::new(),
             kind: IMAPErrorKind::LiteralContainsNull,
         })),
     }
@@ -188,6 +194,13 @@ pub(crate) fn literal(input: &[u8]) -> IMAPResult<&[u8], Literal> {
 
 /// `astring = 1*ASTRING-CHAR / string`
 pub(crate) fn astring(input: &[u8]) -> IMAPResult<&[u8], AString> {
+    check_length(
+        input,
+        is_astring_char,
+        limits().max_atom_length,
+        Limit::AtomLength,
+    )?;
+
     alt((
         map(take_while1(is_astring_char), |bytes: &[u8]| {
             // # Safety
@@ -205,6 +218,13 @@ pub(crate) fn astring(input: &[u8]) -> IMAPResult<&[u8], AString> {
 
 /// `atom = 1*ATOM-CHAR`
 pub(crate) fn atom(input: &[u8]) -> IMAPResult<&[u8], Atom> {
+    check_length(
+        input,
+        is_atom_char,
+        limits().max_atom_length,
+        Limit::AtomLength,
+    )?;
+
     let parser = take_while1(is_atom_char);
 
     let (remaining, parsed_atom) = parser(input)?;
@@ -239,13 +259,37 @@ pub(crate) fn nil(input: &[u8]) -> IMAPResult<&[u8], &[u8]> {
 // ----- text -----
 
 /// `text = 1*TEXT-CHAR`
+///
+/// # Quirks
+///
+/// * 8-bit bytes are accepted with [`DecodeQuirks::eight_bit_text`](crate::decode::DecodeQuirks::eight_bit_text).
+///   Every non-ASCII character (or invalid UTF-8 sequence) is rectified to `?`.
 pub(crate) fn text(input: &[u8]) -> IMAPResult<&[u8], Text> {
-    map(take_while1(is_text_char), |bytes|
-        // # Safety
-        // 
-        // `is_text_char` makes sure that the sequence of bytes
-        // is always valid ASCII. Thus, it is also valid UTF-8.
-        Text::unvalidated(from_utf8(bytes).unwrap()))(input)
+    if !quirks().eight_bit_text {
+        return map(take_while1(is_text_char), |bytes|
+            // # Safety
+            //
+            // `is_text_char` makes sure that the sequence of bytes
+            // is always valid ASCII. Thus, it is also valid UTF-8.
+            Text::unvalidated(from_utf8(bytes).unwrap()))(input);
+    }
+
+    map(
+        take_while1(|byte| is_text_char(byte) || byte >= 0x80),
+        |bytes: &[u8]| match from_utf8(bytes) {
+            Ok(text) if text.is_ascii() => Text::unvalidated(text),
+            _ => {
+                log::warn!("Rectified 8-bit text");
+
+                Text::unvalidated(
+                    String::from_utf8_lossy(bytes)
+                        .chars()
+                        .map(|c| if c.is_ascii() { c } else { '?' })
+                        .collect::<String>(),
+                )
+            }
+        },
+    )(input)
 }
 
 // ----- base64 -----
@@ -281,7 +325,16 @@ pub(crate) fn charset(input: &[u8]) -> IMAPResult<&[u8], Charset> {
 
 /// `tag = 1*<any ASTRING-CHAR except "+">`
 pub(crate) fn tag_imap(input: &[u8]) -> IMAPResult<&[u8], Tag> {
-    map(take_while1(|b| is_astring_char(b) && b != b'+'), |val| {
+    let is_tag_char = |b| is_astring_char(b) && b != b'+';
+
+    check_length(
+        input,
+        is_tag_char,
+        limits().max_tag_length,
+        Limit::TagLength,
+    )?;
+
+    map(take_while1(is_tag_char), |val| {
         // # Safety
         //
         // `is_astring_char` ensures that `val` is UTF-8.
@@ -10,11 +10,11 @@ use nom::{
     branch::alt,
     bytes::streaming::{tag, tag_no_case, take_while_m_n},
     character::streaming::char,
-    combinator::{map, map_res, value},
+    combinator::{map, map_res, opt, recognize, value},
     sequence::{delimited, preceded, tuple},
 };
 
-use crate::decode::{IMAPErrorKind, IMAPParseError, IMAPResult};
+use crate::decode::{quirks, IMAPErrorKind, IMAPParseError, IMAPResult};
 
 /// ```abnf
 /// date = date-text / DQUOTE date-text DQUOTE
@@ -98,24 +98,46 @@ pub(crate) fn time(input: &[u8]) -> IMAPResult<&[u8], Option<NaiveTime>> {
 ///              zone
 ///             DQUOTE
 /// ```
+///
+/// # Quirks
+///
+/// * A leading day-of-week (in any case), and `SP` instead of `-` between day, month, and year
+///   are accepted with
+///   [`DecodeQuirks::relaxed_date_time`](crate::decode::DecodeQuirks::relaxed_date_time).
 pub(crate) fn date_time(input: &[u8]) -> IMAPResult<&[u8], DateTime> {
-    let mut parser = delimited(
-        dquote,
-        tuple((
-            date_day_fixed,
-            tag(b"-"),
-            date_month,
-            tag(b"-"),
-            date_year,
-            sp,
-            time,
-            sp,
-            zone,
-        )),
-        dquote,
-    );
-
-    let (remaining, (d, _, m, _, y, _, time, _, zone)) = parser(input)?;
+    let (remaining, (d, _, m, _, y, _, time, _, zone)) = if quirks().relaxed_date_time {
+        delimited(
+            tuple((dquote, opt(day_of_week))),
+            tuple((
+                alt((date_day_fixed, date_day)),
+                date_separator,
+                date_month,
+                date_separator,
+                date_year,
+                sp,
+                time,
+                sp,
+                zone,
+            )),
+            dquote,
+        )(input)?
+    } else {
+        delimited(
+            dquote,
+            tuple((
+                date_day_fixed,
+                tag(b"-"),
+                date_month,
+                tag(b"-"),
+                date_year,
+                sp,
+                time,
+                sp,
+                zone,
+            )),
+            dquote,
+        )(input)?
+    };
 
     let date = ChronoNaiveDate::from_ymd_opt(y.into(), m.into(), d.into());
 
@@ -128,17 +150,44 @@ pub(crate) fn date_time(input: &[u8]) -> IMAPResult<&[u8], DateTime> {
             } else {
                 Err(nom::Err::Failure(IMAPParseError {
                     input,
+                    context: Vec::new(),
                     kind: IMAPErrorKind::BadDateTime,
                 }))
             }
         }
         _ => Err(nom::Err::Failure(IMAPParseError {
             input,
+            context: Vec::new(),
             kind: IMAPErrorKind::BadDateTime,
         })),
     }
 }
 
+/// `day-name ["," [SP]] / day-name SP` (used with `DecodeQuirks::relaxed_date_time`)
+///
+/// ```abnf
+/// day-name = "Mon" / "Tue" / "Wed" / "Thu" / "Fri" / "Sat" / "Sun"
+/// ```
+fn day_of_week(input: &[u8]) -> IMAPResult<&[u8], &[u8]> {
+    recognize(tuple((
+        alt((
+            tag_no_case(b"Mon"),
+            tag_no_case(b"Tue"),
+            tag_no_case(b"Wed"),
+            tag_no_case(b"Thu"),
+            tag_no_case(b"Fri"),
+            tag_no_case(b"Sat"),
+            tag_no_case(b"Sun"),
+        )),
+        alt((recognize(tuple((tag(b","), opt(sp)))), recognize(sp))),
+    )))(input)
+}
+
+/// `"-" / SP` (used with `DecodeQuirks::relaxed_date_time`)
+fn date_separator(input: &[u8]) -> IMAPResult<&[u8], &[u8]> {
+    alt((tag(b"-"), tag(b" ")))(input)
+}
+
 /// Fixed-format version of date-day.
 ///
 /// ```abnf
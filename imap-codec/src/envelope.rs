@@ -1,19 +1,23 @@
+use std::str::from_utf8;
+
 use abnf_core::streaming::sp;
 use imap_types::{
-    core::NString,
+    core::{IString, NString, Quoted},
     envelope::{Address, Envelope},
+    utils::indicators::is_text_char,
 };
 use nom::{
     branch::alt,
     bytes::streaming::tag,
     combinator::map,
-    multi::many1,
+    error::{context, ErrorKind},
     sequence::{delimited, tuple},
+    Needed,
 };
 
 use crate::{
     core::{nil, nstring},
-    decode::IMAPResult,
+    decode::{many1, quirks, IMAPErrorKind, IMAPParseError, IMAPResult},
 };
 
 /// ```abnf
@@ -31,30 +35,33 @@ use crate::{
 ///            ")"
 /// ```
 pub(crate) fn envelope(input: &[u8]) -> IMAPResult<&[u8], Envelope> {
-    let mut parser = delimited(
-        tag(b"("),
-        tuple((
-            env_date,
-            sp,
-            env_subject,
-            sp,
-            env_from,
-            sp,
-            env_sender,
-            sp,
-            env_reply_to,
-            sp,
-            env_to,
-            sp,
-            env_cc,
-            sp,
-            env_bcc,
-            sp,
-            env_in_reply_to,
-            sp,
-            env_message_id,
-        )),
-        tag(b")"),
+    let mut parser = context(
+        "envelope",
+        delimited(
+            tag(b"("),
+            tuple((
+                env_date,
+                sp,
+                env_subject,
+                sp,
+                env_from,
+                sp,
+                env_sender,
+                sp,
+                env_reply_to,
+                sp,
+                env_to,
+                sp,
+                env_cc,
+                sp,
+                env_bcc,
+                sp,
+                env_in_reply_to,
+                sp,
+                env_message_id,
+            )),
+            tag(b")"),
+        ),
     );
 
     let (
@@ -99,16 +106,18 @@ pub(crate) fn envelope(input: &[u8]) -> IMAPResult<&[u8], Envelope> {
     ))
 }
 
-#[inline]
 /// `env-date = nstring`
+///
+/// See [`env_nstring`] for quirks.
 pub(crate) fn env_date(input: &[u8]) -> IMAPResult<&[u8], NString> {
-    nstring(input)
+    env_nstring(input, false)
 }
 
-#[inline]
 /// `env-subject = nstring`
+///
+/// See [`env_nstring`] for quirks.
 pub(crate) fn env_subject(input: &[u8]) -> IMAPResult<&[u8], NString> {
-    nstring(input)
+    env_nstring(input, false)
 }
 
 /// `env-from = "(" 1*address ")" / nil`
@@ -159,16 +168,101 @@ pub(crate) fn env_bcc(input: &[u8]) -> IMAPResult<&[u8], Vec<Address>> {
     ))(input)
 }
 
-#[inline]
 /// `env-in-reply-to = nstring`
+///
+/// See [`env_nstring`] for quirks.
 pub(crate) fn env_in_reply_to(input: &[u8]) -> IMAPResult<&[u8], NString> {
-    nstring(input)
+    env_nstring(input, false)
 }
 
-#[inline]
 /// `env-message-id = nstring`
+///
+/// See [`env_nstring`] for quirks.
 pub(crate) fn env_message_id(input: &[u8]) -> IMAPResult<&[u8], NString> {
-    nstring(input)
+    env_nstring(input, true)
+}
+
+/// `nstring` of an envelope field
+///
+/// # Quirks
+///
+/// * An unquoted string (that may contain spaces) is accepted with
+///   [`DecodeQuirks::unquoted_envelope_strings`](crate::decode::DecodeQuirks::unquoted_envelope_strings).
+///   It ends before the next field or, when `last` is set, before the closing `)` of the envelope.
+fn env_nstring(input: &[u8], last: bool) -> IMAPResult<&[u8], NString> {
+    if !quirks().unquoted_envelope_strings || !is_unquoted(input) {
+        return nstring(input);
+    }
+
+    for (position, byte) in input.iter().enumerate() {
+        if !is_text_char(*byte) {
+            return Err(nom::Err::Error(IMAPParseError {
+                input: &input[position..],
+                context: Vec::new(),
+                kind: IMAPErrorKind::Nom(ErrorKind::Verify),
+            }));
+        }
+
+        let end = if last {
+            *byte == b')'
+        } else if *byte == b' ' {
+            match starts_field(&input[position + 1..]) {
+                Some(starts_field) => starts_field,
+                None => break,
+            }
+        } else {
+            false
+        };
+
+        if end {
+            log::warn!("Rectified unquoted string in envelope");
+
+            // # Safety
+            //
+            // `is_text_char` makes sure that the sequence of bytes
+            // is always valid ASCII. Thus, it is also valid UTF-8.
+            let unquoted = from_utf8(&input[..position]).unwrap();
+
+            return Ok((
+                &input[position..],
+                NString(Some(IString::Quoted(Quoted::unvalidated(unquoted)))),
+            ));
+        }
+    }
+
+    Err(nom::Err::Incomplete(Needed::Unknown))
+}
+
+/// Whether `input` starts with an unquoted string, i.e., neither with a string nor with `NIL`.
+fn is_unquoted(input: &[u8]) -> bool {
+    match input.first() {
+        None | Some(b'"' | b'{' | b'(' | b')' | b' ') => false,
+        Some(_) => !matches!(starts_nil(input), Some(true) | None),
+    }
+}
+
+/// Whether `input` starts with the next field (after `SP`), or `None` if we can't know yet.
+fn starts_field(input: &[u8]) -> Option<bool> {
+    match input.first() {
+        None => None,
+        Some(b'"' | b'{' | b'(') => Some(true),
+        Some(_) => starts_nil(input),
+    }
+}
+
+/// Whether `input` starts with `NIL` (followed by `SP` or `)`), or `None` if we can't know yet.
+fn starts_nil(input: &[u8]) -> Option<bool> {
+    let length = input.len().min(3);
+
+    if !input[..length].eq_ignore_ascii_case(&b"NIL"[..length]) {
+        return Some(false);
+    }
+
+    match input.get(3) {
+        None => None,
+        Some(b' ' | b')') => Some(true),
+        Some(_) => Some(false),
+    }
 }
 
 /// `address = "("
@@ -239,9 +333,8 @@ pub(crate) fn addr_host(input: &[u8]) -> IMAPResult<&[u8], NString> {
 
 #[cfg(test)]
 mod tests {
-    use imap_types::core::{IString, NString};
-
     use super::*;
+    use crate::decode::{with_quirks, DecodeQuirks};
 
     #[test]
     fn test_parse_address() {
@@ -259,4 +352,51 @@ mod tests {
         );
         assert_eq!(rem, b"");
     }
+
+    #[test]
+    fn test_parse_unquoted_envelope_string() {
+        let quoted = |value| NString(Some(IString::Quoted(Quoted::unvalidated(value))));
+
+        let test = b"(NIL Nile cruise NIL NIL NIL NIL NIL NIL NIL NIL)";
+        assert!(envelope(test).is_err());
+
+        with_quirks(DecodeQuirks::lenient(), || {
+            assert_eq!(envelope(test).unwrap().1.subject, quoted("Nile cruise"));
+
+            let tests = [
+                (
+                    b"Nile cruise NIL ".as_ref(),
+                    quoted("Nile cruise"),
+                    b" NIL ".as_ref(),
+                ),
+                (
+                    b"a (b) \"c\" ".as_ref(),
+                    quoted("a"),
+                    b" (b) \"c\" ".as_ref(),
+                ),
+                (b"NIL NIL ".as_ref(), NString(None), b" NIL ".as_ref()),
+                (b"\"quoted\" ".as_ref(), quoted("quoted"), b" ".as_ref()),
+            ];
+
+            for (test, expected, remaining) in tests {
+                assert_eq!(env_subject(test).unwrap(), (remaining, expected));
+            }
+
+            assert_eq!(
+                env_message_id(b"<1@x> y)").unwrap(),
+                (b")".as_ref(), quoted("<1@x> y"))
+            );
+
+            // We can't know where the string ends yet.
+            assert!(matches!(
+                env_subject(b"Nile cruise"),
+                Err(nom::Err::Incomplete(_))
+            ));
+            assert!(matches!(
+                env_subject(b"Nile cruise N"),
+                Err(nom::Err::Incomplete(_))
+            ));
+            assert!(matches!(env_subject(b"Nile\r\n"), Err(nom::Err::Error(_))));
+        });
+    }
 }
@@ -0,0 +1,374 @@
+//! IMAP ANNOTATE Extension
+
+use std::io::Write;
+
+use abnf_core::streaming::sp;
+use imap_types::{
+    core::NonEmptyVec,
+    extensions::annotate::{AnnotationData, AnnotationEntry, AnnotationValue},
+    fetch::MessageDataItemName,
+    mailbox::ListMailbox,
+    response::Code,
+};
+use nom::{
+    branch::alt,
+    bytes::streaming::{tag, tag_no_case},
+    combinator::{map, value},
+    sequence::{delimited, preceded, separated_pair, tuple},
+};
+
+use crate::{
+    core::{nstring, string},
+    decode::{separated_list1, IMAPResult},
+    encode::{utils::join_serializable, EncodeContext, EncodeIntoContext},
+    mailbox::list_mailbox,
+};
+
+/// ```abnf
+/// fetch-annotation = "ANNOTATION" SP "(" entries SP attribs ")"
+///
+/// entries = entry-match / "(" entry-match *(SP entry-match) ")"
+///
+/// attribs = attrib-match / "(" attrib-match *(SP attrib-match) ")"
+///
+/// entry-match = list-mailbox
+///
+/// attrib-match = list-mailbox
+/// ```
+pub(crate) fn fetch_annotation(input: &[u8]) -> IMAPResult<&[u8], MessageDataItemName> {
+    let mut parser = preceded(
+        tag_no_case(b"ANNOTATION "),
+        delimited(
+            tag(b"("),
+            separated_pair(one_or_more_match, sp, one_or_more_match),
+            tag(b")"),
+        ),
+    );
+
+    let (remaining, (entries, attributes)) = parser(input)?;
+
+    Ok((
+        remaining,
+        MessageDataItemName::Annotation {
+            entries,
+            attributes,
+        },
+    ))
+}
+
+fn one_or_more_match(input: &[u8]) -> IMAPResult<&[u8], NonEmptyVec<ListMailbox>> {
+    alt((
+        map(
+            delimited(tag(b"("), separated_list1(sp, list_mailbox), tag(b")")),
+            // Safety: Safe because we use `separated_list1` above.
+            NonEmptyVec::unvalidated,
+        ),
+        map(list_mailbox, NonEmptyVec::from),
+    ))(input)
+}
+
+/// ```abnf
+/// fetch-annotation-resp = "ANNOTATION" SP
+///                         ("(" entry-att *(SP entry-att) ")" /
+///                          "(" entry *(SP entry) ")")
+/// ```
+pub(crate) fn fetch_annotation_resp(input: &[u8]) -> IMAPResult<&[u8], AnnotationData> {
+    preceded(
+        tag_no_case(b"ANNOTATION "),
+        alt((
+            map(entry_atts, AnnotationData::Values),
+            map(
+                delimited(tag(b"("), separated_list1(sp, string), tag(b")")),
+                // Safety: Safe because we use `separated_list1` above.
+                |entries| AnnotationData::Entries(NonEmptyVec::unvalidated(entries)),
+            ),
+        )),
+    )(input)
+}
+
+/// `"(" entry-att *(SP entry-att) ")"`
+pub(crate) fn entry_atts(input: &[u8]) -> IMAPResult<&[u8], NonEmptyVec<AnnotationEntry>> {
+    map(
+        delimited(tag(b"("), separated_list1(sp, entry_att), tag(b")")),
+        // Safety: Safe because we use `separated_list1` above.
+        NonEmptyVec::unvalidated,
+    )(input)
+}
+
+/// ```abnf
+/// entry-att = entry SP "(" att-value *(SP att-value) ")"
+///
+/// entry = string
+/// ```
+pub(crate) fn entry_att(input: &[u8]) -> IMAPResult<&[u8], AnnotationEntry> {
+    let mut parser = separated_pair(
+        string,
+        sp,
+        delimited(tag(b"("), separated_list1(sp, att_value), tag(b")")),
+    );
+
+    let (remaining, (entry, values)) = parser(input)?;
+
+    Ok((
+        remaining,
+        AnnotationEntry {
+            entry,
+            // Safety: Safe because we use `separated_list1` above.
+            values: NonEmptyVec::unvalidated(values),
+        },
+    ))
+}
+
+/// ```abnf
+/// att-value = attrib SP value
+///
+/// attrib = string
+///
+/// value = nstring
+/// ```
+///
+/// Note: `literal8` is not supported as `value`.
+pub(crate) fn att_value(input: &[u8]) -> IMAPResult<&[u8], AnnotationValue> {
+    map(separated_pair(string, sp, nstring), |(attribute, value)| {
+        AnnotationValue { attribute, value }
+    })(input)
+}
+
+/// `resp-text-code =/ "ANNOTATE" SP ("TOOBIG" / "TOOMANY")`
+pub(crate) fn annotate_code(input: &[u8]) -> IMAPResult<&[u8], Code> {
+    preceded(
+        tuple((tag_no_case(b"ANNOTATE"), sp)),
+        alt((
+            value(Code::AnnotateTooBig, tag_no_case(b"TOOBIG")),
+            value(Code::AnnotateTooMany, tag_no_case(b"TOOMANY")),
+        )),
+    )(input)
+}
+
+/// Encodes `entry-match / "(" entry-match *(SP entry-match) ")"`.
+pub(crate) fn encode_one_or_more_match(
+    matches: &NonEmptyVec<ListMailbox>,
+    ctx: &mut EncodeContext,
+) -> std::io::Result<()> {
+    match matches.as_ref() {
+        [single] => single.encode_ctx(ctx),
+        matches => {
+            ctx.write_all(b"(")?;
+            join_serializable(matches, b" ", ctx)?;
+            ctx.write_all(b")")
+        }
+    }
+}
+
+impl<'a> EncodeIntoContext for AnnotationEntry<'a> {
+    fn encode_ctx(&self, ctx: &mut EncodeContext) -> std::io::Result<()> {
+        self.entry.encode_ctx(ctx)?;
+        ctx.write_all(b" (")?;
+        join_serializable(self.values.as_ref(), b" ", ctx)?;
+        ctx.write_all(b")")
+    }
+}
+
+impl<'a> EncodeIntoContext for AnnotationValue<'a> {
+    fn encode_ctx(&self, ctx: &mut EncodeContext) -> std::io::Result<()> {
+        self.attribute.encode_ctx(ctx)?;
+        ctx.write_all(b" ")?;
+        self.value.encode_ctx(ctx)
+    }
+}
+
+impl<'a> EncodeIntoContext for AnnotationData<'a> {
+    fn encode_ctx(&self, ctx: &mut EncodeContext) -> std::io::Result<()> {
+        ctx.write_all(b"(")?;
+        match self {
+            Self::Values(entries) => join_serializable(entries.as_ref(), b" ", ctx)?,
+            Self::Entries(entries) => join_serializable(entries.as_ref(), b" ", ctx)?,
+        }
+        ctx.write_all(b")")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroU32;
+
+    use imap_types::{
+        command::{Command, CommandBody},
+        core::{AString, IString, NString, Tag, Text},
+        fetch::MessageDataItem,
+        response::{Capability, Data, Response, Status},
+        search::SearchKey,
+    };
+
+    use super::*;
+    use crate::{
+        decode::Decoder,
+        testing::{kat_inverse_command, kat_inverse_response},
+        CommandCodec,
+    };
+
+    fn comment(value: &str) -> NonEmptyVec<AnnotationEntry> {
+        NonEmptyVec::from(AnnotationEntry {
+            entry: IString::try_from("/comment").unwrap(),
+            values: NonEmptyVec::from(AnnotationValue::new(
+                IString::try_from("value.priv").unwrap(),
+                NString::try_from(value).unwrap(),
+            )),
+        })
+    }
+
+    #[test]
+    fn test_kat_inverse_command_annotate() {
+        kat_inverse_command(&[
+            (
+                b"A FETCH 1 (ANNOTATION (/comment value.priv))\r\n".as_ref(),
+                b"".as_ref(),
+                Command::new(
+                    "A",
+                    CommandBody::fetch(
+                        1,
+                        vec![MessageDataItemName::Annotation {
+                            entries: NonEmptyVec::from(ListMailbox::try_from("/comment").unwrap()),
+                            attributes: NonEmptyVec::from(
+                                ListMailbox::try_from("value.priv").unwrap(),
+                            ),
+                        }],
+                        false,
+                    )
+                    .unwrap(),
+                )
+                .unwrap(),
+            ),
+            (
+                b"A FETCH 1 (ANNOTATION ((/comment /altsubject) (value.priv value.shared)))\r\n"
+                    .as_ref(),
+                b"".as_ref(),
+                Command::new(
+                    "A",
+                    CommandBody::fetch(
+                        1,
+                        vec![MessageDataItemName::Annotation {
+                            entries: vec![
+                                ListMailbox::try_from("/comment").unwrap(),
+                                ListMailbox::try_from("/altsubject").unwrap(),
+                            ]
+                            .try_into()
+                            .unwrap(),
+                            attributes: vec![
+                                ListMailbox::try_from("value.priv").unwrap(),
+                                ListMailbox::try_from("value.shared").unwrap(),
+                            ]
+                            .try_into()
+                            .unwrap(),
+                        }],
+                        false,
+                    )
+                    .unwrap(),
+                )
+                .unwrap(),
+            ),
+            (
+                b"A STORE 1 ANNOTATION (\"/comment\" (\"value.priv\" \"My comment\"))\r\n".as_ref(),
+                b"".as_ref(),
+                Command::new(
+                    "A",
+                    CommandBody::store_annotation(1, comment("My comment"), false).unwrap(),
+                )
+                .unwrap(),
+            ),
+            (
+                b"A SEARCH ANNOTATION /comment value.priv urgent\r\n".as_ref(),
+                b"".as_ref(),
+                Command::new(
+                    "A",
+                    CommandBody::search(
+                        None,
+                        SearchKey::Annotation {
+                            entry: ListMailbox::try_from("/comment").unwrap(),
+                            attribute: ListMailbox::try_from("value.priv").unwrap(),
+                            value: AString::try_from("urgent").unwrap(),
+                        },
+                        false,
+                    ),
+                )
+                .unwrap(),
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_store_annotation_without_prefix_or_suffix() {
+        for input in [
+            b"A STORE 1 +ANNOTATION (\"/comment\" (\"value.priv\" \"x\"))\r\n".as_ref(),
+            b"A STORE 1 -ANNOTATION (\"/comment\" (\"value.priv\" \"x\"))\r\n",
+            b"A STORE 1 ANNOTATION.SILENT (\"/comment\" (\"value.priv\" \"x\"))\r\n",
+        ] {
+            assert!(CommandCodec::default().decode(input).is_err());
+        }
+    }
+
+    #[test]
+    fn test_kat_inverse_response_annotate() {
+        kat_inverse_response(&[
+            (
+                b"* 1 FETCH (ANNOTATION (\"/comment\" (\"value.priv\" \"My comment\")))\r\n"
+                    .as_ref(),
+                b"".as_ref(),
+                Response::Data(Data::Fetch {
+                    seq: NonZeroU32::new(1).unwrap(),
+                    items: NonEmptyVec::from(MessageDataItem::Annotation(AnnotationData::Values(
+                        comment("My comment"),
+                    ))),
+                }),
+            ),
+            (
+                b"* 1 FETCH (ANNOTATION (\"/comment\" \"/altsubject\"))\r\n".as_ref(),
+                b"".as_ref(),
+                Response::Data(Data::Fetch {
+                    seq: NonZeroU32::new(1).unwrap(),
+                    items: NonEmptyVec::from(MessageDataItem::Annotation(AnnotationData::Entries(
+                        vec![
+                            IString::try_from("/comment").unwrap(),
+                            IString::try_from("/altsubject").unwrap(),
+                        ]
+                        .try_into()
+                        .unwrap(),
+                    ))),
+                }),
+            ),
+            (
+                b"A NO [ANNOTATE TOOBIG] ...\r\n".as_ref(),
+                b"".as_ref(),
+                Response::Status(
+                    Status::no(
+                        Some(Tag::try_from("A").unwrap()),
+                        Some(Code::AnnotateTooBig),
+                        Text::try_from("...").unwrap(),
+                    )
+                    .unwrap(),
+                ),
+            ),
+            (
+                b"A NO [ANNOTATE TOOMANY] ...\r\n".as_ref(),
+                b"".as_ref(),
+                Response::Status(
+                    Status::no(
+                        Some(Tag::try_from("A").unwrap()),
+                        Some(Code::AnnotateTooMany),
+                        Text::try_from("...").unwrap(),
+                    )
+                    .unwrap(),
+                ),
+            ),
+            (
+                b"* CAPABILITY IMAP4REV1 ANNOTATE-EXPERIMENT-1\r\n".as_ref(),
+                b"".as_ref(),
+                Response::Data(Data::Capability(
+                    vec![Capability::Imap4Rev1, Capability::AnnotateExperiment1]
+                        .try_into()
+                        .unwrap(),
+                )),
+            ),
+        ]);
+    }
+}
@@ -0,0 +1,251 @@
+//! Contexts for IMAP4
+
+use std::io::Write;
+
+use abnf_core::streaming::{dquote, sp};
+use imap_types::{
+    command::CommandBody,
+    core::NonEmptyVec,
+    extensions::{context::ContextPosition, esearch::SearchReturnData},
+    response::Code,
+};
+use nom::{
+    bytes::streaming::{tag, tag_no_case},
+    combinator::map,
+    sequence::{delimited, preceded, separated_pair},
+};
+
+use crate::{
+    core::{number, tag_imap},
+    decode::{many1, separated_list1, IMAPResult},
+    encode::{EncodeContext, EncodeIntoContext},
+    sequence::sequence_set,
+};
+
+/// ```abnf
+/// cancelupdate = "CANCELUPDATE" 1*(SP quoted)
+/// ```
+///
+/// Note: We require every `quoted` to be a valid tag.
+pub(crate) fn cancelupdate(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
+    let mut parser = preceded(
+        tag_no_case(b"CANCELUPDATE"),
+        many1(preceded(sp, delimited(dquote, tag_imap, dquote))),
+    );
+
+    let (remaining, tags) = parser(input)?;
+
+    Ok((
+        remaining,
+        CommandBody::CancelUpdate {
+            // Safety: Safe because we use `many1` above.
+            tags: NonEmptyVec::unvalidated(tags),
+        },
+    ))
+}
+
+/// ```abnf
+/// context-position SP sequence-set
+///
+/// context-position = number
+/// ```
+pub(crate) fn context_position(input: &[u8]) -> IMAPResult<&[u8], ContextPosition> {
+    map(
+        separated_pair(number, sp, sequence_set),
+        |(position, sequence_set)| ContextPosition {
+            position,
+            sequence_set,
+        },
+    )(input)
+}
+
+/// ```abnf
+/// "(" context-position SP sequence-set *(SP context-position SP sequence-set) ")"
+/// ```
+fn context_positions(input: &[u8]) -> IMAPResult<&[u8], NonEmptyVec<ContextPosition>> {
+    map(
+        delimited(tag(b"("), separated_list1(sp, context_position), tag(b")")),
+        // Safety: Safe because we use `separated_list1` above.
+        NonEmptyVec::unvalidated,
+    )(input)
+}
+
+/// ```abnf
+/// ret-data-addto = "ADDTO" SP "(" context-position SP sequence-set
+///                  *(SP context-position SP sequence-set) ")"
+/// ```
+pub(crate) fn ret_data_addto(input: &[u8]) -> IMAPResult<&[u8], SearchReturnData> {
+    map(
+        preceded(tag_no_case(b"ADDTO "), context_positions),
+        SearchReturnData::AddTo,
+    )(input)
+}
+
+/// ```abnf
+/// ret-data-removefrom = "REMOVEFROM" SP "(" context-position SP sequence-set
+///                       *(SP context-position SP sequence-set) ")"
+/// ```
+pub(crate) fn ret_data_removefrom(input: &[u8]) -> IMAPResult<&[u8], SearchReturnData> {
+    map(
+        preceded(tag_no_case(b"REMOVEFROM "), context_positions),
+        SearchReturnData::RemoveFrom,
+    )(input)
+}
+
+/// ```abnf
+/// "NOUPDATE" SP quoted
+/// ```
+///
+/// Note: We require the `quoted` to be a valid tag.
+pub(crate) fn noupdate(input: &[u8]) -> IMAPResult<&[u8], Code> {
+    map(
+        preceded(
+            tag_no_case(b"NOUPDATE "),
+            delimited(dquote, tag_imap, dquote),
+        ),
+        |tag| Code::NoUpdate { tag },
+    )(input)
+}
+
+impl EncodeIntoContext for ContextPosition {
+    fn encode_ctx(&self, ctx: &mut EncodeContext) -> std::io::Result<()> {
+        self.position.encode_ctx(ctx)?;
+        ctx.write_all(b" ")?;
+        self.sequence_set.encode_ctx(ctx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use imap_types::{
+        command::Command,
+        core::Tag,
+        extensions::esearch::SearchReturnOption,
+        response::{Capability, Data, Response, Status},
+        search::SearchKey,
+        sequence::SequenceSet,
+    };
+
+    use super::*;
+    use crate::testing::{kat_inverse_command, kat_inverse_response};
+
+    #[test]
+    fn test_kat_inverse_command_context() {
+        kat_inverse_command(&[
+            (
+                b"B01 UID SEARCH RETURN (UPDATE) FLAGGED\r\n".as_ref(),
+                b"".as_ref(),
+                Command::new(
+                    "B01",
+                    CommandBody::search_return(
+                        None,
+                        SearchKey::Flagged,
+                        vec![SearchReturnOption::Update],
+                        true,
+                    ),
+                )
+                .unwrap(),
+            ),
+            (
+                b"B02 SEARCH RETURN (COUNT CONTEXT) UNSEEN\r\n".as_ref(),
+                b"".as_ref(),
+                Command::new(
+                    "B02",
+                    CommandBody::search_return(
+                        None,
+                        SearchKey::Unseen,
+                        vec![SearchReturnOption::Count, SearchReturnOption::Context],
+                        false,
+                    ),
+                )
+                .unwrap(),
+            ),
+            (
+                b"B03 CANCELUPDATE \"B01\" \"B02\"\r\n".as_ref(),
+                b"".as_ref(),
+                Command::new(
+                    "B03",
+                    CommandBody::cancel_update(vec![
+                        Tag::try_from("B01").unwrap(),
+                        Tag::try_from("B02").unwrap(),
+                    ])
+                    .unwrap(),
+                )
+                .unwrap(),
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_kat_inverse_response_context() {
+        kat_inverse_response(&[
+            (
+                b"* ESEARCH (TAG \"B01\") UID ADDTO (1 2733) ADDTO (0 2731:2732)\r\n".as_ref(),
+                b"".as_ref(),
+                Response::Data(Data::esearch(
+                    Some(Tag::try_from("B01").unwrap().into()),
+                    true,
+                    vec![
+                        SearchReturnData::AddTo(NonEmptyVec::from(ContextPosition {
+                            position: 1,
+                            sequence_set: SequenceSet::try_from(2733).unwrap(),
+                        })),
+                        SearchReturnData::AddTo(NonEmptyVec::from(ContextPosition {
+                            position: 0,
+                            sequence_set: SequenceSet::try_from("2731:2732").unwrap(),
+                        })),
+                    ],
+                )),
+            ),
+            (
+                b"* ESEARCH (TAG \"B01\") UID REMOVEFROM (0 32768 7 1:3)\r\n".as_ref(),
+                b"".as_ref(),
+                Response::Data(Data::esearch(
+                    Some(Tag::try_from("B01").unwrap().into()),
+                    true,
+                    vec![SearchReturnData::RemoveFrom(
+                        vec![
+                            ContextPosition {
+                                position: 0,
+                                sequence_set: SequenceSet::try_from(32768).unwrap(),
+                            },
+                            ContextPosition {
+                                position: 7,
+                                sequence_set: SequenceSet::try_from("1:3").unwrap(),
+                            },
+                        ]
+                        .try_into()
+                        .unwrap(),
+                    )],
+                )),
+            ),
+            (
+                b"B01 OK [NOUPDATE \"B01\"] Too many updates\r\n".as_ref(),
+                b"".as_ref(),
+                Response::Status(
+                    Status::ok(
+                        Some(Tag::try_from("B01").unwrap()),
+                        Some(Code::NoUpdate {
+                            tag: Tag::try_from("B01").unwrap(),
+                        }),
+                        "Too many updates",
+                    )
+                    .unwrap(),
+                ),
+            ),
+            (
+                b"* CAPABILITY IMAP4REV1 CONTEXT=SEARCH CONTEXT=SORT\r\n".as_ref(),
+                b"".as_ref(),
+                Response::Data(Data::Capability(
+                    vec![
+                        Capability::Imap4Rev1,
+                        Capability::ContextSearch,
+                        Capability::ContextSort,
+                    ]
+                    .try_into()
+                    .unwrap(),
+                )),
+            ),
+        ]);
+    }
+}
@@ -0,0 +1,407 @@
+//! IMAP CONVERT Extension
+
+use std::{io::Write, num::NonZeroU32};
+
+use abnf_core::streaming::sp;
+use imap_types::{
+    command::CommandBody,
+    core::{AString, NonEmptyVec},
+    extensions::convert::{ConvertItem, ConvertItemName, ConvertParams},
+    extensions::esearch::SearchCorrelator,
+    fetch::Part,
+};
+use nom::{
+    branch::alt,
+    bytes::streaming::{tag, tag_no_case},
+    combinator::{map, opt},
+    sequence::{delimited, preceded, separated_pair, tuple},
+};
+
+use crate::{
+    core::{astring, nstring, number},
+    decode::{separated_list1, IMAPResult},
+    encode::{utils::join_serializable, EncodeContext, EncodeIntoContext},
+    extensions::esearch::search_correlator,
+    fetch::{section_part, uniqueid},
+    sequence::sequence_set,
+};
+
+/// ```abnf
+/// convert = "CONVERT" SP sequence-set SP "(" convert-att *(SP convert-att) ")"
+/// ```
+pub(crate) fn convert(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
+    let mut parser = tuple((
+        tag_no_case(b"CONVERT"),
+        sp,
+        sequence_set,
+        sp,
+        delimited(tag(b"("), separated_list1(sp, convert_att), tag(b")")),
+    ));
+
+    let (remaining, (_, _, sequence_set, _, items)) = parser(input)?;
+
+    Ok((
+        remaining,
+        CommandBody::Convert {
+            sequence_set,
+            // Safety: Safe because we use `separated_list1` above.
+            items: NonEmptyVec::unvalidated(items),
+            uid: false,
+        },
+    ))
+}
+
+/// ```abnf
+/// convert-att = ("BINARY" / "BINARY.SIZE") section-binary SP convert-params
+/// ```
+pub(crate) fn convert_att(input: &[u8]) -> IMAPResult<&[u8], ConvertItemName> {
+    alt((
+        map(
+            tuple((
+                tag_no_case(b"BINARY.SIZE"),
+                section_binary,
+                sp,
+                convert_params,
+            )),
+            |(_, section, _, params)| ConvertItemName::BinarySize { section, params },
+        ),
+        map(
+            tuple((tag_no_case(b"BINARY"), section_binary, sp, convert_params)),
+            |(_, section, _, params)| ConvertItemName::Binary { section, params },
+        ),
+    ))(input)
+}
+
+/// ```abnf
+/// converted-response = "CONVERTED" search-correlator [SP "UID" SP uniqueid]
+///                      SP "(" converted-att *(SP converted-att) ")"
+/// ```
+///
+/// Note: The leading `nz-number SP` is parsed in `message_data`.
+#[allow(clippy::type_complexity)]
+pub(crate) fn converted(
+    input: &[u8],
+) -> IMAPResult<
+    &[u8],
+    (
+        SearchCorrelator,
+        Option<NonZeroU32>,
+        NonEmptyVec<ConvertItem>,
+    ),
+> {
+    let mut parser = tuple((
+        tag_no_case(b"CONVERTED"),
+        search_correlator,
+        opt(preceded(tag_no_case(b" UID "), uniqueid)),
+        sp,
+        delimited(tag(b"("), separated_list1(sp, converted_att), tag(b")")),
+    ));
+
+    let (remaining, (_, correlator, uid, _, items)) = parser(input)?;
+
+    // Safety: Safe because we use `separated_list1` above.
+    Ok((
+        remaining,
+        (correlator, uid, NonEmptyVec::unvalidated(items)),
+    ))
+}
+
+/// ```abnf
+/// converted-att = "BINARY" section-binary SP convert-params SP nstring /
+///                 "BINARY.SIZE" section-binary SP convert-params SP number
+/// ```
+pub(crate) fn converted_att(input: &[u8]) -> IMAPResult<&[u8], ConvertItem> {
+    alt((
+        map(
+            tuple((
+                tag_no_case(b"BINARY.SIZE"),
+                section_binary,
+                sp,
+                convert_params,
+                sp,
+                number,
+            )),
+            |(_, section, _, params, _, size)| ConvertItem::BinarySize {
+                section,
+                params,
+                size,
+            },
+        ),
+        map(
+            tuple((
+                tag_no_case(b"BINARY"),
+                section_binary,
+                sp,
+                convert_params,
+                sp,
+                nstring,
+            )),
+            |(_, section, _, params, _, value)| ConvertItem::Binary {
+                section,
+                params,
+                value,
+            },
+        ),
+    ))(input)
+}
+
+/// `section-binary = "[" [section-part] "]"` (RFC 3516)
+pub(crate) fn section_binary(input: &[u8]) -> IMAPResult<&[u8], Option<Part>> {
+    delimited(tag(b"["), opt(map(section_part, Part)), tag(b"]"))(input)
+}
+
+/// ```abnf
+/// convert-params = "(" media-type [SP "(" param-name SP param-value
+///                  *(SP param-name SP param-value) ")"] ")"
+///
+/// media-type  = astring
+/// param-name  = astring
+/// param-value = astring
+/// ```
+pub(crate) fn convert_params(input: &[u8]) -> IMAPResult<&[u8], ConvertParams> {
+    let mut parser = delimited(
+        tag(b"("),
+        tuple((
+            astring,
+            opt(preceded(
+                sp,
+                delimited(
+                    tag(b"("),
+                    separated_list1(sp, separated_pair(astring, sp, astring)),
+                    tag(b")"),
+                ),
+            )),
+        )),
+        tag(b")"),
+    );
+
+    let (remaining, (media_type, params)) = parser(input)?;
+
+    Ok((
+        remaining,
+        ConvertParams {
+            media_type,
+            params: params.unwrap_or_default(),
+        },
+    ))
+}
+
+impl<'a> EncodeIntoContext for ConvertParams<'a> {
+    fn encode_ctx(&self, ctx: &mut EncodeContext) -> std::io::Result<()> {
+        ctx.write_all(b"(")?;
+        self.media_type.encode_ctx(ctx)?;
+
+        if !self.params.is_empty() {
+            ctx.write_all(b" (")?;
+            join_serializable(&self.params, b" ", ctx)?;
+            ctx.write_all(b")")?;
+        }
+
+        ctx.write_all(b")")
+    }
+}
+
+/// A conversion parameter, i.e., `name SP value`.
+impl<'a> EncodeIntoContext for (AString<'a>, AString<'a>) {
+    fn encode_ctx(&self, ctx: &mut EncodeContext) -> std::io::Result<()> {
+        self.0.encode_ctx(ctx)?;
+        ctx.write_all(b" ")?;
+        self.1.encode_ctx(ctx)
+    }
+}
+
+fn encode_section_binary(section: &Option<Part>, ctx: &mut EncodeContext) -> std::io::Result<()> {
+    ctx.write_all(b"[")?;
+    if let Some(part) = section {
+        part.encode_ctx(ctx)?;
+    }
+    ctx.write_all(b"]")
+}
+
+impl<'a> EncodeIntoContext for ConvertItemName<'a> {
+    fn encode_ctx(&self, ctx: &mut EncodeContext) -> std::io::Result<()> {
+        let (name, section, params) = match self {
+            Self::Binary { section, params } => ("BINARY", section, params),
+            Self::BinarySize { section, params } => ("BINARY.SIZE", section, params),
+        };
+
+        write!(ctx, "{name}")?;
+        encode_section_binary(section, ctx)?;
+        ctx.write_all(b" ")?;
+        params.encode_ctx(ctx)
+    }
+}
+
+impl<'a> EncodeIntoContext for ConvertItem<'a> {
+    fn encode_ctx(&self, ctx: &mut EncodeContext) -> std::io::Result<()> {
+        match self {
+            Self::Binary {
+                section,
+                params,
+                value,
+            } => {
+                ctx.write_all(b"BINARY")?;
+                encode_section_binary(section, ctx)?;
+                ctx.write_all(b" ")?;
+                params.encode_ctx(ctx)?;
+                ctx.write_all(b" ")?;
+                value.encode_ctx(ctx)
+            }
+            Self::BinarySize {
+                section,
+                params,
+                size,
+            } => {
+                ctx.write_all(b"BINARY.SIZE")?;
+                encode_section_binary(section, ctx)?;
+                ctx.write_all(b" ")?;
+                params.encode_ctx(ctx)?;
+                write!(ctx, " {size}")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use imap_types::{
+        command::Command,
+        core::{AString, NString, Tag, Text},
+        response::{Capability, Code, Data, Response, Status},
+    };
+
+    use super::*;
+    use crate::testing::{kat_inverse_command, kat_inverse_response};
+
+    #[test]
+    fn test_kat_inverse_command_convert() {
+        kat_inverse_command(&[
+            (
+                b"A CONVERT 1 (BINARY[1] (image/png))\r\n".as_ref(),
+                b"".as_ref(),
+                Command::new(
+                    "A",
+                    CommandBody::convert(
+                        1,
+                        vec![ConvertItemName::Binary {
+                            section: Some(Part(NonEmptyVec::from(NonZeroU32::new(1).unwrap()))),
+                            params: ConvertParams::new(AString::try_from("image/png").unwrap()),
+                        }],
+                        false,
+                    )
+                    .unwrap(),
+                )
+                .unwrap(),
+            ),
+            (
+                b"A UID CONVERT 100:200 (BINARY.SIZE[2.1] (image/jpeg (PIX-X 320 PIX-Y 240)) BINARY[] (text/plain (CHARSET UTF-8)))\r\n".as_ref(),
+                b"".as_ref(),
+                Command::new(
+                    "A",
+                    CommandBody::convert(
+                        "100:200",
+                        vec![
+                            ConvertItemName::BinarySize {
+                                section: Some(Part(
+                                    vec![NonZeroU32::new(2).unwrap(), NonZeroU32::new(1).unwrap()]
+                                        .try_into()
+                                        .unwrap(),
+                                )),
+                                params: ConvertParams::new(AString::try_from("image/jpeg").unwrap())
+                                    .with_param(
+                                        AString::try_from("PIX-X").unwrap(),
+                                        AString::try_from("320").unwrap(),
+                                    )
+                                    .with_param(
+                                        AString::try_from("PIX-Y").unwrap(),
+                                        AString::try_from("240").unwrap(),
+                                    ),
+                            },
+                            ConvertItemName::Binary {
+                                section: None,
+                                params: ConvertParams::new(AString::try_from("text/plain").unwrap())
+                                    .with_param(
+                                        AString::try_from("CHARSET").unwrap(),
+                                        AString::try_from("UTF-8").unwrap(),
+                                    ),
+                            },
+                        ],
+                        true,
+                    )
+                    .unwrap(),
+                )
+                .unwrap(),
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_kat_inverse_response_convert() {
+        kat_inverse_response(&[
+            (
+                b"* 2 CONVERTED (TAG \"A\") UID 1234 (BINARY[1] (text/plain (CHARSET UTF-8)) \"Hello\" BINARY.SIZE[1] (text/plain (CHARSET UTF-8)) 5)\r\n".as_ref(),
+                b"".as_ref(),
+                Response::Data(Data::converted(
+                    NonZeroU32::new(2).unwrap(),
+                    Tag::try_from("A").unwrap().into(),
+                    Some(NonZeroU32::new(1234).unwrap()),
+                    vec![
+                        ConvertItem::Binary {
+                            section: Some(Part(NonEmptyVec::from(NonZeroU32::new(1).unwrap()))),
+                            params: ConvertParams::new(AString::try_from("text/plain").unwrap())
+                                .with_param(
+                                    AString::try_from("CHARSET").unwrap(),
+                                    AString::try_from("UTF-8").unwrap(),
+                                ),
+                            value: NString::try_from("Hello").unwrap(),
+                        },
+                        ConvertItem::BinarySize {
+                            section: Some(Part(NonEmptyVec::from(NonZeroU32::new(1).unwrap()))),
+                            params: ConvertParams::new(AString::try_from("text/plain").unwrap())
+                                .with_param(
+                                    AString::try_from("CHARSET").unwrap(),
+                                    AString::try_from("UTF-8").unwrap(),
+                                ),
+                            size: 5,
+                        },
+                    ]
+                    .try_into()
+                    .unwrap(),
+                )),
+            ),
+            (
+                b"A NO [UNKNOWN-CVT] ...\r\n".as_ref(),
+                b"".as_ref(),
+                Response::Status(
+                    Status::no(
+                        Some(Tag::try_from("A").unwrap()),
+                        Some(Code::UnknownCvt),
+                        Text::try_from("...").unwrap(),
+                    )
+                    .unwrap(),
+                ),
+            ),
+            (
+                b"A BAD [BADPARAMS] ...\r\n".as_ref(),
+                b"".as_ref(),
+                Response::Status(
+                    Status::bad(
+                        Some(Tag::try_from("A").unwrap()),
+                        Some(Code::BadParams),
+                        Text::try_from("...").unwrap(),
+                    )
+                    .unwrap(),
+                ),
+            ),
+            (
+                b"* CAPABILITY IMAP4REV1 CONVERT\r\n".as_ref(),
+                b"".as_ref(),
+                Response::Data(Data::Capability(
+                    vec![Capability::Imap4Rev1, Capability::Convert]
+                        .try_into()
+                        .unwrap(),
+                )),
+            ),
+        ]);
+    }
+}
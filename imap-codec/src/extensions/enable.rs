@@ -13,13 +13,12 @@ use imap_types::{command::CommandBody, extensions::enable::CapabilityEnable, res
 use nom::{
     bytes::streaming::tag_no_case,
     combinator::map,
-    multi::{many0, many1},
     sequence::{preceded, tuple},
 };
 
 use crate::{
     core::atom,
-    decode::IMAPResult,
+    decode::{many0, many1, IMAPResult},
     encode::{EncodeContext, EncodeIntoContext},
 };
 
@@ -0,0 +1,353 @@
+//! IMAP4 Extension for SEARCH Command Controlling What Kind of Information Is Returned
+
+use std::io::Write;
+
+use abnf_core::streaming::{dquote, sp};
+use imap_types::{
+    extensions::esearch::{SearchCorrelator, SearchReturnData, SearchReturnOption},
+    response::Data,
+};
+use nom::{
+    branch::alt,
+    bytes::streaming::{tag, tag_no_case},
+    combinator::{map, opt, value},
+    sequence::{delimited, preceded, tuple},
+};
+
+use crate::{
+    core::{nil, number, nz_number, tag_imap},
+    decode::{many0, separated_list0, IMAPResult},
+    encode::{utils::join_serializable, EncodeContext, EncodeIntoContext},
+    extensions::{
+        context::{ret_data_addto, ret_data_removefrom},
+        partial::partial_range,
+    },
+    mailbox::mailbox,
+    sequence::sequence_set,
+};
+
+/// ```abnf
+/// search-return-opts = SP "RETURN" SP "(" [search-return-opt *(SP search-return-opt)] ")"
+/// ```
+///
+/// Note: `RETURN ()` is equivalent to `RETURN (ALL)`.
+pub(crate) fn search_return_opts(input: &[u8]) -> IMAPResult<&[u8], Vec<SearchReturnOption>> {
+    let mut parser = preceded(
+        tuple((sp, tag_no_case(b"RETURN"), sp)),
+        delimited(tag(b"("), separated_list0(sp, search_return_opt), tag(b")")),
+    );
+
+    let (remaining, return_options) = parser(input)?;
+
+    let return_options = if return_options.is_empty() {
+        vec![SearchReturnOption::All]
+    } else {
+        return_options
+    };
+
+    Ok((remaining, return_options))
+}
+
+/// ```abnf
+/// search-return-opt = "MIN" / "MAX" / "ALL" / "COUNT"
+/// ```
+///
+/// `search-return-opt =/ "PARTIAL" SP partial-range` (RFC 9394)
+///
+/// `search-return-opt =/ "UPDATE" / "CONTEXT"` (RFC 5267)
+pub(crate) fn search_return_opt(input: &[u8]) -> IMAPResult<&[u8], SearchReturnOption> {
+    alt((
+        value(SearchReturnOption::Min, tag_no_case(b"MIN")),
+        value(SearchReturnOption::Max, tag_no_case(b"MAX")),
+        value(SearchReturnOption::All, tag_no_case(b"ALL")),
+        value(SearchReturnOption::Count, tag_no_case(b"COUNT")),
+        map(
+            preceded(tag_no_case(b"PARTIAL "), partial_range),
+            SearchReturnOption::Partial,
+        ),
+        value(SearchReturnOption::Update, tag_no_case(b"UPDATE")),
+        value(SearchReturnOption::Context, tag_no_case(b"CONTEXT")),
+    ))(input)
+}
+
+/// ```abnf
+/// esearch-response = "ESEARCH" [search-correlator] [SP "UID"] *(SP search-return-data)
+/// ```
+pub(crate) fn esearch_response(input: &[u8]) -> IMAPResult<&[u8], Data> {
+    let mut parser = tuple((
+        tag_no_case(b"ESEARCH"),
+        opt(search_correlator),
+        opt(preceded(sp, tag_no_case(b"UID"))),
+        many0(preceded(sp, search_return_data)),
+    ));
+
+    let (remaining, (_, correlator, uid, items)) = parser(input)?;
+
+    Ok((
+        remaining,
+        Data::ESearch {
+            correlator,
+            uid: uid.is_some(),
+            items,
+        },
+    ))
+}
+
+/// ```abnf
+/// search-correlator = SP "(" "TAG" SP tag-string ")"
+/// ```
+///
+/// `search-correlator =/ SP "(" "TAG" SP tag-string SP "MAILBOX" SP astring SP "UIDVALIDITY" SP nz-number ")"` (RFC 7377)
+///
+/// Note: `tag-string` is a `string`, but only the quoted form is supported.
+pub(crate) fn search_correlator(input: &[u8]) -> IMAPResult<&[u8], SearchCorrelator> {
+    let mut parser = delimited(
+        tuple((sp, tag(b"("), tag_no_case(b"TAG"), sp)),
+        tuple((
+            delimited(dquote, tag_imap, dquote),
+            opt(tuple((
+                preceded(tag_no_case(b" MAILBOX "), mailbox),
+                preceded(tag_no_case(b" UIDVALIDITY "), nz_number),
+            ))),
+        )),
+        tag(b")"),
+    );
+
+    let (remaining, (tag, mailbox)) = parser(input)?;
+
+    Ok((remaining, SearchCorrelator { tag, mailbox }))
+}
+
+/// ```abnf
+/// search-return-data = "MIN" SP nz-number /
+///                      "MAX" SP nz-number /
+///                      "ALL" SP sequence-set /
+///                      "COUNT" SP number
+/// ```
+///
+/// `search-return-data =/ "PARTIAL" SP "(" partial-range SP partial-results ")"` (RFC 9394)
+///
+/// `partial-results = sequence-set / "NIL"` (RFC 9394)
+///
+/// `search-return-data =/ ret-data-addto / ret-data-removefrom` (RFC 5267)
+pub(crate) fn search_return_data(input: &[u8]) -> IMAPResult<&[u8], SearchReturnData> {
+    alt((
+        map(
+            preceded(tag_no_case(b"MIN "), nz_number),
+            SearchReturnData::Min,
+        ),
+        map(
+            preceded(tag_no_case(b"MAX "), nz_number),
+            SearchReturnData::Max,
+        ),
+        map(
+            preceded(tag_no_case(b"ALL "), sequence_set),
+            SearchReturnData::All,
+        ),
+        map(
+            preceded(tag_no_case(b"COUNT "), number),
+            SearchReturnData::Count,
+        ),
+        map(
+            preceded(
+                tag_no_case(b"PARTIAL "),
+                delimited(
+                    tag(b"("),
+                    tuple((
+                        partial_range,
+                        sp,
+                        alt((map(sequence_set, Some), value(None, nil))),
+                    )),
+                    tag(b")"),
+                ),
+            ),
+            |(range, _, results)| SearchReturnData::Partial { range, results },
+        ),
+        ret_data_addto,
+        ret_data_removefrom,
+    ))(input)
+}
+
+impl EncodeIntoContext for SearchReturnOption {
+    fn encode_ctx(&self, ctx: &mut EncodeContext) -> std::io::Result<()> {
+        match self {
+            Self::Min => ctx.write_all(b"MIN"),
+            Self::Max => ctx.write_all(b"MAX"),
+            Self::All => ctx.write_all(b"ALL"),
+            Self::Count => ctx.write_all(b"COUNT"),
+            Self::Partial(range) => {
+                ctx.write_all(b"PARTIAL ")?;
+                range.encode_ctx(ctx)
+            }
+            Self::Update => ctx.write_all(b"UPDATE"),
+            Self::Context => ctx.write_all(b"CONTEXT"),
+        }
+    }
+}
+
+impl<'a> EncodeIntoContext for SearchCorrelator<'a> {
+    fn encode_ctx(&self, ctx: &mut EncodeContext) -> std::io::Result<()> {
+        ctx.write_all(b"(TAG \"")?;
+        self.tag.encode_ctx(ctx)?;
+        ctx.write_all(b"\"")?;
+        if let Some((mailbox, uid_validity)) = &self.mailbox {
+            ctx.write_all(b" MAILBOX ")?;
+            mailbox.encode_ctx(ctx)?;
+            ctx.write_all(b" UIDVALIDITY ")?;
+            uid_validity.encode_ctx(ctx)?;
+        }
+        ctx.write_all(b")")
+    }
+}
+
+impl EncodeIntoContext for SearchReturnData {
+    fn encode_ctx(&self, ctx: &mut EncodeContext) -> std::io::Result<()> {
+        match self {
+            Self::Min(min) => write!(ctx, "MIN {min}"),
+            Self::Max(max) => write!(ctx, "MAX {max}"),
+            Self::All(sequence_set) => {
+                ctx.write_all(b"ALL ")?;
+                sequence_set.encode_ctx(ctx)
+            }
+            Self::Count(count) => write!(ctx, "COUNT {count}"),
+            Self::Partial { range, results } => {
+                ctx.write_all(b"PARTIAL (")?;
+                range.encode_ctx(ctx)?;
+                ctx.write_all(b" ")?;
+                match results {
+                    Some(sequence_set) => sequence_set.encode_ctx(ctx)?,
+                    None => ctx.write_all(b"NIL")?,
+                }
+                ctx.write_all(b")")
+            }
+            Self::AddTo(positions) => {
+                ctx.write_all(b"ADDTO (")?;
+                join_serializable(positions.as_ref(), b" ", ctx)?;
+                ctx.write_all(b")")
+            }
+            Self::RemoveFrom(positions) => {
+                ctx.write_all(b"REMOVEFROM (")?;
+                join_serializable(positions.as_ref(), b" ", ctx)?;
+                ctx.write_all(b")")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroU32;
+
+    use imap_types::{
+        command::{Command, CommandBody},
+        core::{Charset, Tag},
+        response::Response,
+        search::SearchKey,
+        sequence::SequenceSet,
+    };
+
+    use super::*;
+    use crate::{
+        decode::Decoder,
+        testing::{kat_inverse_command, kat_inverse_response},
+        CommandCodec,
+    };
+
+    #[test]
+    fn test_kat_inverse_command_esearch() {
+        kat_inverse_command(&[
+            (
+                b"A SEARCH RETURN (MIN MAX COUNT) FLAGGED\r\n".as_ref(),
+                b"".as_ref(),
+                Command::new(
+                    "A",
+                    CommandBody::search_return(
+                        None,
+                        SearchKey::Flagged,
+                        vec![
+                            SearchReturnOption::Min,
+                            SearchReturnOption::Max,
+                            SearchReturnOption::Count,
+                        ],
+                        false,
+                    ),
+                )
+                .unwrap(),
+            ),
+            (
+                b"A UID SEARCH RETURN (ALL) CHARSET UTF-8 UNSEEN\r\n".as_ref(),
+                b"".as_ref(),
+                Command::new(
+                    "A",
+                    CommandBody::search_return(
+                        Some(Charset::try_from("UTF-8").unwrap()),
+                        SearchKey::Unseen,
+                        vec![SearchReturnOption::All],
+                        true,
+                    ),
+                )
+                .unwrap(),
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_decode_search_return_empty() {
+        let (_, got) = CommandCodec::default()
+            .decode(b"A SEARCH RETURN () UNSEEN\r\n")
+            .unwrap();
+
+        assert_eq!(
+            got.body,
+            CommandBody::search_return(
+                None,
+                SearchKey::Unseen,
+                vec![SearchReturnOption::All],
+                false
+            )
+        );
+    }
+
+    #[test]
+    fn test_kat_inverse_response_esearch() {
+        kat_inverse_response(&[
+            (
+                b"* ESEARCH (TAG \"A282\") MIN 2 COUNT 3\r\n".as_ref(),
+                b"".as_ref(),
+                Response::Data(Data::esearch(
+                    Some(Tag::try_from("A282").unwrap().into()),
+                    false,
+                    vec![
+                        SearchReturnData::Min(NonZeroU32::new(2).unwrap()),
+                        SearchReturnData::Count(3),
+                    ],
+                )),
+            ),
+            (
+                b"* ESEARCH (TAG \"A283\") UID ALL 4:18,21,28\r\n".as_ref(),
+                b"".as_ref(),
+                Response::Data(Data::esearch(
+                    Some(Tag::try_from("A283").unwrap().into()),
+                    true,
+                    vec![SearchReturnData::All(
+                        SequenceSet::try_from("4:18,21,28").unwrap(),
+                    )],
+                )),
+            ),
+            (
+                b"* ESEARCH\r\n".as_ref(),
+                b"".as_ref(),
+                Response::Data(Data::esearch(None, false, vec![])),
+            ),
+            (
+                b"* ESEARCH UID MAX 7\r\n".as_ref(),
+                b"".as_ref(),
+                Response::Data(Data::esearch(
+                    None,
+                    true,
+                    vec![SearchReturnData::Max(NonZeroU32::new(7).unwrap())],
+                )),
+            ),
+        ]);
+    }
+}
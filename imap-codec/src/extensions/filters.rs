@@ -0,0 +1,113 @@
+//! IMAP4 Extension for Named Searches (Filters)
+
+use std::{io::Write, str::from_utf8};
+
+use imap_types::{extensions::filters::FilterName, utils::indicators::is_filter_name_char};
+use nom::bytes::streaming::take_while1;
+
+use crate::{
+    decode::IMAPResult,
+    encode::{EncodeContext, EncodeIntoContext},
+};
+
+/// `filter-name = 1*<any ATOM-CHAR except "/">`
+pub(crate) fn filter_name(input: &[u8]) -> IMAPResult<&[u8], FilterName> {
+    let (remaining, parsed) = take_while1(is_filter_name_char)(input)?;
+
+    // # Safety
+    //
+    // `unwrap` is safe, because `is_filter_name_char` enforces ...
+    // * that the string is always UTF8, and ...
+    // * contains only the allowed characters.
+    Ok((
+        remaining,
+        FilterName::unvalidated(from_utf8(parsed).unwrap()),
+    ))
+}
+
+impl<'a> EncodeIntoContext for FilterName<'a> {
+    fn encode_ctx(&self, ctx: &mut EncodeContext) -> std::io::Result<()> {
+        ctx.write_all(self.inner().as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use imap_types::{
+        command::{Command, CommandBody},
+        datetime::NaiveDate,
+        response::{Capability, Data, Response},
+        search::SearchKey,
+    };
+
+    use super::*;
+    use crate::testing::{kat_inverse_command, kat_inverse_response};
+
+    #[test]
+    fn test_parse_filter_name() {
+        let (rem, val) = filter_name(b"urgent/x").unwrap();
+        assert_eq!(rem, b"/x");
+        assert_eq!(val, FilterName::try_from("urgent").unwrap());
+
+        assert!(filter_name(b"/urgent").is_err());
+    }
+
+    #[test]
+    fn test_kat_inverse_command_filters() {
+        kat_inverse_command(&[
+            (
+                b"A SEARCH FILTER urgent\r\n".as_ref(),
+                b"".as_ref(),
+                Command::new(
+                    "A",
+                    CommandBody::search(
+                        None,
+                        SearchKey::Filter(FilterName::try_from("urgent").unwrap()),
+                        false,
+                    ),
+                )
+                .unwrap(),
+            ),
+            (
+                b"A UID SEARCH NOT FILTER On-The-Road SINCE 1-Feb-1994\r\n".as_ref(),
+                b"".as_ref(),
+                Command::new(
+                    "A",
+                    CommandBody::search(
+                        None,
+                        SearchKey::And(
+                            vec![
+                                SearchKey::Not(Box::new(SearchKey::Filter(
+                                    FilterName::try_from("On-The-Road").unwrap(),
+                                ))),
+                                SearchKey::Since(
+                                    NaiveDate::try_from(
+                                        chrono::NaiveDate::from_ymd_opt(1994, 2, 1).unwrap(),
+                                    )
+                                    .unwrap(),
+                                ),
+                            ]
+                            .try_into()
+                            .unwrap(),
+                        ),
+                        true,
+                    ),
+                )
+                .unwrap(),
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_kat_inverse_response_filters() {
+        kat_inverse_response(&[(
+            b"* CAPABILITY IMAP4REV1 FILTERS\r\n".as_ref(),
+            b"".as_ref(),
+            Response::Data(Data::Capability(
+                vec![Capability::Imap4Rev1, Capability::Filters]
+                    .try_into()
+                    .unwrap(),
+            )),
+        )]);
+    }
+}
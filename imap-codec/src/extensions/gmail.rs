@@ -0,0 +1,204 @@
+//! Gmail IMAP Extensions (X-GM-EXT-1)
+
+use std::io::Write;
+
+use abnf_core::streaming::sp;
+use imap_types::extensions::gmail::GmailLabel;
+use nom::{
+    branch::alt,
+    bytes::streaming::tag,
+    character::streaming::char,
+    combinator::map,
+    sequence::{delimited, preceded},
+};
+
+use crate::{
+    core::{astring, atom},
+    decode::{separated_list0, IMAPResult},
+    encode::{EncodeContext, EncodeIntoContext},
+};
+
+/// ```abnf
+/// label = "\" atom / astring
+/// ```
+pub(crate) fn gmail_label(input: &[u8]) -> IMAPResult<&[u8], GmailLabel> {
+    alt((
+        map(preceded(char('\\'), atom), GmailLabel::System),
+        map(astring, GmailLabel::User),
+    ))(input)
+}
+
+/// ```abnf
+/// label-list = "(" [label *(SP label)] ")"
+/// ```
+pub(crate) fn gmail_label_list(input: &[u8]) -> IMAPResult<&[u8], Vec<GmailLabel>> {
+    delimited(tag(b"("), separated_list0(sp, gmail_label), tag(b")"))(input)
+}
+
+impl<'a> EncodeIntoContext for GmailLabel<'a> {
+    fn encode_ctx(&self, ctx: &mut EncodeContext) -> std::io::Result<()> {
+        match self {
+            Self::System(atom) => write!(ctx, "\\{}", atom),
+            Self::User(astring) => astring.encode_ctx(ctx),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroU32;
+
+    use imap_types::{
+        command::{Command, CommandBody},
+        core::{AString, Atom, NonEmptyVec},
+        fetch::{MacroOrMessageDataItemNames, MessageDataItem, MessageDataItemName},
+        flag::{StoreResponse, StoreType},
+        response::{Capability, Data, Response},
+    };
+
+    use super::*;
+    use crate::testing::{kat_inverse_command, kat_inverse_response, known_answer_test_encode};
+
+    #[test]
+    fn test_parse_gmail_label() {
+        let tests = [
+            (
+                b"\\Inbox)".as_ref(),
+                GmailLabel::System(Atom::try_from("Inbox").unwrap()),
+            ),
+            (
+                b"Important)".as_ref(),
+                GmailLabel::User(AString::try_from("Important").unwrap()),
+            ),
+            (
+                b"\"Muy Importante\")".as_ref(),
+                GmailLabel::User(AString::try_from("Muy Importante").unwrap()),
+            ),
+        ];
+
+        for (test, expected) in tests {
+            let (rem, got) = gmail_label(test).unwrap();
+            assert_eq!(expected, got);
+            assert_eq!(rem, b")");
+        }
+    }
+
+    #[test]
+    fn test_encode_gmail_label() {
+        let tests = [
+            (
+                GmailLabel::try_from("\\Important").unwrap(),
+                b"\\Important".as_ref(),
+            ),
+            (GmailLabel::try_from("foo").unwrap(), b"foo"),
+            (GmailLabel::try_from("foo bar").unwrap(), b"\"foo bar\""),
+        ];
+
+        for test in tests {
+            known_answer_test_encode(test);
+        }
+    }
+
+    #[test]
+    fn test_kat_inverse_command_gmail() {
+        kat_inverse_command(&[
+            (
+                b"A FETCH 1 (X-GM-MSGID X-GM-THRID X-GM-LABELS)\r\n".as_ref(),
+                b"".as_ref(),
+                Command::new(
+                    "A",
+                    CommandBody::fetch(
+                        "1",
+                        MacroOrMessageDataItemNames::MessageDataItemNames(vec![
+                            MessageDataItemName::GmailMessageId,
+                            MessageDataItemName::GmailThreadId,
+                            MessageDataItemName::GmailLabels,
+                        ]),
+                        false,
+                    )
+                    .unwrap(),
+                )
+                .unwrap(),
+            ),
+            (
+                b"A STORE 1 +X-GM-LABELS (\\Inbox foo)\r\n".as_ref(),
+                b"".as_ref(),
+                Command::new(
+                    "A",
+                    CommandBody::store_gmail_labels(
+                        "1",
+                        StoreType::Add,
+                        StoreResponse::Answer,
+                        vec![
+                            GmailLabel::try_from("\\Inbox").unwrap(),
+                            GmailLabel::try_from("foo").unwrap(),
+                        ],
+                        false,
+                    )
+                    .unwrap(),
+                )
+                .unwrap(),
+            ),
+            (
+                b"A UID STORE 1:* -X-GM-LABELS.SILENT (\"Muy Importante\")\r\n".as_ref(),
+                b"".as_ref(),
+                Command::new(
+                    "A",
+                    CommandBody::store_gmail_labels(
+                        "1:*",
+                        StoreType::Remove,
+                        StoreResponse::Silent,
+                        vec![GmailLabel::try_from("Muy Importante").unwrap()],
+                        true,
+                    )
+                    .unwrap(),
+                )
+                .unwrap(),
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_kat_inverse_response_gmail() {
+        kat_inverse_response(&[
+            (
+                b"* CAPABILITY IMAP4REV1 X-GM-EXT-1\r\n".as_ref(),
+                b"".as_ref(),
+                Response::Data(Data::Capability(
+                    NonEmptyVec::try_from(vec![Capability::Imap4Rev1, Capability::GmailExt1])
+                        .unwrap(),
+                )),
+            ),
+            (
+                b"* 1 FETCH (X-GM-MSGID 1278455344230334865 X-GM-THRID 1266894439832287888 X-GM-LABELS (\\Inbox \\Sent Important \"Muy Importante\"))\r\n".as_ref(),
+                b"".as_ref(),
+                Response::Data(Data::Fetch {
+                    seq: NonZeroU32::new(1).unwrap(),
+                    items: NonEmptyVec::try_from(vec![
+                        MessageDataItem::GmailMessageId(1278455344230334865),
+                        MessageDataItem::GmailThreadId(1266894439832287888),
+                        MessageDataItem::GmailLabels(vec![
+                            GmailLabel::try_from("\\Inbox").unwrap(),
+                            GmailLabel::try_from("\\Sent").unwrap(),
+                            GmailLabel::try_from("Important").unwrap(),
+                            GmailLabel::try_from("Muy Importante").unwrap(),
+                        ]),
+                    ])
+                    .unwrap(),
+                }),
+            ),
+            (
+                b"* 2 FETCH (UID 4 X-GM-LABELS ())\r\n".as_ref(),
+                b"".as_ref(),
+                Response::Data(Data::Fetch {
+                    seq: NonZeroU32::new(2).unwrap(),
+                    items: NonEmptyVec::try_from(vec![
+                        MessageDataItem::Uid(NonZeroU32::new(4).unwrap()),
+                        MessageDataItem::GmailLabels(vec![]),
+                    ])
+                    .unwrap(),
+                }),
+            ),
+        ]);
+    }
+}
@@ -9,15 +9,11 @@
 
 use std::io::Write;
 
-#[cfg(not(feature = "quirk_crlf_relaxed"))]
-use abnf_core::streaming::crlf;
-#[cfg(feature = "quirk_crlf_relaxed")]
-use abnf_core::streaming::crlf_relaxed as crlf;
 use imap_types::{command::CommandBody, extensions::idle::IdleDone};
 use nom::{bytes::streaming::tag_no_case, combinator::value, sequence::tuple};
 
 use crate::{
-    decode::IMAPResult,
+    decode::{crlf, IMAPResult},
     encode::{EncodeContext, EncodeIntoContext},
 };
 
@@ -0,0 +1,153 @@
+//! IMAP Response Code for Command Progress Notifications
+
+use abnf_core::streaming::{dquote, sp};
+use imap_types::response::Code;
+use nom::{
+    branch::alt,
+    bytes::streaming::{tag, tag_no_case},
+    combinator::{map, opt, value},
+    sequence::{delimited, preceded, tuple},
+};
+
+use crate::{
+    core::{nil, number, nz_number, tag_imap},
+    decode::IMAPResult,
+};
+
+/// ```abnf
+/// "INPROGRESS" [SP "(" progress-tag SP progress-state ")"]
+///
+/// progress-tag = quoted / nil
+///
+/// progress-state = progress-current SP progress-goal
+///
+/// progress-current = number / nil
+///
+/// progress-goal = nz-number / nil
+/// ```
+///
+/// Note: `progress-tag` is a `quoted`, but we require it to be a valid tag.
+pub(crate) fn inprogress(input: &[u8]) -> IMAPResult<&[u8], Code> {
+    let mut parser = preceded(
+        tag_no_case(b"INPROGRESS"),
+        opt(preceded(
+            sp,
+            delimited(
+                tag(b"("),
+                tuple((
+                    alt((
+                        map(delimited(dquote, tag_imap, dquote), Some),
+                        value(None, nil),
+                    )),
+                    sp,
+                    alt((map(number, Some), value(None, nil))),
+                    sp,
+                    alt((map(nz_number, Some), value(None, nil))),
+                )),
+                tag(b")"),
+            ),
+        )),
+    );
+
+    let (remaining, progress) = parser(input)?;
+
+    let code = match progress {
+        Some((tag, _, current, _, goal)) => Code::InProgress { tag, current, goal },
+        None => Code::InProgress {
+            tag: None,
+            current: None,
+            goal: None,
+        },
+    };
+
+    Ok((remaining, code))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroU32;
+
+    use imap_types::{
+        core::Tag,
+        response::{Response, Status},
+    };
+
+    use super::*;
+    use crate::{decode::Decoder, testing::kat_inverse_response, ResponseCodec};
+
+    #[test]
+    fn test_kat_inverse_response_inprogress() {
+        kat_inverse_response(&[
+            (
+                b"* OK [INPROGRESS (\"A1\" 2000 20000)] Searching\r\n".as_ref(),
+                b"".as_ref(),
+                Response::Status(
+                    Status::ok(
+                        None,
+                        Some(Code::in_progress(
+                            Some(Tag::try_from("A1").unwrap()),
+                            Some(2000),
+                            NonZeroU32::new(20000),
+                        )),
+                        "Searching",
+                    )
+                    .unwrap(),
+                ),
+            ),
+            (
+                b"* OK [INPROGRESS (\"A2\" 5 NIL)] Copying\r\n".as_ref(),
+                b"".as_ref(),
+                Response::Status(
+                    Status::ok(
+                        None,
+                        Some(Code::in_progress(
+                            Some(Tag::try_from("A2").unwrap()),
+                            Some(5),
+                            None,
+                        )),
+                        "Copying",
+                    )
+                    .unwrap(),
+                ),
+            ),
+            (
+                b"* OK [INPROGRESS (NIL 0 NIL)] Working\r\n".as_ref(),
+                b"".as_ref(),
+                Response::Status(
+                    Status::ok(
+                        None,
+                        Some(Code::in_progress(None, Some(0), None)),
+                        "Working",
+                    )
+                    .unwrap(),
+                ),
+            ),
+            (
+                b"* OK [INPROGRESS] Hang in there\r\n".as_ref(),
+                b"".as_ref(),
+                Response::Status(
+                    Status::ok(
+                        None,
+                        Some(Code::in_progress(None, None, None)),
+                        "Hang in there",
+                    )
+                    .unwrap(),
+                ),
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_decode_inprogress_nil() {
+        let (_, got) = ResponseCodec::default()
+            .decode(b"* OK [INPROGRESS (NIL NIL NIL)] ...\r\n")
+            .unwrap();
+
+        assert_eq!(
+            got,
+            Response::Status(
+                Status::ok(None, Some(Code::in_progress(None, None, None)), "...").unwrap()
+            )
+        );
+    }
+}
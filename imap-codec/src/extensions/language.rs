@@ -0,0 +1,208 @@
+//! Internet Message Access Protocol Internationalization
+
+use abnf_core::streaming::sp;
+use imap_types::{command::CommandBody, core::NonEmptyVec, response::Data};
+use nom::{
+    bytes::streaming::{tag, tag_no_case},
+    combinator::opt,
+    sequence::{delimited, preceded, tuple},
+};
+
+use crate::{
+    core::astring,
+    decode::{many0, separated_list1, IMAPResult},
+};
+
+/// ```abnf
+/// language-cmd = "LANGUAGE" *(SP lang-range-quoted)
+///
+/// lang-range-quoted = astring
+/// ```
+pub(crate) fn language(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
+    let mut parser = preceded(tag_no_case(b"LANGUAGE"), many0(preceded(sp, astring)));
+
+    let (remaining, ranges) = parser(input)?;
+
+    Ok((remaining, CommandBody::Language { ranges }))
+}
+
+/// ```abnf
+/// comparator-cmd = "COMPARATOR" *(SP comp-order-quoted)
+///
+/// comp-order-quoted = astring
+/// ```
+pub(crate) fn comparator(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
+    let mut parser = preceded(tag_no_case(b"COMPARATOR"), many0(preceded(sp, astring)));
+
+    let (remaining, comparators) = parser(input)?;
+
+    Ok((remaining, CommandBody::Comparator { comparators }))
+}
+
+/// ```abnf
+/// language-data = "LANGUAGE" SP "(" lang-tag-quoted *(SP lang-tag-quoted) ")"
+///
+/// lang-tag-quoted = astring
+/// ```
+pub(crate) fn language_data(input: &[u8]) -> IMAPResult<&[u8], Data> {
+    let mut parser = preceded(
+        tag_no_case(b"LANGUAGE "),
+        delimited(tag(b"("), separated_list1(sp, astring), tag(b")")),
+    );
+
+    let (remaining, tags) = parser(input)?;
+
+    Ok((
+        remaining,
+        Data::Language {
+            // Safety: Safe because we use `separated_list1` above.
+            tags: NonEmptyVec::unvalidated(tags),
+        },
+    ))
+}
+
+/// ```abnf
+/// comparator-data = "COMPARATOR" SP comp-sel-quoted [SP "(" comp-id-quoted *(SP comp-id-quoted) ")"]
+///
+/// comp-sel-quoted = astring
+///
+/// comp-id-quoted = astring
+/// ```
+pub(crate) fn comparator_data(input: &[u8]) -> IMAPResult<&[u8], Data> {
+    let mut parser = tuple((
+        tag_no_case(b"COMPARATOR "),
+        astring,
+        opt(preceded(
+            sp,
+            delimited(tag(b"("), separated_list1(sp, astring), tag(b")")),
+        )),
+    ));
+
+    let (remaining, (_, selected, matching)) = parser(input)?;
+
+    Ok((
+        remaining,
+        Data::Comparator {
+            selected,
+            matching: matching.unwrap_or_default(),
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use imap_types::{
+        command::Command,
+        core::{AString, Tag},
+        response::{Capability, Code, Response, Status},
+    };
+
+    use super::*;
+    use crate::testing::{kat_inverse_command, kat_inverse_response};
+
+    #[test]
+    fn test_kat_inverse_command_language() {
+        kat_inverse_command(&[
+            (
+                b"A LANGUAGE\r\n".as_ref(),
+                b"".as_ref(),
+                Command::new("A", CommandBody::language(vec![]).unwrap()).unwrap(),
+            ),
+            (
+                b"A LANGUAGE fr de-CH \"*\"\r\n".as_ref(),
+                b"".as_ref(),
+                Command::new(
+                    "A",
+                    CommandBody::language(vec![
+                        AString::try_from("fr").unwrap(),
+                        AString::try_from("de-CH").unwrap(),
+                        AString::try_from("*").unwrap(),
+                    ])
+                    .unwrap(),
+                )
+                .unwrap(),
+            ),
+            (
+                b"A COMPARATOR\r\n".as_ref(),
+                b"".as_ref(),
+                Command::new("A", CommandBody::comparator(vec![]).unwrap()).unwrap(),
+            ),
+            (
+                b"A COMPARATOR \"cz;*\" i;basic\r\n".as_ref(),
+                b"".as_ref(),
+                Command::new(
+                    "A",
+                    CommandBody::comparator(vec![
+                        AString::try_from("cz;*").unwrap(),
+                        AString::try_from("i;basic").unwrap(),
+                    ])
+                    .unwrap(),
+                )
+                .unwrap(),
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_kat_inverse_response_language() {
+        kat_inverse_response(&[
+            (
+                b"* LANGUAGE (EN DE IT i-default)\r\n".as_ref(),
+                b"".as_ref(),
+                Response::Data(
+                    Data::language(vec![
+                        AString::try_from("EN").unwrap(),
+                        AString::try_from("DE").unwrap(),
+                        AString::try_from("IT").unwrap(),
+                        AString::try_from("i-default").unwrap(),
+                    ])
+                    .unwrap(),
+                ),
+            ),
+            (
+                b"* COMPARATOR i;basic\r\n".as_ref(),
+                b"".as_ref(),
+                Response::Data(Data::comparator("i;basic", vec![]).unwrap()),
+            ),
+            (
+                b"* COMPARATOR i;basic (i;basic i;octet)\r\n".as_ref(),
+                b"".as_ref(),
+                Response::Data(
+                    Data::comparator(
+                        "i;basic",
+                        vec![
+                            AString::try_from("i;basic").unwrap(),
+                            AString::try_from("i;octet").unwrap(),
+                        ],
+                    )
+                    .unwrap(),
+                ),
+            ),
+            (
+                b"A NO [BADCOMPARATOR] Unknown comparator\r\n".as_ref(),
+                b"".as_ref(),
+                Response::Status(
+                    Status::no(
+                        Some(Tag::try_from("A").unwrap()),
+                        Some(Code::BadComparator),
+                        "Unknown comparator",
+                    )
+                    .unwrap(),
+                ),
+            ),
+            (
+                b"* CAPABILITY IMAP4REV1 LANGUAGE I18NLEVEL=2\r\n".as_ref(),
+                b"".as_ref(),
+                Response::Data(Data::Capability(
+                    vec![
+                        Capability::Imap4Rev1,
+                        Capability::Language,
+                        Capability::I18nLevel(2),
+                    ]
+                    .try_into()
+                    .unwrap(),
+                )),
+            ),
+        ]);
+    }
+}
@@ -0,0 +1,240 @@
+//! IMAP4 Multimailbox SEARCH Extension
+
+use std::io::Write;
+
+use abnf_core::streaming::sp;
+use imap_types::{
+    command::CommandBody, core::NonEmptyVec, extensions::multisearch::FilterMailboxes,
+    mailbox::Mailbox,
+};
+use nom::{
+    branch::alt,
+    bytes::streaming::{tag, tag_no_case},
+    combinator::{map, opt, value},
+    sequence::{delimited, preceded, tuple},
+};
+
+use crate::{
+    decode::{separated_list1, IMAPResult},
+    encode::{utils::join_serializable, EncodeContext, EncodeIntoContext},
+    extensions::esearch::search_return_opts,
+    mailbox::mailbox,
+    search::search_program,
+};
+
+/// ```abnf
+/// esearch = "ESEARCH" [SP esearch-source-opts] [search-return-opts] SP search-program
+///
+/// esearch-source-opts = "IN" SP "(" source-mbox ")"
+///
+/// source-mbox = filter-mailboxes *(SP filter-mailboxes)
+/// ```
+///
+/// Note: `scope-options` are not supported.
+pub(crate) fn esearch(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
+    let mut parser = tuple((
+        tag_no_case(b"ESEARCH"),
+        opt(preceded(
+            tag_no_case(b" IN "),
+            delimited(tag(b"("), separated_list1(sp, filter_mailboxes), tag(b")")),
+        )),
+        opt(search_return_opts),
+        search_program,
+    ));
+
+    let (remaining, (_, source_options, return_options, (charset, criteria))) = parser(input)?;
+
+    Ok((
+        remaining,
+        CommandBody::ESearch {
+            source_options: source_options.unwrap_or_default(),
+            return_options: return_options.unwrap_or_default(),
+            charset,
+            criteria,
+        },
+    ))
+}
+
+/// ```abnf
+/// filter-mailboxes = filter-mailboxes-selected / filter-mailboxes-other
+///
+/// filter-mailboxes-selected = "selected" / "selected-delayed"
+///
+/// filter-mailboxes-other = "inboxes" / "personal" / "subscribed" /
+///                          ("subtree" SP one-or-more-mailbox) /
+///                          ("subtree-one" SP one-or-more-mailbox) /
+///                          ("mailboxes" SP one-or-more-mailbox)
+/// ```
+pub(crate) fn filter_mailboxes(input: &[u8]) -> IMAPResult<&[u8], FilterMailboxes> {
+    alt((
+        value(
+            FilterMailboxes::SelectedDelayed,
+            tag_no_case(b"selected-delayed"),
+        ),
+        value(FilterMailboxes::Selected, tag_no_case(b"selected")),
+        value(FilterMailboxes::Inboxes, tag_no_case(b"inboxes")),
+        value(FilterMailboxes::Personal, tag_no_case(b"personal")),
+        value(FilterMailboxes::Subscribed, tag_no_case(b"subscribed")),
+        map(
+            preceded(tag_no_case(b"subtree-one "), one_or_more_mailbox),
+            FilterMailboxes::SubtreeOne,
+        ),
+        map(
+            preceded(tag_no_case(b"subtree "), one_or_more_mailbox),
+            FilterMailboxes::Subtree,
+        ),
+        map(
+            preceded(tag_no_case(b"mailboxes "), one_or_more_mailbox),
+            FilterMailboxes::Mailboxes,
+        ),
+    ))(input)
+}
+
+/// ```abnf
+/// one-or-more-mailbox = mailbox / many-mailboxes
+///
+/// many-mailboxes = "(" mailbox *(SP mailbox) ")"
+/// ```
+pub(crate) fn one_or_more_mailbox(input: &[u8]) -> IMAPResult<&[u8], NonEmptyVec<Mailbox>> {
+    alt((
+        map(
+            delimited(tag(b"("), separated_list1(sp, mailbox), tag(b")")),
+            // Safety: Safe because we use `separated_list1` above.
+            NonEmptyVec::unvalidated,
+        ),
+        map(mailbox, NonEmptyVec::from),
+    ))(input)
+}
+
+impl<'a> EncodeIntoContext for FilterMailboxes<'a> {
+    fn encode_ctx(&self, ctx: &mut EncodeContext) -> std::io::Result<()> {
+        let (name, mailboxes) = match self {
+            Self::Selected => return ctx.write_all(b"selected"),
+            Self::SelectedDelayed => return ctx.write_all(b"selected-delayed"),
+            Self::Inboxes => return ctx.write_all(b"inboxes"),
+            Self::Personal => return ctx.write_all(b"personal"),
+            Self::Subscribed => return ctx.write_all(b"subscribed"),
+            Self::Subtree(mailboxes) => ("subtree", mailboxes),
+            Self::SubtreeOne(mailboxes) => ("subtree-one", mailboxes),
+            Self::Mailboxes(mailboxes) => ("mailboxes", mailboxes),
+        };
+
+        write!(ctx, "{name} ")?;
+
+        match mailboxes.as_ref() {
+            [mailbox] => mailbox.encode_ctx(ctx),
+            mailboxes => {
+                ctx.write_all(b"(")?;
+                join_serializable(mailboxes, b" ", ctx)?;
+                ctx.write_all(b")")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroU32;
+
+    use imap_types::{
+        command::Command,
+        core::Tag,
+        extensions::esearch::{SearchCorrelator, SearchReturnData, SearchReturnOption},
+        response::{Capability, Data, Response},
+        search::SearchKey,
+        sequence::SequenceSet,
+    };
+
+    use super::*;
+    use crate::testing::{kat_inverse_command, kat_inverse_response};
+
+    #[test]
+    fn test_kat_inverse_command_multisearch() {
+        kat_inverse_command(&[
+            (
+                b"A ESEARCH IN (personal) RETURN (COUNT) UNSEEN\r\n".as_ref(),
+                b"".as_ref(),
+                Command::new(
+                    "A",
+                    CommandBody::esearch(
+                        vec![FilterMailboxes::Personal],
+                        vec![SearchReturnOption::Count],
+                        None,
+                        SearchKey::Unseen,
+                    ),
+                )
+                .unwrap(),
+            ),
+            (
+                b"A ESEARCH IN (mailboxes (folder1 folder2) subtree-one INBOX) FLAGGED\r\n"
+                    .as_ref(),
+                b"".as_ref(),
+                Command::new(
+                    "A",
+                    CommandBody::esearch(
+                        vec![
+                            FilterMailboxes::Mailboxes(
+                                vec![
+                                    Mailbox::try_from("folder1").unwrap(),
+                                    Mailbox::try_from("folder2").unwrap(),
+                                ]
+                                .try_into()
+                                .unwrap(),
+                            ),
+                            FilterMailboxes::SubtreeOne(NonEmptyVec::from(Mailbox::Inbox)),
+                        ],
+                        vec![],
+                        None,
+                        SearchKey::Flagged,
+                    ),
+                )
+                .unwrap(),
+            ),
+            (
+                b"A ESEARCH CHARSET UTF-8 ALL\r\n".as_ref(),
+                b"".as_ref(),
+                Command::new(
+                    "A",
+                    CommandBody::esearch(
+                        vec![],
+                        vec![],
+                        Some("UTF-8".try_into().unwrap()),
+                        SearchKey::All,
+                    ),
+                )
+                .unwrap(),
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_kat_inverse_response_multisearch() {
+        kat_inverse_response(&[
+            (
+                b"* ESEARCH (TAG \"A\" MAILBOX folder1 UIDVALIDITY 1) UID ALL 3001:3004\r\n"
+                    .as_ref(),
+                b"".as_ref(),
+                Response::Data(Data::esearch(
+                    Some(SearchCorrelator::mailbox(
+                        Tag::try_from("A").unwrap(),
+                        Mailbox::try_from("folder1").unwrap(),
+                        NonZeroU32::new(1).unwrap(),
+                    )),
+                    true,
+                    vec![SearchReturnData::All(
+                        SequenceSet::try_from("3001:3004").unwrap(),
+                    )],
+                )),
+            ),
+            (
+                b"* CAPABILITY IMAP4REV1 MULTISEARCH\r\n".as_ref(),
+                b"".as_ref(),
+                Response::Data(Data::Capability(
+                    vec![Capability::Imap4Rev1, Capability::MultiSearch]
+                        .try_into()
+                        .unwrap(),
+                )),
+            ),
+        ]);
+    }
+}
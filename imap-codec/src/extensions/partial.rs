@@ -0,0 +1,201 @@
+//! IMAP PARTIAL Extension
+
+use std::io::Write;
+
+use imap_types::extensions::partial::PartialRange;
+use nom::{
+    branch::alt,
+    bytes::streaming::tag,
+    combinator::map,
+    sequence::{preceded, separated_pair},
+};
+
+use crate::{
+    core::nz_number,
+    decode::IMAPResult,
+    encode::{EncodeContext, EncodeIntoContext},
+};
+
+/// ```abnf
+/// partial-range = partial-range-first / partial-range-last
+///
+/// partial-range-first = nz-number ":" nz-number
+///
+/// partial-range-last = MINUS nz-number ":" MINUS nz-number
+/// ```
+pub(crate) fn partial_range(input: &[u8]) -> IMAPResult<&[u8], PartialRange> {
+    alt((
+        map(
+            separated_pair(nz_number, tag(b":"), nz_number),
+            |(first, last)| PartialRange::First(first, last),
+        ),
+        map(
+            separated_pair(
+                preceded(tag(b"-"), nz_number),
+                tag(b":"),
+                preceded(tag(b"-"), nz_number),
+            ),
+            |(first, last)| PartialRange::Last(first, last),
+        ),
+    ))(input)
+}
+
+impl EncodeIntoContext for PartialRange {
+    fn encode_ctx(&self, ctx: &mut EncodeContext) -> std::io::Result<()> {
+        match self {
+            Self::First(first, last) => write!(ctx, "{first}:{last}"),
+            Self::Last(first, last) => write!(ctx, "-{first}:-{last}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroU32;
+
+    use imap_types::{
+        command::{Command, CommandBody},
+        core::Tag,
+        extensions::esearch::{SearchReturnData, SearchReturnOption},
+        fetch::{FetchModifier, MacroOrMessageDataItemNames, MessageDataItemName},
+        response::{Capability, Data, Response},
+        search::SearchKey,
+        sequence::SequenceSet,
+    };
+
+    use super::*;
+    use crate::testing::{kat_inverse_command, kat_inverse_response, known_answer_test_encode};
+
+    #[test]
+    fn test_parse_partial_range() {
+        let tests = [
+            (
+                b"1:100 ".as_ref(),
+                PartialRange::First(NonZeroU32::new(1).unwrap(), NonZeroU32::new(100).unwrap()),
+            ),
+            (
+                b"-1:-50 ".as_ref(),
+                PartialRange::Last(NonZeroU32::new(1).unwrap(), NonZeroU32::new(50).unwrap()),
+            ),
+        ];
+
+        for (test, expected) in tests {
+            let (rem, got) = partial_range(test).unwrap();
+            assert_eq!(expected, got);
+            assert_eq!(rem, b" ");
+        }
+
+        assert!(partial_range(b"0:10 ").is_err());
+        assert!(partial_range(b"-1:10 ").is_err());
+    }
+
+    #[test]
+    fn test_encode_partial_range() {
+        let tests = [
+            (
+                PartialRange::First(NonZeroU32::new(101).unwrap(), NonZeroU32::new(200).unwrap()),
+                b"101:200".as_ref(),
+            ),
+            (
+                PartialRange::Last(NonZeroU32::new(1).unwrap(), NonZeroU32::new(50).unwrap()),
+                b"-1:-50",
+            ),
+        ];
+
+        for test in tests {
+            known_answer_test_encode(test);
+        }
+    }
+
+    #[test]
+    fn test_kat_inverse_command_partial() {
+        kat_inverse_command(&[
+            (
+                b"A UID SEARCH RETURN (PARTIAL -1:-50) UNDELETED\r\n".as_ref(),
+                b"".as_ref(),
+                Command::new(
+                    "A",
+                    CommandBody::search_return(
+                        None,
+                        SearchKey::Undeleted,
+                        vec![SearchReturnOption::Partial(PartialRange::Last(
+                            NonZeroU32::new(1).unwrap(),
+                            NonZeroU32::new(50).unwrap(),
+                        ))],
+                        true,
+                    ),
+                )
+                .unwrap(),
+            ),
+            (
+                b"A UID FETCH 1:* (FLAGS) (PARTIAL -1:-30)\r\n".as_ref(),
+                b"".as_ref(),
+                Command::new(
+                    "A",
+                    CommandBody::fetch_with_modifiers(
+                        "1:*",
+                        MacroOrMessageDataItemNames::MessageDataItemNames(vec![
+                            MessageDataItemName::Flags,
+                        ]),
+                        vec![FetchModifier::Partial(PartialRange::Last(
+                            NonZeroU32::new(1).unwrap(),
+                            NonZeroU32::new(30).unwrap(),
+                        ))],
+                        true,
+                    )
+                    .unwrap(),
+                )
+                .unwrap(),
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_kat_inverse_response_partial() {
+        kat_inverse_response(&[
+            (
+                b"* ESEARCH (TAG \"A\") UID PARTIAL (-1:-50 200:250,252:300)\r\n".as_ref(),
+                b"".as_ref(),
+                Response::Data(Data::esearch(
+                    Some(Tag::try_from("A").unwrap().into()),
+                    true,
+                    vec![SearchReturnData::Partial {
+                        range: PartialRange::Last(
+                            NonZeroU32::new(1).unwrap(),
+                            NonZeroU32::new(50).unwrap(),
+                        ),
+                        results: Some(SequenceSet::try_from("200:250,252:300").unwrap()),
+                    }],
+                )),
+            ),
+            (
+                b"* ESEARCH (TAG \"B\") UID PARTIAL (1:100 NIL)\r\n".as_ref(),
+                b"".as_ref(),
+                Response::Data(Data::esearch(
+                    Some(Tag::try_from("B").unwrap().into()),
+                    true,
+                    vec![SearchReturnData::Partial {
+                        range: PartialRange::First(
+                            NonZeroU32::new(1).unwrap(),
+                            NonZeroU32::new(100).unwrap(),
+                        ),
+                        results: None,
+                    }],
+                )),
+            ),
+            (
+                b"* CAPABILITY IMAP4REV1 ESEARCH PARTIAL\r\n".as_ref(),
+                b"".as_ref(),
+                Response::Data(Data::Capability(
+                    vec![
+                        Capability::Imap4Rev1,
+                        Capability::ESearch,
+                        Capability::Partial,
+                    ]
+                    .try_into()
+                    .unwrap(),
+                )),
+            ),
+        ]);
+    }
+}
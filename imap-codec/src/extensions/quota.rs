@@ -12,13 +12,12 @@ use imap_types::{
 use nom::{
     bytes::streaming::{tag, tag_no_case},
     combinator::map,
-    multi::{many0, separated_list0, separated_list1},
     sequence::{delimited, preceded, tuple},
 };
 
 use crate::{
     core::{astring, atom, number64},
-    decode::IMAPResult,
+    decode::{many0, separated_list0, separated_list1, IMAPResult},
     encode::{EncodeContext, EncodeIntoContext},
     mailbox::mailbox,
 };
@@ -474,6 +473,18 @@ mod tests {
                     .unwrap(),
                 ),
             ),
+            (
+                b"* CAPABILITY QUOTA QUOTA=RES-MAILBOX QUOTA=RES-ANNOTATION-STORAGE\r\n",
+                b"",
+                Response::Data(
+                    Data::capability(vec![
+                        Capability::Quota,
+                        Capability::QuotaRes(Resource::Mailbox),
+                        Capability::QuotaRes(Resource::AnnotationStorage),
+                    ])
+                    .unwrap(),
+                ),
+            ),
             (
                 b"* CAPABILITY QUOTA QUOTASET QUOTA=RES-STORAGE QUOTA=RES-MESSAGE\r\n",
                 b"",
@@ -0,0 +1,230 @@
+//! IMAP4 Login Referrals and IMAP4 Mailbox Referrals
+
+use std::str::from_utf8;
+
+use abnf_core::streaming::sp;
+#[cfg(feature = "ext_mailbox_referrals")]
+use imap_types::command::CommandBody;
+use imap_types::{core::NonEmptyVec, extensions::referrals::ImapUrl, response::Code};
+#[cfg(feature = "ext_mailbox_referrals")]
+use nom::sequence::tuple;
+use nom::{
+    bytes::streaming::{tag_no_case, take_while1},
+    combinator::map,
+    error::ErrorKind,
+    sequence::preceded,
+};
+
+use crate::decode::{separated_list1, IMAPErrorKind, IMAPParseError, IMAPResult};
+#[cfg(feature = "ext_mailbox_referrals")]
+use crate::mailbox::{list_mailbox, mailbox};
+
+/// ```abnf
+/// referral-response-code = "REFERRAL" 1*(SP imapurl)
+/// ```
+pub(crate) fn referral(input: &[u8]) -> IMAPResult<&[u8], Code> {
+    map(
+        preceded(
+            tag_no_case(b"REFERRAL"),
+            preceded(sp, separated_list1(sp, imap_url)),
+        ),
+        // Safety: Safe because we use `separated_list1` above.
+        |urls| Code::Referral(NonEmptyVec::unvalidated(urls)),
+    )(input)
+}
+
+/// ```abnf
+/// imapurl = "imap://" iserver "/" [enc-mailbox [uidvalidity]]
+/// ```
+///
+/// Note: We first take everything up to the next SP or "]" and validate it afterwards.
+pub(crate) fn imap_url(input: &[u8]) -> IMAPResult<&[u8], ImapUrl> {
+    let (remaining, url) = take_while1(is_url_char)(input)?;
+
+    // # Safety
+    //
+    // `unwrap` is safe, because `is_url_char` only accepts ASCII.
+    match ImapUrl::try_from(from_utf8(url).unwrap()) {
+        Ok(url) => Ok((remaining, url)),
+        Err(_) => Err(nom::Err::Error(IMAPParseError {
+            input,
+            context: Vec::new(),
+            kind: IMAPErrorKind::Nom(ErrorKind::Verify),
+        })),
+    }
+}
+
+fn is_url_char(byte: u8) -> bool {
+    byte.is_ascii_graphic() && byte != b']'
+}
+
+/// ```abnf
+/// rlist = "RLIST" SP mailbox SP list-mailbox
+/// ```
+#[cfg(feature = "ext_mailbox_referrals")]
+pub(crate) fn rlist(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
+    let mut parser = tuple((tag_no_case(b"RLIST"), sp, mailbox, sp, list_mailbox));
+
+    let (remaining, (_, _, reference, _, mailbox_wildcard)) = parser(input)?;
+
+    Ok((
+        remaining,
+        CommandBody::RList {
+            reference,
+            mailbox_wildcard,
+        },
+    ))
+}
+
+/// ```abnf
+/// rlsub = "RLSUB" SP mailbox SP list-mailbox
+/// ```
+#[cfg(feature = "ext_mailbox_referrals")]
+pub(crate) fn rlsub(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
+    let mut parser = tuple((tag_no_case(b"RLSUB"), sp, mailbox, sp, list_mailbox));
+
+    let (remaining, (_, _, reference, _, mailbox_wildcard)) = parser(input)?;
+
+    Ok((
+        remaining,
+        CommandBody::RLsub {
+            reference,
+            mailbox_wildcard,
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "ext_mailbox_referrals")]
+    use imap_types::{
+        command::Command,
+        flag::FlagNameAttribute,
+        mailbox::Mailbox,
+        response::{Capability, Data},
+    };
+    use imap_types::{
+        core::Tag,
+        response::{Response, Status},
+    };
+
+    use super::*;
+    #[cfg(feature = "ext_mailbox_referrals")]
+    use crate::testing::kat_inverse_command;
+    use crate::{decode::Decoder, testing::kat_inverse_response, ResponseCodec};
+
+    #[test]
+    fn test_kat_inverse_response_referral() {
+        kat_inverse_response(&[
+            (
+                b"A NO [REFERRAL imap://user;AUTH=*@SERVER2/] Remote Server\r\n".as_ref(),
+                b"".as_ref(),
+                Response::Status(Status::No {
+                    tag: Some(Tag::try_from("A").unwrap()),
+                    code: Some(Code::Referral(NonEmptyVec::from(
+                        ImapUrl::try_from("imap://user;AUTH=*@SERVER2/").unwrap(),
+                    ))),
+                    text: "Remote Server".try_into().unwrap(),
+                }),
+            ),
+            (
+                b"* BYE [REFERRAL imap://user@server2.example.com:1430/] Server not accepting connections.  Try SERVER2\r\n".as_ref(),
+                b"".as_ref(),
+                Response::Status(Status::Bye {
+                    code: Some(Code::Referral(NonEmptyVec::from(
+                        ImapUrl::try_from("imap://user@server2.example.com:1430/").unwrap(),
+                    ))),
+                    text: "Server not accepting connections.  Try SERVER2"
+                        .try_into()
+                        .unwrap(),
+                }),
+            ),
+            (
+                b"B NO [REFERRAL imap://server2/SHARED/FOO;UIDVALIDITY=385759045] Remote mailbox. Try SERVER2.\r\n".as_ref(),
+                b"".as_ref(),
+                Response::Status(Status::No {
+                    tag: Some(Tag::try_from("B").unwrap()),
+                    code: Some(Code::Referral(NonEmptyVec::from(
+                        ImapUrl::try_from("imap://server2/SHARED/FOO;UIDVALIDITY=385759045")
+                            .unwrap(),
+                    ))),
+                    text: "Remote mailbox. Try SERVER2.".try_into().unwrap(),
+                }),
+            ),
+            (
+                b"* NO [REFERRAL imap://user;AUTH=*@SERVER2/SHARED/FOO imap://user;AUTH=*@SERVER3/SHARED/FOO] Remote mailbox.\r\n".as_ref(),
+                b"".as_ref(),
+                Response::Status(Status::No {
+                    tag: None,
+                    code: Some(Code::Referral(
+                        vec![
+                            ImapUrl::try_from("imap://user;AUTH=*@SERVER2/SHARED/FOO").unwrap(),
+                            ImapUrl::try_from("imap://user;AUTH=*@SERVER3/SHARED/FOO").unwrap(),
+                        ]
+                        .try_into()
+                        .unwrap(),
+                    )),
+                    text: "Remote mailbox.".try_into().unwrap(),
+                }),
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_decode_referral_multiple_urls() {
+        let (remaining, response) = ResponseCodec::default()
+            .decode(b"* NO [REFERRAL IMAP://user;AUTH=*@SERVER2/SHARED/FOO IMAP://user;AUTH=*@SERVER3/SHARED/FOO] Remote\r\n")
+            .unwrap();
+
+        assert!(remaining.is_empty());
+        match response {
+            Response::Status(Status::No {
+                code: Some(Code::Referral(urls)),
+                ..
+            }) => assert_eq!(urls.as_ref().len(), 2),
+            _ => panic!("Expected `Code::Referral`, got {response:?}"),
+        }
+    }
+
+    #[cfg(feature = "ext_mailbox_referrals")]
+    #[test]
+    fn test_kat_inverse_command_rlist_rlsub() {
+        kat_inverse_command(&[
+            (
+                b"A RLIST \"\" %\r\n".as_ref(),
+                b"".as_ref(),
+                Command::new("A", CommandBody::rlist("", "%").unwrap()).unwrap(),
+            ),
+            (
+                b"A RLSUB foo/ bar/*\r\n?".as_ref(),
+                b"?".as_ref(),
+                Command::new("A", CommandBody::rlsub("foo/", "bar/*").unwrap()).unwrap(),
+            ),
+        ]);
+    }
+
+    #[cfg(feature = "ext_mailbox_referrals")]
+    #[test]
+    fn test_kat_inverse_response_remote() {
+        kat_inverse_response(&[
+            (
+                b"* CAPABILITY IMAP4REV1 MAILBOX-REFERRALS\r\n".as_ref(),
+                b"".as_ref(),
+                Response::Data(Data::Capability(
+                    vec![Capability::Imap4Rev1, Capability::MailboxReferrals]
+                        .try_into()
+                        .unwrap(),
+                )),
+            ),
+            (
+                b"* LIST (\\Remote \\Noinferiors) \"/\" SHARED/FOO\r\n".as_ref(),
+                b"".as_ref(),
+                Response::Data(Data::List {
+                    items: vec![FlagNameAttribute::Remote, FlagNameAttribute::Noinferiors],
+                    delimiter: Some('/'.try_into().unwrap()),
+                    mailbox: Mailbox::try_from("SHARED/FOO").unwrap(),
+                }),
+            ),
+        ]);
+    }
+}
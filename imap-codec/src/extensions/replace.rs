@@ -0,0 +1,156 @@
+//! IMAP REPLACE Extension
+
+use abnf_core::streaming::sp;
+use imap_types::command::CommandBody;
+use nom::{bytes::streaming::tag_no_case, sequence::tuple};
+
+use crate::{command::append_message, decode::IMAPResult, mailbox::mailbox, sequence::seq_number};
+
+/// ```abnf
+/// replace = "REPLACE" SP seq-number SP mailbox append-message
+/// ```
+pub(crate) fn replace(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
+    let mut parser = tuple((
+        tag_no_case(b"REPLACE"),
+        sp,
+        seq_number,
+        sp,
+        mailbox,
+        append_message,
+    ));
+
+    let (remaining, (_, _, sequence_number_or_uid, _, mailbox, (flags, date, message))) =
+        parser(input)?;
+
+    Ok((
+        remaining,
+        CommandBody::Replace {
+            sequence_number_or_uid,
+            mailbox,
+            flags,
+            date,
+            message,
+            uid: false,
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use imap_types::{
+        command::{Command, CommandBody},
+        core::LiteralMode,
+        datetime::DateTime,
+        flag::Flag,
+        response::{Capability, Data, Response},
+        sequence::SeqOrUid,
+    };
+
+    use crate::{
+        decode::{CommandDecodeError, Decoder},
+        encode::{Encoder, Fragment},
+        testing::{kat_inverse_command, kat_inverse_response},
+        CommandCodec,
+    };
+
+    #[test]
+    fn test_kat_inverse_command_replace() {
+        kat_inverse_command(&[
+            (
+                b"A REPLACE 4 Drafts {5+}\r\nHello\r\n".as_ref(),
+                b"".as_ref(),
+                Command::new(
+                    "A",
+                    CommandBody::replace(4, "Drafts", vec![], None, "Hello", false)
+                        .map(|mut body| {
+                            if let CommandBody::Replace { message, .. } = &mut body {
+                                message.set_mode(LiteralMode::NonSync);
+                            }
+                            body
+                        })
+                        .unwrap(),
+                )
+                .unwrap(),
+            ),
+            (
+                b"A UID REPLACE 1337 INBOX (\\Seen \\Draft) \"01-Jul-2003 10:52:37 +0200\" {5}\r\nHello\r\n?"
+                    .as_ref(),
+                b"?".as_ref(),
+                Command::new(
+                    "A",
+                    CommandBody::replace(
+                        1337,
+                        "INBOX",
+                        vec![Flag::Seen, Flag::Draft],
+                        Some(
+                            DateTime::try_from(
+                                chrono::DateTime::parse_from_rfc2822(
+                                    "Tue, 1 Jul 2003 10:52:37 +0200",
+                                )
+                                .unwrap(),
+                            )
+                            .unwrap(),
+                        ),
+                        "Hello",
+                        true,
+                    )
+                    .unwrap(),
+                )
+                .unwrap(),
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_replace_literal_found() {
+        assert_eq!(
+            CommandCodec::default().decode(b"A REPLACE * Drafts {5}\r\n"),
+            Err(CommandDecodeError::LiteralFound {
+                tag: "A".try_into().unwrap(),
+                length: 5,
+                mode: LiteralMode::Sync,
+            })
+        );
+    }
+
+    #[test]
+    fn test_encode_replace_fragments() {
+        let command = Command::new(
+            "A",
+            CommandBody::replace(SeqOrUid::Asterisk, "Drafts", vec![], None, "Hello", false)
+                .unwrap(),
+        )
+        .unwrap();
+
+        let fragments: Vec<_> = CommandCodec::default().encode(&command).collect();
+
+        assert_eq!(
+            fragments,
+            [
+                Fragment::Line {
+                    data: b"A REPLACE * Drafts {5}\r\n".to_vec()
+                },
+                Fragment::Literal {
+                    data: b"Hello".to_vec(),
+                    mode: LiteralMode::Sync
+                },
+                Fragment::Line {
+                    data: b"\r\n".to_vec()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_kat_inverse_response_replace() {
+        kat_inverse_response(&[(
+            b"* CAPABILITY IMAP4REV1 REPLACE\r\n".as_ref(),
+            b"".as_ref(),
+            Response::Data(Data::Capability(
+                vec![Capability::Imap4Rev1, Capability::Replace]
+                    .try_into()
+                    .unwrap(),
+            )),
+        )]);
+    }
+}
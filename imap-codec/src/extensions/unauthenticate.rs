@@ -0,0 +1,31 @@
+#[cfg(test)]
+mod tests {
+    use imap_types::{
+        command::{Command, CommandBody},
+        response::{Capability, Data, Response},
+    };
+
+    use crate::testing::{kat_inverse_command, kat_inverse_response};
+
+    #[test]
+    fn test_kat_inverse_command_unauthenticate() {
+        kat_inverse_command(&[(
+            b"A UNAUTHENTICATE\r\n".as_ref(),
+            b"".as_ref(),
+            Command::new("A", CommandBody::unauthenticate()).unwrap(),
+        )]);
+    }
+
+    #[test]
+    fn test_kat_inverse_response_unauthenticate() {
+        kat_inverse_response(&[(
+            b"* CAPABILITY IMAP4REV1 UNAUTHENTICATE\r\n".as_ref(),
+            b"".as_ref(),
+            Response::Data(Data::Capability(
+                vec![Capability::Imap4Rev1, Capability::Unauthenticate]
+                    .try_into()
+                    .unwrap(),
+            )),
+        )]);
+    }
+}
@@ -2,26 +2,49 @@ use std::num::NonZeroU32;
 
 use abnf_core::streaming::sp;
 use imap_types::{
-    core::{AString, NonEmptyVec},
-    fetch::{MessageDataItem, MessageDataItemName, Part, PartSpecifier, Section},
+    core::{AString, NString, NonEmptyVec},
+    fetch::{FetchModifier, MessageDataItem, MessageDataItemName, Part, PartSpecifier, Section},
 };
 use nom::{
     branch::alt,
     bytes::streaming::{tag, tag_no_case},
     combinator::{map, opt, value},
-    multi::separated_list1,
-    sequence::{delimited, tuple},
+    error::context,
+    sequence::{delimited, preceded, tuple},
 };
 
 use crate::{
     body::body,
-    core::{astring, nstring, number, nz_number},
+    core::{astring, nil, nstring, number, number64, nz_number},
     datetime::date_time,
-    decode::IMAPResult,
+    decode::{limits, separated_list1, IMAPResult},
     envelope::envelope,
+    extensions::{
+        annotate::{fetch_annotation, fetch_annotation_resp},
+        gmail::gmail_label_list,
+        partial::partial_range,
+    },
     flag::flag_fetch,
 };
 
+/// ```abnf
+/// fetch-modifiers = SP "(" fetch-modifier *(SP fetch-modifier) ")"
+/// ```
+pub(crate) fn fetch_modifiers(input: &[u8]) -> IMAPResult<&[u8], Vec<FetchModifier>> {
+    preceded(
+        sp,
+        delimited(tag(b"("), separated_list1(sp, fetch_modifier), tag(b")")),
+    )(input)
+}
+
+/// `fetch-modifier =/ "PARTIAL" SP partial-range` (RFC 9394)
+pub(crate) fn fetch_modifier(input: &[u8]) -> IMAPResult<&[u8], FetchModifier> {
+    map(
+        preceded(tag_no_case(b"PARTIAL "), partial_range),
+        FetchModifier::Partial,
+    )(input)
+}
+
 /// `fetch-att = "ENVELOPE" /
 ///              "FLAGS" /
 ///              "INTERNALDATE" /
@@ -30,6 +53,14 @@ use crate::{
 ///              "UID" /
 ///              "BODY" section ["<" number "." nz-number ">"] /
 ///              "BODY.PEEK" section ["<" number "." nz-number ">"]`
+///
+/// `fetch-att =/ "SAVEDATE"` (RFC 8514)
+///
+/// `fetch-att =/ "PREVIEW" [SP "(" preview-mod *(SP preview-mod) ")"]` (RFC 8970)
+///
+/// `fetch-att =/ "X-GM-MSGID" / "X-GM-THRID" / "X-GM-LABELS"`
+///
+/// `fetch-att =/ fetch-annotation` (RFC 5257)
 pub(crate) fn fetch_att(input: &[u8]) -> IMAPResult<&[u8], MessageDataItemName> {
     alt((
         value(MessageDataItemName::Envelope, tag_no_case(b"ENVELOPE")),
@@ -83,6 +114,34 @@ pub(crate) fn fetch_att(input: &[u8]) -> IMAPResult<&[u8], MessageDataItemName>
         value(MessageDataItemName::Rfc822Size, tag_no_case(b"RFC822.SIZE")),
         value(MessageDataItemName::Rfc822Text, tag_no_case(b"RFC822.TEXT")),
         value(MessageDataItemName::Rfc822, tag_no_case(b"RFC822")),
+        value(MessageDataItemName::SaveDate, tag_no_case(b"SAVEDATE")),
+        map(
+            tuple((
+                tag_no_case(b"PREVIEW"),
+                // Note: "LAZY" is the only `preview-mod` defined so far.
+                opt(delimited(
+                    tag(b" ("),
+                    separated_list1(sp, tag_no_case(b"LAZY")),
+                    tag(b")"),
+                )),
+            )),
+            |(_, lazy)| MessageDataItemName::Preview {
+                lazy: lazy.is_some(),
+            },
+        ),
+        value(
+            MessageDataItemName::GmailMessageId,
+            tag_no_case(b"X-GM-MSGID"),
+        ),
+        value(
+            MessageDataItemName::GmailThreadId,
+            tag_no_case(b"X-GM-THRID"),
+        ),
+        value(
+            MessageDataItemName::GmailLabels,
+            tag_no_case(b"X-GM-LABELS"),
+        ),
+        fetch_annotation,
     ))(input)
 }
 
@@ -100,19 +159,38 @@ pub(crate) fn msg_att(input: &[u8]) -> IMAPResult<&[u8], NonEmptyVec<MessageData
     )(input)
 }
 
-/// `msg-att-dynamic = "FLAGS" SP "(" [flag-fetch *(SP flag-fetch)] ")"`
+/// ```abnf
+/// msg-att-dynamic = "FLAGS" SP "(" [flag-fetch *(SP flag-fetch)] ")"
+///
+/// msg-att-dynamic =/ "X-GM-LABELS" SP label-list
+///
+/// msg-att-dynamic =/ fetch-annotation-resp ; RFC 5257
+/// ```
 ///
 /// Note: MAY change for a message
 pub(crate) fn msg_att_dynamic(input: &[u8]) -> IMAPResult<&[u8], MessageDataItem> {
-    let mut parser = tuple((
-        tag_no_case(b"FLAGS"),
-        sp,
-        delimited(tag(b"("), opt(separated_list1(sp, flag_fetch)), tag(b")")),
-    ));
-
-    let (remaining, (_, _, flags)) = parser(input)?;
-
-    Ok((remaining, MessageDataItem::Flags(flags.unwrap_or_default())))
+    alt((
+        map(
+            tuple((
+                tag_no_case(b"FLAGS"),
+                sp,
+                context(
+                    "flag-fetch list",
+                    delimited(tag(b"("), opt(separated_list1(sp, flag_fetch)), tag(b")")),
+                ),
+            )),
+            |(_, _, flags)| MessageDataItem::Flags(flags.unwrap_or_default()),
+        ),
+        map(
+            tuple((
+                tag_no_case(b"X-GM-LABELS"),
+                sp,
+                context("label-list", gmail_label_list),
+            )),
+            |(_, _, labels)| MessageDataItem::GmailLabels(labels),
+        ),
+        map(fetch_annotation_resp, MessageDataItem::Annotation),
+    ))(input)
 }
 
 /// `msg-att-static = "ENVELOPE" SP envelope /
@@ -123,6 +201,12 @@ pub(crate) fn msg_att_dynamic(input: &[u8]) -> IMAPResult<&[u8], MessageDataItem
 ///                   "BODY" section ["<" number ">"] SP nstring /
 ///                   "UID" SP uniqueid`
 ///
+/// `msg-att-static =/ "SAVEDATE" SP (date-time / nil)` (RFC 8514)
+///
+/// `msg-att-static =/ "PREVIEW" SP nstring` (RFC 8970)
+///
+/// `msg-att-static =/ "X-GM-MSGID" SP number64 / "X-GM-THRID" SP number64`
+///
 /// Note: MUST NOT change for a message
 pub(crate) fn msg_att_static(input: &[u8]) -> IMAPResult<&[u8], MessageDataItem> {
     alt((
@@ -131,40 +215,52 @@ pub(crate) fn msg_att_static(input: &[u8]) -> IMAPResult<&[u8], MessageDataItem>
             |(_, _, envelope)| MessageDataItem::Envelope(envelope),
         ),
         map(
-            tuple((tag_no_case(b"INTERNALDATE"), sp, date_time)),
+            tuple((
+                tag_no_case(b"INTERNALDATE"),
+                sp,
+                context("date-time", date_time),
+            )),
             |(_, _, date_time)| MessageDataItem::InternalDate(date_time),
         ),
         map(
-            tuple((tag_no_case(b"RFC822.HEADER"), sp, nstring)),
+            tuple((tag_no_case(b"RFC822.HEADER"), sp, message_content)),
             |(_, _, nstring)| MessageDataItem::Rfc822Header(nstring),
         ),
         map(
-            tuple((tag_no_case(b"RFC822.TEXT"), sp, nstring)),
+            tuple((tag_no_case(b"RFC822.TEXT"), sp, message_content)),
             |(_, _, nstring)| MessageDataItem::Rfc822Text(nstring),
         ),
         map(
-            tuple((tag_no_case(b"RFC822.SIZE"), sp, number)),
+            tuple((tag_no_case(b"RFC822.SIZE"), sp, context("number", number))),
             |(_, _, num)| MessageDataItem::Rfc822Size(num),
         ),
         map(
-            tuple((tag_no_case(b"RFC822"), sp, nstring)),
+            tuple((tag_no_case(b"RFC822"), sp, message_content)),
             |(_, _, nstring)| MessageDataItem::Rfc822(nstring),
         ),
         map(
-            tuple((tag_no_case(b"BODYSTRUCTURE"), sp, body(8))),
+            tuple((
+                tag_no_case(b"BODYSTRUCTURE"),
+                sp,
+                body(limits().max_body_structure_nesting),
+            )),
             |(_, _, body)| MessageDataItem::BodyStructure(body),
         ),
         map(
-            tuple((tag_no_case(b"BODY"), sp, body(8))),
+            tuple((
+                tag_no_case(b"BODY"),
+                sp,
+                body(limits().max_body_structure_nesting),
+            )),
             |(_, _, body)| MessageDataItem::Body(body),
         ),
         map(
             tuple((
                 tag_no_case(b"BODY"),
-                section,
+                context("section", section),
                 opt(delimited(tag(b"<"), number, tag(b">"))),
                 sp,
-                nstring,
+                message_content,
             )),
             |(_, section, origin, _, data)| MessageDataItem::BodyExt {
                 section,
@@ -172,12 +268,55 @@ pub(crate) fn msg_att_static(input: &[u8]) -> IMAPResult<&[u8], MessageDataItem>
                 data,
             },
         ),
-        map(tuple((tag_no_case(b"UID"), sp, uniqueid)), |(_, _, uid)| {
-            MessageDataItem::Uid(uid)
-        }),
+        map(
+            tuple((tag_no_case(b"UID"), sp, context("uniqueid", uniqueid))),
+            |(_, _, uid)| MessageDataItem::Uid(uid),
+        ),
+        map(
+            tuple((
+                tag_no_case(b"SAVEDATE"),
+                sp,
+                context(
+                    "date-time / nil",
+                    alt((map(date_time, Some), value(None, nil))),
+                ),
+            )),
+            |(_, _, date_time)| MessageDataItem::SaveDate(date_time),
+        ),
+        map(
+            tuple((tag_no_case(b"PREVIEW"), sp, context("nstring", nstring))),
+            |(_, _, nstring)| MessageDataItem::Preview(nstring),
+        ),
+        map(
+            tuple((
+                tag_no_case(b"X-GM-MSGID"),
+                sp,
+                context("number64", number64),
+            )),
+            |(_, _, id)| MessageDataItem::GmailMessageId(id),
+        ),
+        map(
+            tuple((
+                tag_no_case(b"X-GM-THRID"),
+                sp,
+                context("number64", number64),
+            )),
+            |(_, _, id)| MessageDataItem::GmailThreadId(id),
+        ),
     ))(input)
 }
 
+/// Rule name of the content of `BODY[<section>]<<origin>>`, `RFC822`, `RFC822.HEADER`, and
+/// `RFC822.TEXT`.
+///
+/// Note: The `LiteralSinkDecoder` uses this to find literals that contain message content.
+pub(crate) const MESSAGE_CONTENT: &str = "message content";
+
+/// `nstring` (see [`MESSAGE_CONTENT`])
+fn message_content(input: &[u8]) -> IMAPResult<&[u8], NString> {
+    context(MESSAGE_CONTENT, nstring)(input)
+}
+
 #[inline]
 /// `uniqueid = nz-number`
 ///
@@ -312,6 +451,12 @@ mod tests {
             (MessageDataItemName::Rfc822Size, b"RFC822.SIZE"),
             (MessageDataItemName::Rfc822Text, b"RFC822.TEXT"),
             (MessageDataItemName::Uid, b"UID"),
+            (MessageDataItemName::SaveDate, b"SAVEDATE"),
+            (MessageDataItemName::Preview { lazy: false }, b"PREVIEW"),
+            (
+                MessageDataItemName::Preview { lazy: true },
+                b"PREVIEW (LAZY)",
+            ),
         ];
 
         for test in tests {
@@ -416,6 +561,22 @@ mod tests {
                 MessageDataItem::Uid(NonZeroU32::try_from(u32::MAX).unwrap()),
                 b"UID 4294967295",
             ),
+            (MessageDataItem::SaveDate(None), b"SAVEDATE NIL"),
+            (
+                MessageDataItem::SaveDate(Some(
+                    DateTime::try_from(
+                        chrono::DateTime::parse_from_rfc2822("Tue, 1 Jul 2003 10:52:37 +0200")
+                            .unwrap(),
+                    )
+                    .unwrap(),
+                )),
+                b"SAVEDATE \"01-Jul-2003 10:52:37 +0200\"",
+            ),
+            (MessageDataItem::Preview(NString(None)), b"PREVIEW NIL"),
+            (
+                MessageDataItem::Preview(NString::try_from("Hello, World!").unwrap()),
+                b"PREVIEW \"Hello, World!\"",
+            ),
         ];
 
         for test in tests {
@@ -5,11 +5,14 @@ use nom::{
     bytes::streaming::tag,
     character::streaming::char,
     combinator::{map, recognize, value},
-    multi::{separated_list0, separated_list1},
+    error::context,
     sequence::{delimited, preceded, tuple},
 };
 
-use crate::{core::atom, decode::IMAPResult};
+use crate::{
+    core::atom,
+    decode::{separated_list0, separated_list1, IMAPResult},
+};
 
 /// ```abnf
 /// flag = "\Answered" /
@@ -52,7 +55,10 @@ pub(crate) fn flag(input: &[u8]) -> IMAPResult<&[u8], Flag> {
 
 /// `flag-list = "(" [flag *(SP flag)] ")"`
 pub(crate) fn flag_list(input: &[u8]) -> IMAPResult<&[u8], Vec<Flag>> {
-    delimited(tag(b"("), separated_list0(sp, flag), tag(b")"))(input)
+    context(
+        "flag-list",
+        delimited(tag(b"("), separated_list0(sp, flag), tag(b")")),
+    )(input)
 }
 
 /// `flag-fetch = flag / "\Recent"`
@@ -95,6 +95,9 @@
 //! imap-codec can't otherwise access their emails, we may add a `quirk_` feature to quickly resolve the problem.
 //! Of course, imap-codec should never violate the IMAP standard itself. So, we need to do this carefully.
 //!
+//! The `quirk_` features only define the defaults. Quirks can be enabled or disabled per codec at runtime,
+//! e.g., to be strict toward some peers and lenient toward others (see [`DecodeQuirks`](crate::decode::DecodeQuirks)).
+//!
 //! [imap-types]: https://docs.rs/imap-types/latest/imap_types
 //! [imap-types features]: https://docs.rs/imap-types/latest/imap_types/#features
 //! [IMAP4rev1]: https://tools.ietf.org/html/rfc3501
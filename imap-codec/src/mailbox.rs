@@ -10,14 +10,17 @@ use nom::{
     branch::alt,
     bytes::streaming::{tag, tag_no_case, take_while1},
     combinator::{map, opt, value},
-    multi::many0,
+    error::context,
     sequence::{delimited, preceded, tuple},
 };
 
 use crate::{
     core::{astring, nil, number, nz_number, quoted_char, string},
-    decode::IMAPResult,
-    extensions::quota::{quota_response, quotaroot_response},
+    decode::{many0, IMAPResult},
+    extensions::{
+        esearch::esearch_response,
+        quota::{quota_response, quotaroot_response},
+    },
     flag::{flag_list, mbx_list_flags},
     status::status_att_list,
 };
@@ -49,7 +52,7 @@ pub(crate) fn list_mailbox(input: &[u8]) -> IMAPResult<&[u8], ListMailbox> {
 ///
 /// Refer to section 5.1 for further semantic details of mailbox names.
 pub(crate) fn mailbox(input: &[u8]) -> IMAPResult<&[u8], Mailbox> {
-    map(astring, Mailbox::from)(input)
+    context("mailbox", map(astring, Mailbox::from))(input)
 }
 
 /// `mailbox-data = "FLAGS" SP flag-list /
@@ -59,6 +62,8 @@ pub(crate) fn mailbox(input: &[u8]) -> IMAPResult<&[u8], Mailbox> {
 ///                 "STATUS" SP mailbox SP "(" [status-att-list] ")" /
 ///                 number SP "EXISTS" /
 ///                 number SP "RECENT"`
+///
+/// `mailbox-data =/ esearch-response` (RFC 4731)
 pub(crate) fn mailbox_data(input: &[u8]) -> IMAPResult<&[u8], Data> {
     alt((
         map(
@@ -108,6 +113,7 @@ pub(crate) fn mailbox_data(input: &[u8]) -> IMAPResult<&[u8], Data> {
         ),
         quotaroot_response,
         quota_response,
+        esearch_response,
     ))(input)
 }
 
@@ -1,9 +1,5 @@
 use std::str::from_utf8;
 
-#[cfg(not(feature = "quirk_crlf_relaxed"))]
-use abnf_core::streaming::crlf;
-#[cfg(feature = "quirk_crlf_relaxed")]
-use abnf_core::streaming::crlf_relaxed as crlf;
 use abnf_core::streaming::sp;
 use base64::{engine::general_purpose::STANDARD as _base64, Engine};
 use imap_types::{
@@ -13,20 +9,27 @@ use imap_types::{
         Response, Status,
     },
 };
-#[cfg(feature = "quirk_missing_text")]
-use nom::combinator::peek;
 use nom::{
     branch::alt,
     bytes::streaming::{tag, tag_no_case, take_until, take_while},
-    combinator::{map, map_res, opt, value},
-    multi::separated_list1,
+    combinator::{map, map_res, opt, peek, value},
+    error::context,
     sequence::{delimited, preceded, terminated, tuple},
 };
 
+#[cfg(any(feature = "ext_login_referrals", feature = "ext_mailbox_referrals"))]
+use crate::extensions::referrals::referral;
 use crate::{
     core::{atom, charset, nz_number, tag_imap, text},
-    decode::IMAPResult,
-    extensions::enable::enable_data,
+    decode::{crlf, quirks, separated_list1, IMAPResult},
+    extensions::{
+        annotate::annotate_code,
+        context::noupdate,
+        convert::converted,
+        enable::enable_data,
+        inprogress::inprogress,
+        language::{comparator_data, language_data},
+    },
     fetch::msg_att,
     flag::flag_perm,
     mailbox::mailbox_data,
@@ -39,11 +42,14 @@ pub(crate) fn greeting(input: &[u8]) -> IMAPResult<&[u8], Greeting> {
     let mut parser = tuple((
         tag(b"*"),
         sp,
-        alt((
-            resp_cond_auth,
-            map(resp_cond_bye, |resp_text| (GreetingKind::Bye, resp_text)),
-        )),
-        crlf,
+        context(
+            "resp-cond-auth / resp-cond-bye",
+            alt((
+                resp_cond_auth,
+                map(resp_cond_bye, |resp_text| (GreetingKind::Bye, resp_text)),
+            )),
+        ),
+        context("CRLF", crlf),
     ));
 
     let (remaining, (_, _, (kind, (code, text)), _)) = parser(input)?;
@@ -79,40 +85,54 @@ pub(crate) fn resp_text(input: &[u8]) -> IMAPResult<&[u8], (Option<Code>, Text)>
     let (_, start) = opt(tag(b"["))(input)?;
 
     if start.is_some() {
-        tuple((
-            preceded(
-                tag(b"["),
-                map(
-                    alt((
-                        terminated(resp_text_code, tag(b"]")),
-                        map(
-                            terminated(
-                                take_while(|b: u8| b != b']' && b != b'\r' && b != b'\n'),
-                                tag(b"]"),
+        context(
+            "resp-text",
+            tuple((
+                preceded(
+                    tag(b"["),
+                    map(
+                        alt((
+                            terminated(resp_text_code, tag(b"]")),
+                            map(
+                                terminated(
+                                    take_while(|b: u8| b != b']' && b != b'\r' && b != b'\n'),
+                                    tag(b"]"),
+                                ),
+                                |bytes: &[u8]| Code::Other(CodeOther::unvalidated(bytes)),
                             ),
-                            |bytes: &[u8]| Code::Other(CodeOther::unvalidated(bytes)),
-                        ),
-                    )),
-                    Some,
+                        )),
+                        Some,
+                    ),
                 ),
-            ),
-            #[cfg(not(feature = "quirk_missing_text"))]
-            preceded(sp, text),
-            #[cfg(feature = "quirk_missing_text")]
-            alt((
-                preceded(sp, text),
-                map(peek(crlf), |_| {
-                    log::warn!("Rectified missing `text` to \"...\"");
-
-                    Text::unvalidated("...")
-                }),
+                code_text,
             )),
-        ))(input)
+        )(input)
     } else {
-        map(text, |text| (None, text))(input)
+        context("resp-text", map(text, |text| (None, text)))(input)
     }
 }
 
+/// `SP text` (after a `resp-text-code`)
+///
+/// # Quirks
+///
+/// * A missing `text` is rectified to `...` with
+///   [`DecodeQuirks::missing_text`](crate::decode::DecodeQuirks::missing_text).
+fn code_text(input: &[u8]) -> IMAPResult<&[u8], Text> {
+    if !quirks().missing_text {
+        return preceded(sp, text)(input);
+    }
+
+    alt((
+        preceded(sp, text),
+        map(peek(crlf), |_| {
+            log::warn!("Rectified missing `text` to \"...\"");
+
+            Text::unvalidated("...")
+        }),
+    ))(input)
+}
+
 /// `resp-text-code = "ALERT" /
 ///                   "BADCHARSET" [SP "(" charset *(SP charset) ")" ] /
 ///                   capability-data /
@@ -127,6 +147,16 @@ pub(crate) fn resp_text(input: &[u8]) -> IMAPResult<&[u8], (Option<Code>, Text)>
 ///                   "COMPRESSIONACTIVE" ; RFC 4978
 ///                   atom [SP 1*<any TEXT-CHAR except "]">]`
 ///
+/// `resp-text-code =/ "BADCOMPARATOR"` (RFC 5255)
+///
+/// `resp-text-code =/ "NOUPDATE" SP quoted` (RFC 5267)
+///
+/// `resp-text-code =/ "UNKNOWN-CVT" / "BADPARAMS"` (RFC 5259)
+///
+/// `resp-text-code =/ "ANNOTATE" SP ("TOOBIG" / "TOOMANY")` (RFC 5257)
+///
+/// `resp-text-code =/ "INPROGRESS" [SP "(" progress-tag SP progress-state ")"]` (RFC 9585)
+///
 /// Note: See errata id: 261
 pub(crate) fn resp_text_code(input: &[u8]) -> IMAPResult<&[u8], Code> {
     alt((
@@ -177,6 +207,14 @@ pub(crate) fn resp_text_code(input: &[u8]) -> IMAPResult<&[u8], Code> {
         value(Code::CompressionActive, tag_no_case(b"COMPRESSIONACTIVE")),
         value(Code::OverQuota, tag_no_case(b"OVERQUOTA")),
         value(Code::TooBig, tag_no_case(b"TOOBIG")),
+        value(Code::BadComparator, tag_no_case(b"BADCOMPARATOR")),
+        value(Code::UnknownCvt, tag_no_case(b"UNKNOWN-CVT")),
+        value(Code::BadParams, tag_no_case(b"BADPARAMS")),
+        annotate_code,
+        noupdate,
+        inprogress,
+        #[cfg(any(feature = "ext_login_referrals", feature = "ext_mailbox_referrals"))]
+        referral,
     ))(input)
 }
 
@@ -228,6 +266,21 @@ pub(crate) fn response(input: &[u8]) -> IMAPResult<&[u8], Response> {
     ))(input)
 }
 
+/// `base64` up to (but excluding) the line ending of a `continue-req`
+///
+/// # Quirks
+///
+/// * `\r` is optional with [`DecodeQuirks::crlf_relaxed`](crate::decode::DecodeQuirks::crlf_relaxed).
+fn continue_base64(input: &[u8]) -> IMAPResult<&[u8], Vec<u8>> {
+    if !quirks().crlf_relaxed {
+        return map_res(take_until("\r\n"), |input| _base64.decode(input))(input);
+    }
+
+    map_res(take_until("\n"), |input: &[u8]| {
+        _base64.decode(input.strip_suffix(b"\r").unwrap_or(input))
+    })(input)
+}
+
 /// `continue-req = "+" SP (resp-text / base64) CRLF`
 pub(crate) fn continue_req(input: &[u8]) -> IMAPResult<&[u8], CommandContinuationRequest> {
     // We can't map the output of `resp_text` directly to `Continue::basic()` because we might end
@@ -243,22 +296,7 @@ pub(crate) fn continue_req(input: &[u8]) -> IMAPResult<&[u8], CommandContinuatio
     let mut parser = tuple((
         tag(b"+ "),
         alt((
-            #[cfg(not(feature = "quirk_crlf_relaxed"))]
-            map(
-                map_res(take_until("\r\n"), |input| _base64.decode(input)),
-                Either::Base64,
-            ),
-            #[cfg(feature = "quirk_crlf_relaxed")]
-            map(
-                map_res(take_until("\n"), |input: &[u8]| {
-                    if !input.is_empty() && input[input.len().saturating_sub(1)] == b'\r' {
-                        _base64.decode(&input[..input.len().saturating_sub(1)])
-                    } else {
-                        _base64.decode(input)
-                    }
-                }),
-                Either::Base64,
-            ),
+            map(continue_base64, Either::Base64),
             map(resp_text, Either::Basic),
         )),
         crlf,
@@ -285,40 +323,45 @@ pub(crate) fn response_data(input: &[u8]) -> IMAPResult<&[u8], Response> {
     let mut parser = tuple((
         tag(b"*"),
         sp,
-        alt((
-            map(resp_cond_state, |(raw_status, code, text)| {
-                let status = match raw_status.to_ascii_lowercase().as_ref() {
-                    "ok" => Status::Ok {
-                        tag: None,
-                        code,
-                        text,
-                    },
-                    "no" => Status::No {
-                        tag: None,
-                        code,
-                        text,
-                    },
-                    "bad" => Status::Bad {
-                        tag: None,
-                        code,
-                        text,
-                    },
-                    _ => unreachable!(),
-                };
-
-                Response::Status(status)
-            }),
-            map(resp_cond_bye, |(code, text)| {
-                Response::Status(Status::Bye { code, text })
-            }),
-            map(mailbox_data, Response::Data),
-            map(message_data, Response::Data),
-            map(capability_data, |caps| {
-                Response::Data(Data::Capability(caps))
-            }),
-            map(enable_data, Response::Data),
-        )),
-        crlf,
+        context(
+            "response-data",
+            alt((
+                map(resp_cond_state, |(raw_status, code, text)| {
+                    let status = match raw_status.to_ascii_lowercase().as_ref() {
+                        "ok" => Status::Ok {
+                            tag: None,
+                            code,
+                            text,
+                        },
+                        "no" => Status::No {
+                            tag: None,
+                            code,
+                            text,
+                        },
+                        "bad" => Status::Bad {
+                            tag: None,
+                            code,
+                            text,
+                        },
+                        _ => unreachable!(),
+                    };
+
+                    Response::Status(status)
+                }),
+                map(resp_cond_bye, |(code, text)| {
+                    Response::Status(Status::Bye { code, text })
+                }),
+                map(mailbox_data, Response::Data),
+                map(message_data, Response::Data),
+                map(capability_data, |caps| {
+                    Response::Data(Data::Capability(caps))
+                }),
+                map(enable_data, Response::Data),
+                map(language_data, Response::Data),
+                map(comparator_data, Response::Data),
+            )),
+        ),
+        context("CRLF", crlf),
     ));
 
     let (remaining, (_, _, response, _)) = parser(input)?;
@@ -354,7 +397,12 @@ pub(crate) fn response_done(input: &[u8]) -> IMAPResult<&[u8], Status> {
 
 /// `response-tagged = tag SP resp-cond-state CRLF`
 pub(crate) fn response_tagged(input: &[u8]) -> IMAPResult<&[u8], Status> {
-    let mut parser = tuple((tag_imap, sp, resp_cond_state, crlf));
+    let mut parser = tuple((
+        context("tag", tag_imap),
+        context("SP after tag", sp),
+        context("resp-cond-state", resp_cond_state),
+        context("CRLF", crlf),
+    ));
 
     let (remaining, (tag, _, (raw_status, code, text), _)) = parser(input)?;
 
@@ -392,15 +440,23 @@ pub(crate) fn response_fatal(input: &[u8]) -> IMAPResult<&[u8], Status> {
 }
 
 /// `message-data = nz-number SP ("EXPUNGE" / ("FETCH" SP msg-att))`
+///
+/// `message-data =/ nz-number SP converted-response` (RFC 5259)
 pub(crate) fn message_data(input: &[u8]) -> IMAPResult<&[u8], Data> {
     let (remaining, seq) = terminated(nz_number, sp)(input)?;
 
     alt((
         map(tag_no_case(b"EXPUNGE"), move |_| Data::Expunge(seq)),
         map(
-            tuple((tag_no_case(b"FETCH"), sp, msg_att)),
+            tuple((tag_no_case(b"FETCH"), sp, context("msg-att", msg_att))),
             move |(_, _, items)| Data::Fetch { seq, items },
         ),
+        map(converted, move |(correlator, uid, items)| Data::Converted {
+            seq,
+            correlator,
+            uid,
+            items,
+        }),
     ))(remaining)
 }
 
@@ -414,11 +470,17 @@ mod tests {
             SinglePartExtensionData, SpecificFields,
         },
         core::{IString, NString, QuotedChar, Tag},
+        fetch::MessageDataItem,
         flag::FlagNameAttribute,
+        mailbox::Mailbox,
+        status::StatusDataItem,
     };
 
     use super::*;
-    use crate::testing::{kat_inverse_greeting, kat_inverse_response, known_answer_test_encode};
+    use crate::{
+        decode::{with_quirks, DecodeQuirks},
+        testing::{kat_inverse_greeting, kat_inverse_response, known_answer_test_encode},
+    };
 
     #[test]
     fn test_kat_inverse_greeting() {
@@ -492,6 +554,81 @@ mod tests {
                 b"",
                 Response::Data(Data::Expunge(123.try_into().unwrap())),
             ),
+            (
+                b"* CAPABILITY IMAP4REV1 PREVIEW\r\n",
+                b"",
+                Response::Data(Data::Capability(
+                    NonEmptyVec::try_from(vec![Capability::Imap4Rev1, Capability::Preview])
+                        .unwrap(),
+                )),
+            ),
+            (
+                b"* 1 FETCH (UID 3 PREVIEW \"Hello, World!\")\r\n",
+                b"",
+                Response::Data(Data::Fetch {
+                    seq: NonZeroU32::new(1).unwrap(),
+                    items: NonEmptyVec::try_from(vec![
+                        MessageDataItem::Uid(NonZeroU32::new(3).unwrap()),
+                        MessageDataItem::Preview(NString::try_from("Hello, World!").unwrap()),
+                    ])
+                    .unwrap(),
+                }),
+            ),
+            (
+                b"* CAPABILITY APPENDLIMIT=35651584 STATUS=SIZE SAVEDATE\r\n",
+                b"",
+                Response::Data(Data::Capability(
+                    NonEmptyVec::try_from(vec![
+                        Capability::AppendLimit(Some(35651584)),
+                        Capability::StatusSize,
+                        Capability::SaveDate,
+                    ])
+                    .unwrap(),
+                )),
+            ),
+            (
+                b"* CAPABILITY APPENDLIMIT\r\n",
+                b"",
+                Response::Data(Data::Capability(NonEmptyVec::from(
+                    Capability::AppendLimit(None),
+                ))),
+            ),
+            (
+                b"* STATUS INBOX (SIZE 1337 APPENDLIMIT NIL)\r\n",
+                b"",
+                Response::Data(Data::Status {
+                    mailbox: Mailbox::Inbox,
+                    items: vec![
+                        StatusDataItem::Size(1337),
+                        StatusDataItem::AppendLimit(None),
+                    ]
+                    .into(),
+                }),
+            ),
+            (
+                b"* STATUS INBOX (APPENDLIMIT 257890)\r\n",
+                b"",
+                Response::Data(Data::Status {
+                    mailbox: Mailbox::Inbox,
+                    items: vec![StatusDataItem::AppendLimit(Some(257890))].into(),
+                }),
+            ),
+            (
+                b"* 3 FETCH (SAVEDATE NIL)\r\n",
+                b"",
+                Response::Data(Data::Fetch {
+                    seq: NonZeroU32::new(3).unwrap(),
+                    items: NonEmptyVec::from(MessageDataItem::SaveDate(None)),
+                }),
+            ),
+            (
+                b"* 2 FETCH (PREVIEW NIL)\r\n",
+                b"",
+                Response::Data(Data::Fetch {
+                    seq: NonZeroU32::new(2).unwrap(),
+                    items: NonEmptyVec::from(MessageDataItem::Preview(NString(None))),
+                }),
+            ),
         ]);
     }
 
@@ -713,19 +850,20 @@ mod tests {
 
     #[test]
     fn test_parse_resp_text_quirk() {
-        #[cfg(not(feature = "quirk_missing_text"))]
-        {
-            assert!(resp_text(b"[IMAP4rev1]\r\n").is_err());
+        let mut quirks = DecodeQuirks::strict();
+
+        with_quirks(quirks, || {
             assert!(resp_text(b"[IMAP4rev1]\r\n").is_err());
             assert!(resp_text(b"[IMAP4rev1] \r\n").is_err());
             assert!(resp_text(b"[IMAP4rev1]  \r\n").is_ok());
-        }
+        });
 
-        #[cfg(feature = "quirk_missing_text")]
-        {
+        quirks.missing_text = true;
+
+        with_quirks(quirks, || {
             assert!(resp_text(b"[IMAP4rev1]\r\n").is_ok());
             assert!(resp_text(b"[IMAP4rev1] \r\n").is_err());
             assert!(resp_text(b"[IMAP4rev1]  \r\n").is_ok());
-        }
+        });
     }
 }
@@ -1,37 +1,72 @@
 use abnf_core::streaming::sp;
-use imap_types::{command::CommandBody, core::NonEmptyVec, search::SearchKey};
+use imap_types::{
+    command::CommandBody,
+    core::{Charset, NonEmptyVec},
+    search::SearchKey,
+};
 use nom::{
     branch::alt,
     bytes::streaming::{tag, tag_no_case},
     combinator::{map, map_opt, opt, value},
-    multi::{many1, separated_list1},
+    error::context,
     sequence::{delimited, preceded, tuple},
 };
 
 use crate::{
     core::{astring, atom, charset, number},
     datetime::date,
-    decode::{IMAPErrorKind, IMAPParseError, IMAPResult},
+    decode::{limits, many1, separated_list1, IMAPErrorKind, IMAPParseError, IMAPResult, Limit},
+    extensions::{esearch::search_return_opts, filters::filter_name},
     fetch::header_fld_name,
+    mailbox::list_mailbox,
     sequence::sequence_set,
 };
 
 /// `search = "SEARCH" [SP "CHARSET" SP charset] 1*(SP search-key)`
 ///
+/// `search = "SEARCH" [search-return-opts] SP search-program` (RFC 4731)
+///
 /// Note: CHARSET argument MUST be registered with IANA
 ///
 /// errata id: 261
 pub(crate) fn search(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
     let mut parser = tuple((
         tag_no_case(b"SEARCH"),
+        opt(search_return_opts),
+        search_program,
+    ));
+
+    let (remaining, (_, return_options, (charset, criteria))) = parser(input)?;
+
+    Ok((
+        remaining,
+        CommandBody::Search {
+            charset,
+            criteria,
+            return_options: return_options.unwrap_or_default(),
+            uid: false,
+        },
+    ))
+}
+
+/// `SP search-program`
+///
+/// `search-program = ["CHARSET" SP charset SP] search-key *(SP search-key)` (RFC 4466)
+///
+/// Note: Multiple search keys are combined into a single `SearchKey::And`.
+pub(crate) fn search_program(input: &[u8]) -> IMAPResult<&[u8], (Option<Charset>, SearchKey)> {
+    let mut parser = tuple((
         opt(map(
             tuple((sp, tag_no_case(b"CHARSET"), sp, charset)),
             |(_, _, _, charset)| charset,
         )),
-        many1(preceded(sp, search_key(9))),
+        many1(preceded(
+            context("SP", sp),
+            context("search-key", search_key(limits().max_search_key_nesting)),
+        )),
     ));
 
-    let (remaining, (_, charset, mut criteria)) = parser(input)?;
+    let (remaining, (charset, mut criteria)) = parser(input)?;
 
     let criteria = match criteria.len() {
         0 => unreachable!(),
@@ -39,14 +74,7 @@ pub(crate) fn search(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
         _ => SearchKey::And(NonEmptyVec::unvalidated(criteria)),
     };
 
-    Ok((
-        remaining,
-        CommandBody::Search {
-            charset,
-            criteria,
-            uid: false,
-        },
-    ))
+    Ok((remaining, (charset, criteria)))
 }
 
 /// `search-key = "ALL" /
@@ -88,6 +116,12 @@ pub(crate) fn search(input: &[u8]) -> IMAPResult<&[u8], CommandBody> {
 ///               sequence-set /
 ///               "(" search-key *(SP search-key) ")"`
 ///
+/// `search-key =/ "FILTER" SP filter-name` (RFC 5466)
+///
+/// `search-key =/ "ANNOTATION" SP entry-match SP attrib-match SP value` (RFC 5257)
+///
+/// Note: `value` is parsed as `astring`.
+///
 /// This parser is recursively defined. Thus, in order to not overflow the stack,
 /// it is needed to limit how may recursions are allowed. (8 should suffice).
 pub(crate) fn search_key(
@@ -103,7 +137,8 @@ fn search_key_limited<'a>(
     if remaining_recursion == 0 {
         return Err(nom::Err::Failure(IMAPParseError {
             input,
-            kind: IMAPErrorKind::RecursionLimitExceeded,
+            context: Vec::new(),
+            kind: IMAPErrorKind::LimitExceeded(Limit::SearchKeyNesting),
         }));
     }
 
@@ -212,6 +247,26 @@ fn search_key_limited<'a>(
                 |(_, _, val)| SearchKey::Uid(val),
             ),
             value(SearchKey::Undraft, tag_no_case(b"UNDRAFT")),
+            map(
+                tuple((tag_no_case(b"FILTER"), sp, filter_name)),
+                |(_, _, val)| SearchKey::Filter(val),
+            ),
+            map(
+                tuple((
+                    tag_no_case(b"ANNOTATION"),
+                    sp,
+                    list_mailbox,
+                    sp,
+                    list_mailbox,
+                    sp,
+                    astring,
+                )),
+                |(_, _, entry, _, attribute, _, value)| SearchKey::Annotation {
+                    entry,
+                    attribute,
+                    value,
+                },
+            ),
             map(sequence_set, SearchKey::SequenceSet),
             map(
                 delimited(tag(b"("), separated_list1(sp, search_key), tag(b")")),
@@ -249,6 +304,7 @@ mod tests {
                         .try_into()
                         .unwrap()
                 )))),
+                return_options: vec![],
                 uid: false,
             }
         );
@@ -291,6 +347,7 @@ mod tests {
             ]
             .try_into()
             .unwrap()),
+            return_options: vec![],
             uid: false,
         };
         assert_eq!(val, expected);
@@ -6,11 +6,14 @@ use nom::{
     branch::alt,
     bytes::streaming::tag,
     combinator::{map, value},
-    multi::separated_list1,
+    error::context,
     sequence::tuple,
 };
 
-use crate::{core::nz_number, decode::IMAPResult};
+use crate::{
+    core::nz_number,
+    decode::{separated_list1_limited, IMAPResult, Limit},
+};
 
 /// `sequence-set = (seq-number / seq-range) ["," sequence-set]`
 ///
@@ -32,19 +35,29 @@ use crate::{core::nz_number, decode::IMAPResult};
 ///
 /// `sequence-set = (seq-number / seq-range) *("," (seq-number / seq-range))`
 pub(crate) fn sequence_set(input: &[u8]) -> IMAPResult<&[u8], SequenceSet> {
-    map(
-        separated_list1(
-            tag(b","),
-            alt((
-                // Ordering is important!
-                map(seq_range, |(from, to)| Sequence::Range(from, to)),
-                map(seq_number, Sequence::Single),
-            )),
+    context(
+        "sequence-set",
+        map(
+            separated_list1_limited(
+                tag(b","),
+                sequence,
+                |limits| limits.max_sequence_set_length,
+                Limit::SequenceSetLength,
+            ),
+            |set| SequenceSet(NonEmptyVec::unvalidated(set)),
         ),
-        |set| SequenceSet(NonEmptyVec::unvalidated(set)),
     )(input)
 }
 
+/// `seq-number / seq-range`
+pub(crate) fn sequence(input: &[u8]) -> IMAPResult<&[u8], Sequence> {
+    alt((
+        // Ordering is important!
+        map(seq_range, |(from, to)| Sequence::Range(from, to)),
+        map(seq_number, Sequence::Single),
+    ))(input)
+}
+
 /// `seq-range = seq-number ":" seq-number`
 ///
 /// Two seq-number values and all values between these two regardless of order.
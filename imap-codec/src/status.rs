@@ -4,13 +4,12 @@ use nom::{
     branch::alt,
     bytes::streaming::tag_no_case,
     combinator::{map, value},
-    multi::separated_list1,
     sequence::tuple,
 };
 
 use crate::{
-    core::{number, number64, nz_number},
-    decode::IMAPResult,
+    core::{nil, number, number64, nz_number},
+    decode::{separated_list1, IMAPResult},
 };
 
 /// `status-att = "MESSAGES" /
@@ -18,6 +17,12 @@ use crate::{
 ///               "UIDNEXT" /
 ///               "UIDVALIDITY" /
 ///               "UNSEEN"`
+///
+/// `status-att =/ "DELETED" / "DELETED-STORAGE"` (RFC 9051, RFC 9208)
+///
+/// `status-att =/ "SIZE"` (RFC 8438)
+///
+/// `status-att =/ "APPENDLIMIT"` (RFC 7889)
 pub(crate) fn status_att(input: &[u8]) -> IMAPResult<&[u8], StatusDataItemName> {
     alt((
         value(StatusDataItemName::Messages, tag_no_case(b"MESSAGES")),
@@ -30,6 +35,8 @@ pub(crate) fn status_att(input: &[u8]) -> IMAPResult<&[u8], StatusDataItemName>
             tag_no_case(b"DELETED-STORAGE"),
         ),
         value(StatusDataItemName::Deleted, tag_no_case(b"DELETED")),
+        value(StatusDataItemName::Size, tag_no_case(b"SIZE")),
+        value(StatusDataItemName::AppendLimit, tag_no_case(b"APPENDLIMIT")),
         #[cfg(feature = "ext_condstore_qresync")]
         value(
             StatusDataItemName::HighestModSeq,
@@ -51,6 +58,12 @@ pub(crate) fn status_att_list(input: &[u8]) -> IMAPResult<&[u8], Vec<StatusDataI
 ///                    ("UIDVALIDITY" SP nz-number) /
 ///                    ("UNSEEN" SP number)`
 ///
+/// `status-att-val =/ ("DELETED" SP number) / ("DELETED-STORAGE" SP number64)` (RFC 9051, RFC 9208)
+///
+/// `status-att-val =/ ("SIZE" SP number64)` (RFC 8438)
+///
+/// `status-att-val =/ ("APPENDLIMIT" SP (number64 / nil))` (RFC 7889)
+///
 /// Note: See errata id: 261
 fn status_att_val(input: &[u8]) -> IMAPResult<&[u8], StatusDataItem> {
     alt((
@@ -82,6 +95,18 @@ fn status_att_val(input: &[u8]) -> IMAPResult<&[u8], StatusDataItem> {
             tuple((tag_no_case(b"DELETED"), sp, number)),
             |(_, _, num)| StatusDataItem::Deleted(num),
         ),
+        map(
+            tuple((tag_no_case(b"SIZE"), sp, number64)),
+            |(_, _, num)| StatusDataItem::Size(num),
+        ),
+        map(
+            tuple((
+                tag_no_case(b"APPENDLIMIT"),
+                sp,
+                alt((map(number64, Some), value(None, nil))),
+            )),
+            |(_, _, limit)| StatusDataItem::AppendLimit(limit),
+        ),
     ))(input)
 }
 
@@ -102,6 +127,8 @@ mod tests {
             (StatusDataItemName::Unseen, b"UNSEEN"),
             (StatusDataItemName::Deleted, b"DELETED"),
             (StatusDataItemName::DeletedStorage, b"DELETED-STORAGE"),
+            (StatusDataItemName::Size, b"SIZE"),
+            (StatusDataItemName::AppendLimit, b"APPENDLIMIT"),
         ];
 
         for test in tests {
@@ -128,6 +155,12 @@ mod tests {
                 StatusDataItem::DeletedStorage(u64::MAX),
                 b"DELETED-STORAGE 18446744073709551615",
             ),
+            (StatusDataItem::Size(u64::MAX), b"SIZE 18446744073709551615"),
+            (StatusDataItem::AppendLimit(None), b"APPENDLIMIT NIL"),
+            (
+                StatusDataItem::AppendLimit(Some(35651584)),
+                b"APPENDLIMIT 35651584",
+            ),
         ];
 
         for test in tests {
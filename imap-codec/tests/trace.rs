@@ -1,5 +1,5 @@
 use imap_codec::{
-    decode::Decoder,
+    decode::{DecodeQuirks, Decoder},
     encode::Encoder,
     imap_types::{
         auth::AuthMechanism,
@@ -80,6 +80,11 @@ fn test_lines_of_trace(trace: &[u8]) {
                 let (rem, parsed2) = CommandCodec::default().decode(&serialized).unwrap();
                 assert!(rem.is_empty());
                 assert_eq!(parsed, parsed2);
+                let (_, lossless) = CommandCodec::default().decode_lossless(&line).unwrap();
+                assert_eq!(
+                    CommandCodec::default().encode_lossless(&lossless).dump(),
+                    line
+                );
                 println!()
             }
             Who::Server => {
@@ -95,6 +100,11 @@ fn test_lines_of_trace(trace: &[u8]) {
                 let (rem, parsed2) = ResponseCodec::default().decode(&serialized).unwrap();
                 assert!(rem.is_empty());
                 assert_eq!(parsed, parsed2);
+                let (_, lossless) = ResponseCodec::default().decode_lossless(&line).unwrap();
+                assert_eq!(
+                    ResponseCodec::default().encode_lossless(&lossless).dump(),
+                    line
+                );
                 println!()
             }
         }
@@ -115,6 +125,11 @@ fn test_trace_known_positive(tests: Vec<(&[u8], Message)>) {
                 let (rem2, got2) = CommandCodec::default().decode(&encoded).unwrap();
                 assert!(rem2.is_empty());
                 assert_eq!(expected, got2);
+                let (_, lossless) = CommandCodec::default().decode_lossless(test).unwrap();
+                assert_eq!(
+                    CommandCodec::default().encode_lossless(&lossless).dump(),
+                    test
+                );
             }
             Message::Response(expected) => {
                 let (rem, got) = ResponseCodec::default().decode(test).unwrap();
@@ -126,6 +141,11 @@ fn test_trace_known_positive(tests: Vec<(&[u8], Message)>) {
                 let (rem2, got2) = ResponseCodec::default().decode(&encoded).unwrap();
                 assert!(rem2.is_empty());
                 assert_eq!(expected, got2);
+                let (_, lossless) = ResponseCodec::default().decode_lossless(test).unwrap();
+                assert_eq!(
+                    ResponseCodec::default().encode_lossless(&lossless).dump(),
+                    test
+                );
             }
         };
 
@@ -133,6 +153,29 @@ fn test_trace_known_positive(tests: Vec<(&[u8], Message)>) {
     }
 }
 
+/// Decode a (quirky) response with `quirks` and compare it to the strictly decoded `normalized`
+/// response, which is also what the encoder produces.
+fn test_quirk_known_positive(quirks: DecodeQuirks, tests: Vec<(&[u8], &[u8])>) {
+    let strict = ResponseCodec::new().with_quirks(DecodeQuirks::strict());
+    let lenient = ResponseCodec::new().with_quirks(quirks);
+
+    for (test, normalized) in tests.into_iter() {
+        println!("// {}", String::from_utf8_lossy(test).trim());
+        assert!(strict.decode(test).is_err());
+        let (rem, got) = lenient.decode(test).unwrap();
+        assert!(rem.is_empty());
+        println!("{:?}", got);
+        let (rem, expected) = strict.decode(normalized).unwrap();
+        assert!(rem.is_empty());
+        assert_eq!(expected, got);
+        let encoded = ResponseCodec::default().encode(&got).dump();
+        println!("// {}", String::from_utf8(encoded.clone()).unwrap().trim());
+        assert_eq!(encoded, normalized);
+
+        println!();
+    }
+}
+
 #[test]
 fn test_from_capability() {
     let tests = {
@@ -1403,3 +1446,111 @@ fn test_trace_rfc2088() {
         .unwrap()
     })
 }
+
+#[test]
+fn test_quirk_unquoted_envelope_strings() {
+    let mut quirks = DecodeQuirks::strict();
+    quirks.unquoted_envelope_strings = true;
+
+    // Exchange
+    test_quirk_known_positive(
+        quirks,
+        vec![(
+            b"* 12 FETCH (UID 4827 ENVELOPE (\"Tue, 17 Oct 2023 09:12:44 +0000\" Quarterly report draft ((\"Alice\" NIL \"alice\" \"example.com\")) ((\"Alice\" NIL \"alice\" \"example.com\")) ((\"Alice\" NIL \"alice\" \"example.com\")) ((NIL NIL \"bob\" \"example.com\")) NIL NIL NIL <DB7PR03MB4972@EURPRD03.example.com>))\r\n".as_ref(),
+            b"* 12 FETCH (UID 4827 ENVELOPE (\"Tue, 17 Oct 2023 09:12:44 +0000\" \"Quarterly report draft\" ((\"Alice\" NIL \"alice\" \"example.com\")) ((\"Alice\" NIL \"alice\" \"example.com\")) ((\"Alice\" NIL \"alice\" \"example.com\")) ((NIL NIL \"bob\" \"example.com\")) NIL NIL NIL \"<DB7PR03MB4972@EURPRD03.example.com>\"))\r\n".as_ref(),
+        ),
+        (
+            b"* 13 FETCH (ENVELOPE (Wed, 18 Oct 2023 11:02:09 -0700 \"Lunch?\" NIL NIL NIL NIL NIL NIL NIL NIL))\r\n".as_ref(),
+            b"* 13 FETCH (ENVELOPE (\"Wed, 18 Oct 2023 11:02:09 -0700\" \"Lunch?\" NIL NIL NIL NIL NIL NIL NIL NIL))\r\n".as_ref(),
+        )],
+    );
+}
+
+#[test]
+fn test_quirk_nil_body_structure_lists() {
+    let mut quirks = DecodeQuirks::strict();
+    quirks.nil_body_structure_lists = true;
+
+    // Courier
+    test_quirk_known_positive(
+        quirks,
+        vec![
+            (
+                b"* 3 FETCH (BODYSTRUCTURE (\"MESSAGE\" \"RFC822\" NIL NIL NIL \"7BIT\" 1024 NIL (\"TEXT\" \"PLAIN\" (\"CHARSET\" \"US-ASCII\") NIL NIL \"7BIT\" 512 10) 20))\r\n".as_ref(),
+                b"* 3 FETCH (BODYSTRUCTURE (\"MESSAGE\" \"RFC822\" NIL NIL NIL \"7BIT\" 1024 (NIL NIL NIL NIL NIL NIL NIL NIL NIL NIL) (\"TEXT\" \"PLAIN\" (\"CHARSET\" \"US-ASCII\") NIL NIL \"7BIT\" 512 10) 20))\r\n".as_ref(),
+            ),
+            (
+                b"* 4 FETCH (BODYSTRUCTURE (\"MESSAGE\" \"RFC822\" NIL NIL NIL \"7BIT\" 1024 (NIL NIL NIL NIL NIL NIL NIL NIL NIL NIL) NIL 20))\r\n".as_ref(),
+                b"* 4 FETCH (BODYSTRUCTURE (\"MESSAGE\" \"RFC822\" NIL NIL NIL \"7BIT\" 1024 (NIL NIL NIL NIL NIL NIL NIL NIL NIL NIL) (\"TEXT\" \"PLAIN\" NIL NIL NIL \"7BIT\" 0 0) 20))\r\n".as_ref(),
+            ),
+            (
+                b"* 5 FETCH (BODYSTRUCTURE (NIL \"MIXED\"))\r\n".as_ref(),
+                b"* 5 FETCH (BODYSTRUCTURE ((\"TEXT\" \"PLAIN\" NIL NIL NIL \"7BIT\" 0 0) \"MIXED\"))\r\n".as_ref(),
+            ),
+        ],
+    );
+}
+
+#[test]
+fn test_quirk_relaxed_date_time() {
+    let mut quirks = DecodeQuirks::strict();
+    quirks.relaxed_date_time = true;
+
+    // iCloud
+    test_quirk_known_positive(
+        quirks,
+        vec![
+            (
+                b"* 5 FETCH (INTERNALDATE \"tue, 17-Oct-2023 09:12:44 +0000\")\r\n".as_ref(),
+                b"* 5 FETCH (INTERNALDATE \"17-Oct-2023 09:12:44 +0000\")\r\n".as_ref(),
+            ),
+            (
+                b"* 6 FETCH (INTERNALDATE \"Wed, 4 Oct 2023 18:30:00 +0200\")\r\n".as_ref(),
+                b"* 6 FETCH (INTERNALDATE \"04-Oct-2023 18:30:00 +0200\")\r\n".as_ref(),
+            ),
+        ],
+    );
+}
+
+#[test]
+fn test_quirk_trailing_spaces() {
+    let mut quirks = DecodeQuirks::strict();
+    quirks.trailing_spaces = true;
+
+    // Yahoo
+    test_quirk_known_positive(
+        quirks,
+        vec![
+            (
+                b"* SEARCH 2 84 882 \r\n".as_ref(),
+                b"* SEARCH 2 84 882\r\n".as_ref(),
+            ),
+            (
+                b"* FLAGS (\\Answered \\Flagged \\Draft \\Deleted \\Seen $Forwarded)  \r\n"
+                    .as_ref(),
+                b"* FLAGS (\\Answered \\Flagged \\Draft \\Deleted \\Seen $Forwarded)\r\n".as_ref(),
+            ),
+        ],
+    );
+}
+
+#[test]
+fn test_quirk_eight_bit_text() {
+    let mut quirks = DecodeQuirks::strict();
+    quirks.eight_bit_text = true;
+
+    // Courier
+    test_quirk_known_positive(
+        quirks,
+        vec![
+            (
+                "* OK [ALERT] Bienvenue à bord\r\n".as_bytes(),
+                b"* OK [ALERT] Bienvenue ? bord\r\n".as_ref(),
+            ),
+            (
+                b"A3 NO Ung\xfcltiger Benutzername\r\n".as_ref(),
+                b"A3 NO Ung?ltiger Benutzername\r\n".as_ref(),
+            ),
+        ],
+    );
+}
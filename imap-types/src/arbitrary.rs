@@ -1,6 +1,8 @@
 use arbitrary::{Arbitrary, Unstructured};
 use chrono::{FixedOffset, TimeZone};
 
+#[cfg(any(feature = "ext_login_referrals", feature = "ext_mailbox_referrals"))]
+use crate::extensions::referrals::ImapUrl;
 use crate::{
     auth::AuthMechanism,
     body::{
@@ -13,9 +15,9 @@ use crate::{
     },
     datetime::{DateTime, NaiveDate},
     envelope::Envelope,
-    extensions::{enable::CapabilityEnable, quota::Resource},
+    extensions::{enable::CapabilityEnable, filters::FilterName, quota::Resource},
     flag::{Flag, FlagNameAttribute},
-    mailbox::{ListCharString, Mailbox, MailboxOther},
+    mailbox::{ListCharString, ListMailbox, Mailbox, MailboxOther},
     response::{
         Capability, Code, CodeOther, CommandContinuationRequestBasic, Greeting, GreetingKind,
         Status,
@@ -68,6 +70,9 @@ implement_tryfrom! { MailboxOther<'a>, AString<'a> }
 implement_tryfrom! { CapabilityEnable<'a>, &str }
 implement_tryfrom! { Resource<'a>, &str }
 implement_tryfrom! { AuthMechanism<'a>, &str }
+implement_tryfrom! { FilterName<'a>, &str }
+#[cfg(any(feature = "ext_login_referrals", feature = "ext_mailbox_referrals"))]
+implement_tryfrom! { ImapUrl<'a>, &str }
 implement_tryfrom_t! { NonEmptyVec<T>, Vec<T> }
 
 impl<'a> Arbitrary<'a> for CommandContinuationRequestBasic<'a> {
@@ -156,7 +161,7 @@ impl<'a> Arbitrary<'a> for CodeOther<'a> {
 impl<'a> Arbitrary<'a> for SearchKey<'a> {
     fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
         fn make_search_key<'a>(u: &mut Unstructured<'a>) -> arbitrary::Result<SearchKey<'a>> {
-            Ok(match u.int_in_range(0u8..=33)? {
+            Ok(match u.int_in_range(0u8..=35)? {
                 0 => SearchKey::SequenceSet(SequenceSet::arbitrary(u)?),
                 1 => SearchKey::All,
                 2 => SearchKey::Answered,
@@ -191,6 +196,12 @@ impl<'a> Arbitrary<'a> for SearchKey<'a> {
                 31 => SearchKey::Unflagged,
                 32 => SearchKey::Unkeyword(Atom::arbitrary(u)?),
                 33 => SearchKey::Unseen,
+                34 => SearchKey::Filter(FilterName::arbitrary(u)?),
+                35 => SearchKey::Annotation {
+                    entry: ListMailbox::arbitrary(u)?,
+                    attribute: ListMailbox::arbitrary(u)?,
+                    value: AString::arbitrary(u)?,
+                },
                 _ => unreachable!(),
             })
         }
@@ -203,7 +214,7 @@ impl<'a> Arbitrary<'a> for SearchKey<'a> {
                 return make_search_key(u);
             }
 
-            Ok(match u.int_in_range(0u8..=36)? {
+            Ok(match u.int_in_range(0u8..=38)? {
                 0 => SearchKey::And({
                     let keys = {
                         let len = u.arbitrary_len::<SearchKey>()?;
@@ -261,6 +272,12 @@ impl<'a> Arbitrary<'a> for SearchKey<'a> {
                 34 => SearchKey::Unflagged,
                 35 => SearchKey::Unkeyword(Atom::arbitrary(u)?),
                 36 => SearchKey::Unseen,
+                37 => SearchKey::Filter(FilterName::arbitrary(u)?),
+                38 => SearchKey::Annotation {
+                    entry: ListMailbox::arbitrary(u)?,
+                    attribute: ListMailbox::arbitrary(u)?,
+                    value: AString::arbitrary(u)?,
+                },
                 _ => unreachable!(),
             })
         }
@@ -16,13 +16,17 @@ use crate::{
     command::error::{AppendError, CopyError, ListError, LoginError, RenameError},
     core::{AString, Charset, Literal, NonEmptyVec, Tag},
     datetime::DateTime,
-    extensions::{compress::CompressionAlgorithm, enable::CapabilityEnable, quota::QuotaSet},
-    fetch::MacroOrMessageDataItemNames,
-    flag::{Flag, StoreResponse, StoreType},
+    extensions::{
+        annotate::AnnotationEntry, compress::CompressionAlgorithm, convert::ConvertItemName,
+        enable::CapabilityEnable, esearch::SearchReturnOption, multisearch::FilterMailboxes,
+        quota::QuotaSet,
+    },
+    fetch::{FetchModifier, MacroOrMessageDataItemNames},
+    flag::{Flag, StoreAttribute, StoreResponse, StoreType},
     mailbox::{ListMailbox, Mailbox},
     search::SearchKey,
     secret::Secret,
-    sequence::SequenceSet,
+    sequence::{SeqOrUid, SequenceSet},
     status::StatusDataItemName,
 };
 
@@ -391,6 +395,11 @@ pub enum CommandBody<'a> {
     /// This should bring the client back to the AUTHENTICATED state.
     Unselect,
 
+    /// Reset the connection to the NOT AUTHENTICATED state (RFC 8437).
+    ///
+    /// This allows a client to re-use the connection for another user.
+    Unauthenticate,
+
     /// 6.3.2.  EXAMINE Command
     ///
     /// Arguments:  mailbox name
@@ -1012,6 +1021,10 @@ pub enum CommandBody<'a> {
         charset: Option<Charset<'a>>,
         /// Criteria.
         criteria: SearchKey<'a>,
+        /// Return options (RFC 4731).
+        ///
+        /// Note: Use an empty list for a "plain" SEARCH command.
+        return_options: Vec<SearchReturnOption>,
         /// Use UID variant.
         uid: bool,
     },
@@ -1045,6 +1058,8 @@ pub enum CommandBody<'a> {
         sequence_set: SequenceSet,
         /// Message data items (or a macro).
         macro_or_item_names: MacroOrMessageDataItemNames<'a>,
+        /// Fetch modifiers (RFC 4466).
+        modifiers: Vec<FetchModifier>,
         /// Use UID variant.
         uid: bool,
     },
@@ -1106,8 +1121,21 @@ pub enum CommandBody<'a> {
         kind: StoreType,
         /// Kind of response, i.e., answer or silent.
         response: StoreResponse,
-        /// Flags.
-        flags: Vec<Flag<'a>>, // FIXME(misuse): must not accept "\*" or "\Recent"
+        /// Attribute to modify, e.g., flags.
+        attribute: StoreAttribute<'a>,
+        /// Use UID variant.
+        uid: bool,
+    },
+
+    /// STORE command that sets annotations (see RFC 5257).
+    ///
+    /// Note: Annotations are always replaced and the server always answers with `FETCH`, i.e.,
+    /// there is neither a `+`/`-` prefix nor a `.SILENT` suffix.
+    StoreAnnotation {
+        /// Set of messages.
+        sequence_set: SequenceSet,
+        /// Annotations to set.
+        entries: NonEmptyVec<AnnotationEntry<'a>>,
         /// Use UID variant.
         uid: bool,
     },
@@ -1362,6 +1390,111 @@ pub enum CommandBody<'a> {
         /// Use UID variant.
         uid: bool,
     },
+
+    /// REPLACE command (see RFC 8508).
+    ///
+    /// Atomically appends a new message to the destination mailbox and removes the message that
+    /// it replaces from the currently selected mailbox. The arguments of the new message are the
+    /// same as for [`CommandBody::Append`].
+    Replace {
+        /// Message to replace.
+        sequence_number_or_uid: SeqOrUid,
+        /// Destination mailbox.
+        mailbox: Mailbox<'a>,
+        /// Flags.
+        flags: Vec<Flag<'a>>,
+        /// Datetime.
+        date: Option<DateTime>,
+        /// Replacement message.
+        message: Literal<'a>,
+        /// Use UID variant.
+        uid: bool,
+    },
+
+    /// LANGUAGE command (see RFC 5255).
+    ///
+    /// Without arguments, the server lists the supported languages in an untagged LANGUAGE
+    /// response. Otherwise, the server selects the first supported language from the given
+    /// language ranges and uses it for human-readable text.
+    Language {
+        /// Language ranges, e.g., `de-CH` or `*`.
+        ranges: Vec<AString<'a>>,
+    },
+
+    /// ESEARCH command (see RFC 7377).
+    ///
+    /// Search multiple mailboxes at once. The results are returned in one ESEARCH response per
+    /// mailbox, correlated with the `MAILBOX` and `UIDVALIDITY` of the mailbox.
+    ///
+    /// Note: The results are always UIDs.
+    ESearch {
+        /// Mailboxes to search (`IN (...)`).
+        ///
+        /// Note: Use an empty list to search the selected mailbox.
+        source_options: Vec<FilterMailboxes<'a>>,
+        /// Return options.
+        return_options: Vec<SearchReturnOption>,
+        /// Charset.
+        charset: Option<Charset<'a>>,
+        /// Criteria.
+        criteria: SearchKey<'a>,
+    },
+
+    /// CONVERT command (see RFC 5259).
+    ///
+    /// Convert body parts of the given messages, e.g., to a different media type or size.
+    /// The results are returned in CONVERTED responses.
+    Convert {
+        /// Set of messages.
+        sequence_set: SequenceSet,
+        /// Items to convert.
+        items: NonEmptyVec<ConvertItemName<'a>>,
+        /// Use UID variant.
+        uid: bool,
+    },
+
+    /// CANCELUPDATE command (see RFC 5267).
+    ///
+    /// Stop updates (ADDTO/REMOVEFROM) of the searches started with the given tags.
+    CancelUpdate {
+        /// Tags of the searches with `RETURN (UPDATE)`.
+        tags: NonEmptyVec<Tag<'a>>,
+    },
+
+    /// COMPARATOR command (see RFC 5255).
+    ///
+    /// Without arguments, the server reports the active comparator in an untagged COMPARATOR
+    /// response. Otherwise, the server selects the first supported comparator from the given
+    /// collation orders and uses it for SEARCH (and SORT).
+    Comparator {
+        /// Collation orders, e.g., `i;unicode-casemap`.
+        comparators: Vec<AString<'a>>,
+    },
+
+    /// RLIST command (see RFC 2193).
+    ///
+    /// Same as [`CommandBody::List`] but the server also returns remote mailboxes, i.e.,
+    /// mailboxes that live on other servers and are marked with `\Remote`.
+    #[cfg(feature = "ext_mailbox_referrals")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ext_mailbox_referrals")))]
+    RList {
+        /// Reference.
+        reference: Mailbox<'a>,
+        /// Mailbox (wildcard).
+        mailbox_wildcard: ListMailbox<'a>,
+    },
+
+    /// RLSUB command (see RFC 2193).
+    ///
+    /// Same as [`CommandBody::Lsub`] but the server also returns remote mailboxes.
+    #[cfg(feature = "ext_mailbox_referrals")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ext_mailbox_referrals")))]
+    RLsub {
+        /// Reference.
+        reference: Mailbox<'a>,
+        /// Mailbox (wildcard).
+        mailbox_wildcard: ListMailbox<'a>,
+    },
 }
 
 impl<'a> CommandBody<'a> {
@@ -1551,6 +1684,7 @@ impl<'a> CommandBody<'a> {
         CommandBody::Search {
             charset,
             criteria,
+            return_options: vec![],
             uid,
         }
     }
@@ -1566,6 +1700,28 @@ impl<'a> CommandBody<'a> {
         Ok(CommandBody::Fetch {
             sequence_set,
             macro_or_item_names: macro_or_item_names.into(),
+            modifiers: vec![],
+            uid,
+        })
+    }
+
+    /// Construct a FETCH command with fetch modifiers.
+    pub fn fetch_with_modifiers<S, I>(
+        sequence_set: S,
+        macro_or_item_names: I,
+        modifiers: Vec<FetchModifier>,
+        uid: bool,
+    ) -> Result<Self, S::Error>
+    where
+        S: TryInto<SequenceSet>,
+        I: Into<MacroOrMessageDataItemNames<'a>>,
+    {
+        let sequence_set = sequence_set.try_into()?;
+
+        Ok(CommandBody::Fetch {
+            sequence_set,
+            macro_or_item_names: macro_or_item_names.into(),
+            modifiers,
             uid,
         })
     }
@@ -1587,7 +1743,25 @@ impl<'a> CommandBody<'a> {
             sequence_set,
             kind,
             response,
-            flags,
+            attribute: StoreAttribute::Flags(flags),
+            uid,
+        })
+    }
+
+    /// Construct a STORE command that sets annotations (RFC 5257).
+    pub fn store_annotation<S>(
+        sequence_set: S,
+        entries: NonEmptyVec<AnnotationEntry<'a>>,
+        uid: bool,
+    ) -> Result<Self, S::Error>
+    where
+        S: TryInto<SequenceSet>,
+    {
+        let sequence_set = sequence_set.try_into()?;
+
+        Ok(CommandBody::StoreAnnotation {
+            sequence_set,
+            entries,
             uid,
         })
     }
@@ -1621,6 +1795,7 @@ impl<'a> CommandBody<'a> {
             Self::Login { .. } => "LOGIN",
             Self::Select { .. } => "SELECT",
             Self::Unselect => "UNSELECT",
+            Self::Unauthenticate => "UNAUTHENTICATE",
             Self::Examine { .. } => "EXAMINE",
             Self::Create { .. } => "CREATE",
             Self::Delete { .. } => "DELETE",
@@ -1636,7 +1811,7 @@ impl<'a> CommandBody<'a> {
             Self::Expunge => "EXPUNGE",
             Self::Search { .. } => "SEARCH",
             Self::Fetch { .. } => "FETCH",
-            Self::Store { .. } => "STORE",
+            Self::Store { .. } | Self::StoreAnnotation { .. } => "STORE",
             Self::Copy { .. } => "COPY",
             Self::Idle => "IDLE",
             Self::Enable { .. } => "ENABLE",
@@ -1645,6 +1820,16 @@ impl<'a> CommandBody<'a> {
             Self::GetQuotaRoot { .. } => "GETQUOTAROOT",
             Self::SetQuota { .. } => "SETQUOTA",
             Self::Move { .. } => "MOVE",
+            Self::Replace { .. } => "REPLACE",
+            Self::Language { .. } => "LANGUAGE",
+            Self::Comparator { .. } => "COMPARATOR",
+            Self::CancelUpdate { .. } => "CANCELUPDATE",
+            Self::ESearch { .. } => "ESEARCH",
+            Self::Convert { .. } => "CONVERT",
+            #[cfg(feature = "ext_mailbox_referrals")]
+            Self::RList { .. } => "RLIST",
+            #[cfg(feature = "ext_mailbox_referrals")]
+            Self::RLsub { .. } => "RLSUB",
         }
     }
 }
@@ -1701,10 +1886,12 @@ mod tests {
     use super::*;
     use crate::{
         auth::AuthMechanism,
-        core::{AString, Charset, IString, Literal, NonEmptyVec},
+        core::{AString, Charset, IString, Literal, NString, NonEmptyVec},
         datetime::DateTime,
         extensions::{
+            annotate::AnnotationValue,
             compress::CompressionAlgorithm,
+            convert::ConvertParams,
             enable::{CapabilityEnable, Utf8Kind},
         },
         fetch::{Macro, MacroOrMessageDataItemNames, MessageDataItemName, Part, Section},
@@ -1910,6 +2097,7 @@ mod tests {
                 "SELECT",
             ),
             (CommandBody::Unselect, "UNSELECT"),
+            (CommandBody::Unauthenticate, "UNAUTHENTICATE"),
             (
                 CommandBody::Examine {
                     mailbox: Mailbox::Inbox,
@@ -1984,6 +2172,7 @@ mod tests {
                 CommandBody::Search {
                     charset: None,
                     criteria: SearchKey::Recent,
+                    return_options: vec![],
                     uid: true,
                 },
                 "SEARCH",
@@ -1992,6 +2181,7 @@ mod tests {
                 CommandBody::Fetch {
                     sequence_set: SequenceSet::try_from(1u32).unwrap(),
                     macro_or_item_names: MacroOrMessageDataItemNames::Macro(Macro::Full),
+                    modifiers: vec![],
                     uid: true,
                 },
                 "FETCH",
@@ -1999,13 +2189,27 @@ mod tests {
             (
                 CommandBody::Store {
                     sequence_set: SequenceSet::try_from(1).unwrap(),
-                    flags: vec![],
+                    attribute: StoreAttribute::Flags(vec![]),
                     response: StoreResponse::Silent,
                     kind: StoreType::Add,
                     uid: true,
                 },
                 "STORE",
             ),
+            (
+                CommandBody::StoreAnnotation {
+                    sequence_set: SequenceSet::try_from(1).unwrap(),
+                    entries: NonEmptyVec::from(AnnotationEntry {
+                        entry: IString::try_from("/comment").unwrap(),
+                        values: NonEmptyVec::from(AnnotationValue::new(
+                            IString::try_from("value.priv").unwrap(),
+                            NString(None),
+                        )),
+                    }),
+                    uid: false,
+                },
+                "STORE",
+            ),
             (
                 CommandBody::Copy {
                     sequence_set: SequenceSet::try_from(1).unwrap(),
@@ -2054,6 +2258,50 @@ mod tests {
                 },
                 "MOVE",
             ),
+            (
+                CommandBody::Replace {
+                    sequence_number_or_uid: SeqOrUid::Asterisk,
+                    mailbox: Mailbox::Inbox,
+                    flags: vec![],
+                    date: None,
+                    message: Literal::try_from("Hello").unwrap(),
+                    uid: false,
+                },
+                "REPLACE",
+            ),
+            (CommandBody::Language { ranges: vec![] }, "LANGUAGE"),
+            (
+                CommandBody::Comparator {
+                    comparators: vec![],
+                },
+                "COMPARATOR",
+            ),
+            (
+                CommandBody::CancelUpdate {
+                    tags: NonEmptyVec::from(Tag::try_from("A").unwrap()),
+                },
+                "CANCELUPDATE",
+            ),
+            (
+                CommandBody::ESearch {
+                    source_options: vec![FilterMailboxes::Personal],
+                    return_options: vec![],
+                    charset: None,
+                    criteria: SearchKey::All,
+                },
+                "ESEARCH",
+            ),
+            (
+                CommandBody::Convert {
+                    sequence_set: SequenceSet::try_from(1).unwrap(),
+                    items: NonEmptyVec::from(ConvertItemName::BinarySize {
+                        section: None,
+                        params: ConvertParams::new(AString::try_from("image/png").unwrap()),
+                    }),
+                    uid: false,
+                },
+                "CONVERT",
+            ),
         ];
 
         for (test, expected) in tests {
@@ -1,8 +1,26 @@
 //! IMAP extensions.
 
+pub mod annotate;
 pub mod compress;
+pub mod context;
+pub mod convert;
 pub mod enable;
+pub mod esearch;
+pub mod filters;
+pub mod gmail;
 pub mod idle;
+pub mod inprogress;
+pub mod language;
 pub mod r#move;
+pub mod multisearch;
+pub mod partial;
 pub mod quota;
+#[cfg(any(feature = "ext_login_referrals", feature = "ext_mailbox_referrals"))]
+#[cfg_attr(
+    docsrs,
+    doc(cfg(any(feature = "ext_login_referrals", feature = "ext_mailbox_referrals")))
+)]
+pub mod referrals;
+pub mod replace;
+pub mod unauthenticate;
 pub mod unselect;
@@ -0,0 +1,109 @@
+//! IMAP ANNOTATE Extension
+//!
+//! This extends ...
+//!
+//! * [`Capability`](crate::response::Capability) with a new variant:
+//!
+//!     - [`Capability::AnnotateExperiment1`](crate::response::Capability::AnnotateExperiment1)
+//!
+//! * [`MessageDataItemName`](crate::fetch::MessageDataItemName) with a new variant:
+//!
+//!     - [`MessageDataItemName::Annotation`](crate::fetch::MessageDataItemName::Annotation)
+//!
+//! * [`MessageDataItem`](crate::fetch::MessageDataItem) with a new variant:
+//!
+//!     - [`MessageDataItem::Annotation`](crate::fetch::MessageDataItem::Annotation)
+//!
+//! * [`CommandBody`](crate::command::CommandBody) with a new variant:
+//!
+//!     - [`CommandBody::StoreAnnotation`](crate::command::CommandBody::StoreAnnotation)
+//!
+//! * [`SearchKey`](crate::search::SearchKey) with a new variant:
+//!
+//!     - [`SearchKey::Annotation`](crate::search::SearchKey::Annotation)
+//!
+//! * [`Code`](crate::response::Code) with new variants:
+//!
+//!     - [`Code::AnnotateTooBig`](crate::response::Code::AnnotateTooBig)
+//!     - [`Code::AnnotateTooMany`](crate::response::Code::AnnotateTooMany)
+
+#[cfg(feature = "arbitrary")]
+use arbitrary::Arbitrary;
+#[cfg(feature = "bounded-static")]
+use bounded_static::ToStatic;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::core::{IString, NString, NonEmptyVec};
+
+/// An annotation entry together with (some of) its attribute values.
+///
+/// # ABNF definition
+///
+/// ```abnf
+/// entry-att = entry SP "(" att-value *(SP att-value) ")"
+///
+/// entry = string
+/// ```
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AnnotationEntry<'a> {
+    /// Entry name, e.g., `/comment`.
+    pub entry: IString<'a>,
+    /// Attribute values.
+    pub values: NonEmptyVec<AnnotationValue<'a>>,
+}
+
+/// The value of an annotation attribute.
+///
+/// # ABNF definition
+///
+/// ```abnf
+/// att-value = attrib SP value
+///
+/// attrib = string
+///
+/// value = nstring
+/// ```
+///
+/// Note: `literal8` is not supported as `value`.
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AnnotationValue<'a> {
+    /// Attribute name, e.g., `value.priv` or `size.shared`.
+    pub attribute: IString<'a>,
+    /// Attribute value.
+    ///
+    /// Note: Use `NIL` to remove an annotation in STORE.
+    pub value: NString<'a>,
+}
+
+impl<'a> AnnotationValue<'a> {
+    pub fn new(attribute: IString<'a>, value: NString<'a>) -> Self {
+        Self { attribute, value }
+    }
+}
+
+/// Annotation data returned in a FETCH response.
+///
+/// # ABNF definition
+///
+/// ```abnf
+/// fetch-annotation-resp = "ANNOTATION" SP
+///                         ("(" entry-att *(SP entry-att) ")" /
+///                          "(" entry *(SP entry) ")")
+/// ```
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum AnnotationData<'a> {
+    /// Entries with their attribute values (as a result of FETCH ANNOTATION).
+    Values(NonEmptyVec<AnnotationEntry<'a>>),
+    /// Names of changed entries (unsolicited, e.g., when another client changed an annotation).
+    Entries(NonEmptyVec<IString<'a>>),
+}
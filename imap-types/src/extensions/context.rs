@@ -0,0 +1,73 @@
+//! Contexts for IMAP4
+//!
+//! This extends ...
+//!
+//! * [`Capability`](crate::response::Capability) with new variants:
+//!
+//!     - [`Capability::ContextSearch`](crate::response::Capability::ContextSearch)
+//!     - [`Capability::ContextSort`](crate::response::Capability::ContextSort)
+//!
+//! * [`CommandBody`] with a new variant:
+//!
+//!     - [`CommandBody::CancelUpdate`]
+//!
+//! * [`SearchReturnOption`](crate::extensions::esearch::SearchReturnOption) with new variants:
+//!
+//!     - [`SearchReturnOption::Update`](crate::extensions::esearch::SearchReturnOption::Update)
+//!     - [`SearchReturnOption::Context`](crate::extensions::esearch::SearchReturnOption::Context)
+//!
+//! * [`SearchReturnData`](crate::extensions::esearch::SearchReturnData) with new variants:
+//!
+//!     - [`SearchReturnData::AddTo`](crate::extensions::esearch::SearchReturnData::AddTo)
+//!     - [`SearchReturnData::RemoveFrom`](crate::extensions::esearch::SearchReturnData::RemoveFrom)
+//!
+//! * [`Code`](crate::response::Code) with a new variant:
+//!
+//!     - [`Code::NoUpdate`](crate::response::Code::NoUpdate)
+
+#[cfg(feature = "arbitrary")]
+use arbitrary::Arbitrary;
+#[cfg(feature = "bounded-static")]
+use bounded_static::ToStatic;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    command::CommandBody,
+    core::{NonEmptyVec, Tag},
+    sequence::SequenceSet,
+};
+
+impl<'a> CommandBody<'a> {
+    /// Construct a CANCELUPDATE command.
+    pub fn cancel_update<T>(tags: T) -> Result<Self, T::Error>
+    where
+        T: TryInto<NonEmptyVec<Tag<'a>>>,
+    {
+        Ok(CommandBody::CancelUpdate {
+            tags: tags.try_into()?,
+        })
+    }
+}
+
+/// Messages added to (or removed from) a search result at a given position.
+///
+/// # ABNF definition
+///
+/// ```abnf
+/// context-position SP sequence-set
+///
+/// context-position = number
+/// ```
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ContextPosition {
+    /// Position of the first message in the search result.
+    ///
+    /// Note: This is `0` when the position is unknown or irrelevant, e.g., for an unsorted SEARCH.
+    pub position: u32,
+    /// Message numbers/UIDs.
+    pub sequence_set: SequenceSet,
+}
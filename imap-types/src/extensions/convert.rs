@@ -0,0 +1,181 @@
+//! IMAP CONVERT Extension
+//!
+//! This extends ...
+//!
+//! * [`Capability`](crate::response::Capability) with a new variant:
+//!
+//!     - [`Capability::Convert`](crate::response::Capability::Convert)
+//!
+//! * [`CommandBody`] with a new variant:
+//!
+//!     - [`CommandBody::Convert`]
+//!
+//! * [`Data`](crate::response::Data) with a new variant:
+//!
+//!     - [`Data::Converted`](crate::response::Data::Converted)
+//!
+//! * [`Code`](crate::response::Code) with new variants:
+//!
+//!     - [`Code::UnknownCvt`](crate::response::Code::UnknownCvt)
+//!     - [`Code::BadParams`](crate::response::Code::BadParams)
+//!
+//! Note: BINARY (RFC 3516) is not supported in FETCH. Thus, the `BINARY[section]` and
+//! `BINARY.SIZE[section]` items are only available in CONVERT and CONVERTED.
+
+use std::num::NonZeroU32;
+
+#[cfg(feature = "arbitrary")]
+use arbitrary::Arbitrary;
+#[cfg(feature = "bounded-static")]
+use bounded_static::ToStatic;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    command::CommandBody,
+    core::{AString, NString, NonEmptyVec},
+    extensions::{convert::error::ConvertError, esearch::SearchCorrelator},
+    fetch::Part,
+    response::Data,
+    sequence::SequenceSet,
+};
+
+impl<'a> CommandBody<'a> {
+    /// Construct a CONVERT command.
+    pub fn convert<S, I>(
+        sequence_set: S,
+        items: I,
+        uid: bool,
+    ) -> Result<Self, ConvertError<S::Error, I::Error>>
+    where
+        S: TryInto<SequenceSet>,
+        I: TryInto<NonEmptyVec<ConvertItemName<'a>>>,
+    {
+        Ok(CommandBody::Convert {
+            sequence_set: sequence_set.try_into().map_err(ConvertError::Sequence)?,
+            items: items.try_into().map_err(ConvertError::Items)?,
+            uid,
+        })
+    }
+}
+
+impl<'a> Data<'a> {
+    /// Construct a CONVERTED response.
+    pub fn converted(
+        seq: NonZeroU32,
+        correlator: SearchCorrelator<'a>,
+        uid: Option<NonZeroU32>,
+        items: NonEmptyVec<ConvertItem<'a>>,
+    ) -> Self {
+        Data::Converted {
+            seq,
+            correlator,
+            uid,
+            items,
+        }
+    }
+}
+
+/// Conversion requested for a body part.
+///
+/// # ABNF definition
+///
+/// ```abnf
+/// convert-params = "(" media-type [SP "(" param-name SP param-value
+///                  *(SP param-name SP param-value) ")"] ")"
+/// ```
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ConvertParams<'a> {
+    /// Target media type, e.g., `image/png` or `text/plain`.
+    pub media_type: AString<'a>,
+    /// Conversion parameters, e.g., `("CHARSET" "UTF-8")` or `("PIX-X" "320")`.
+    ///
+    /// Note: Use an empty list to omit the parameters.
+    pub params: Vec<(AString<'a>, AString<'a>)>,
+}
+
+impl<'a> ConvertParams<'a> {
+    pub fn new(media_type: AString<'a>) -> Self {
+        Self {
+            media_type,
+            params: vec![],
+        }
+    }
+
+    /// Add a conversion parameter.
+    pub fn with_param(mut self, name: AString<'a>, value: AString<'a>) -> Self {
+        self.params.push((name, value));
+        self
+    }
+}
+
+/// Item requested by CONVERT.
+///
+/// # ABNF definition
+///
+/// ```abnf
+/// convert-att = ("BINARY" / "BINARY.SIZE") section-binary SP convert-params
+///
+/// section-binary = "[" [section-part] "]"
+/// ```
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ConvertItemName<'a> {
+    /// The converted body part.
+    Binary {
+        section: Option<Part>,
+        params: ConvertParams<'a>,
+    },
+    /// The size of the converted body part.
+    BinarySize {
+        section: Option<Part>,
+        params: ConvertParams<'a>,
+    },
+}
+
+/// Item returned in a CONVERTED response.
+///
+/// # ABNF definition
+///
+/// ```abnf
+/// converted-att = "BINARY" section-binary SP convert-params SP nstring /
+///                 "BINARY.SIZE" section-binary SP convert-params SP number
+/// ```
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ConvertItem<'a> {
+    /// The converted body part.
+    ///
+    /// Note: `literal8` is not supported. Thus, the converted data must not contain NUL.
+    Binary {
+        section: Option<Part>,
+        params: ConvertParams<'a>,
+        value: NString<'a>,
+    },
+    /// The size of the converted body part.
+    BinarySize {
+        section: Option<Part>,
+        params: ConvertParams<'a>,
+        size: u32,
+    },
+}
+
+/// Error-related types.
+pub mod error {
+    use thiserror::Error;
+
+    #[derive(Clone, Debug, Eq, Error, Hash, Ord, PartialEq, PartialOrd)]
+    pub enum ConvertError<S, I> {
+        #[error("Invalid sequence: {0}")]
+        Sequence(S),
+        #[error("Invalid items: {0}")]
+        Items(I),
+    }
+}
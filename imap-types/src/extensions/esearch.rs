@@ -0,0 +1,173 @@
+//! IMAP4 Extension for SEARCH Command Controlling What Kind of Information Is Returned
+//!
+//! This extends ...
+//!
+//! * [`Capability`](crate::response::Capability) with a new variant:
+//!
+//!     - [`Capability::ESearch`](crate::response::Capability::ESearch)
+//!
+//! * [`CommandBody::Search`](crate::command::CommandBody::Search) with a new field:
+//!
+//!     - `return_options` (see [`SearchReturnOption`])
+//!
+//! * [`Data`](crate::response::Data) with a new variant:
+//!
+//!     - [`Data::ESearch`](crate::response::Data::ESearch)
+
+use std::num::NonZeroU32;
+
+#[cfg(feature = "arbitrary")]
+use arbitrary::Arbitrary;
+#[cfg(feature = "bounded-static")]
+use bounded_static::ToStatic;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    command::CommandBody,
+    core::{Charset, NonEmptyVec, Tag},
+    extensions::{context::ContextPosition, partial::PartialRange},
+    mailbox::Mailbox,
+    response::Data,
+    search::SearchKey,
+    sequence::SequenceSet,
+};
+
+impl<'a> CommandBody<'a> {
+    /// Construct a SEARCH command with return options.
+    ///
+    /// Note: An empty list of return options is encoded as a "plain" SEARCH command.
+    pub fn search_return(
+        charset: Option<Charset<'a>>,
+        criteria: SearchKey<'a>,
+        return_options: Vec<SearchReturnOption>,
+        uid: bool,
+    ) -> Self {
+        CommandBody::Search {
+            charset,
+            criteria,
+            return_options,
+            uid,
+        }
+    }
+}
+
+impl<'a> Data<'a> {
+    /// Construct an ESEARCH response.
+    pub fn esearch(
+        correlator: Option<SearchCorrelator<'a>>,
+        uid: bool,
+        items: Vec<SearchReturnData>,
+    ) -> Self {
+        Data::ESearch {
+            correlator,
+            uid,
+            items,
+        }
+    }
+}
+
+/// Correlates an ESEARCH response with the command that caused it.
+///
+/// # ABNF definition
+///
+/// ```abnf
+/// search-correlator = SP "(" "TAG" SP tag-string ")"
+///
+/// search-correlator =/ SP "(" "TAG" SP tag-string SP "MAILBOX" SP astring
+///                      SP "UIDVALIDITY" SP nz-number ")" ; RFC 7377
+/// ```
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SearchCorrelator<'a> {
+    /// Tag of the command that caused the response.
+    pub tag: Tag<'a>,
+    /// Mailbox (and its UIDVALIDITY) the results belong to (RFC 7377).
+    pub mailbox: Option<(Mailbox<'a>, NonZeroU32)>,
+}
+
+impl<'a> SearchCorrelator<'a> {
+    /// Correlate by tag only.
+    pub fn tag(tag: Tag<'a>) -> Self {
+        Self { tag, mailbox: None }
+    }
+
+    /// Correlate by tag, mailbox, and UIDVALIDITY.
+    pub fn mailbox(tag: Tag<'a>, mailbox: Mailbox<'a>, uid_validity: NonZeroU32) -> Self {
+        Self {
+            tag,
+            mailbox: Some((mailbox, uid_validity)),
+        }
+    }
+}
+
+impl<'a> From<Tag<'a>> for SearchCorrelator<'a> {
+    fn from(tag: Tag<'a>) -> Self {
+        Self::tag(tag)
+    }
+}
+
+/// Search return option.
+///
+/// Note: `RETURN ()` is equivalent to `RETURN (ALL)` and is decoded as such.
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SearchReturnOption {
+    /// Return the lowest message number/UID that satisfies the SEARCH criteria.
+    Min,
+    /// Return the highest message number/UID that satisfies the SEARCH criteria.
+    Max,
+    /// Return all message numbers/UIDs that satisfy the SEARCH criteria.
+    All,
+    /// Return number of the messages that satisfy the SEARCH criteria.
+    Count,
+    /// Return a subset of the message numbers/UIDs that satisfy the SEARCH criteria.
+    ///
+    /// See RFC 9394.
+    Partial(PartialRange),
+    /// Keep the search result up to date and report changes via ADDTO/REMOVEFROM.
+    ///
+    /// See RFC 5267.
+    Update,
+    /// Hint that the search result will be used again, e.g., for paging.
+    ///
+    /// See RFC 5267.
+    Context,
+}
+
+/// Search return data, i.e., the items of an ESEARCH response.
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SearchReturnData {
+    /// Lowest message number/UID that satisfies the SEARCH criteria.
+    Min(NonZeroU32),
+    /// Highest message number/UID that satisfies the SEARCH criteria.
+    Max(NonZeroU32),
+    /// All message numbers/UIDs that satisfy the SEARCH criteria.
+    All(SequenceSet),
+    /// Number of messages that satisfy the SEARCH criteria.
+    Count(u32),
+    /// Subset of the message numbers/UIDs that satisfy the SEARCH criteria.
+    ///
+    /// See RFC 9394.
+    Partial {
+        /// Requested range.
+        range: PartialRange,
+        /// Message numbers/UIDs in the requested range (`None` when there are none).
+        results: Option<SequenceSet>,
+    },
+    /// Messages that were added to the search result.
+    ///
+    /// See RFC 5267.
+    AddTo(NonEmptyVec<ContextPosition>),
+    /// Messages that were removed from the search result.
+    ///
+    /// See RFC 5267.
+    RemoveFrom(NonEmptyVec<ContextPosition>),
+}
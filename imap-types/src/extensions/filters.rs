@@ -0,0 +1,283 @@
+//! IMAP4 Extension for Named Searches (Filters)
+//!
+//! This extends ...
+//!
+//! * [`Capability`](crate::response::Capability) with a new variant:
+//!
+//!     - [`Capability::Filters`](crate::response::Capability::Filters)
+//!
+//! * [`SearchKey`](crate::search::SearchKey) with a new variant:
+//!
+//!     - [`SearchKey::Filter`](crate::search::SearchKey::Filter)
+//!
+//! Filters are stored as METADATA entries below `/private/filters/` or `/shared/filters/`.
+//! [`FilterEntry`] converts between filter names and these entry names.
+
+use std::{
+    borrow::Cow,
+    fmt::{Display, Formatter},
+    str::from_utf8,
+};
+
+#[cfg(feature = "arbitrary")]
+use arbitrary::Arbitrary;
+#[cfg(feature = "bounded-static")]
+use bounded_static::ToStatic;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::{ValidationError, ValidationErrorKind},
+    utils::indicators::is_filter_name_char,
+};
+
+/// The name of a filter.
+///
+/// # ABNF definition
+///
+/// ```abnf
+/// filter-name = 1*<any ATOM-CHAR except "/">
+/// ```
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FilterName<'a>(pub(crate) Cow<'a, str>);
+
+impl<'a> FilterName<'a> {
+    /// Validates if value conforms to filter-name's ABNF definition.
+    pub fn validate(value: impl AsRef<[u8]>) -> Result<(), ValidationError> {
+        let value = value.as_ref();
+
+        if value.is_empty() {
+            return Err(ValidationError::new(ValidationErrorKind::Empty));
+        }
+
+        if let Some(at) = value.iter().position(|b| !is_filter_name_char(*b)) {
+            return Err(ValidationError::new(ValidationErrorKind::InvalidByteAt {
+                byte: value[at],
+                at,
+            }));
+        };
+
+        Ok(())
+    }
+
+    /// Returns a reference to the inner value.
+    pub fn inner(&self) -> &str {
+        self.0.as_ref()
+    }
+
+    /// Consumes the filter name, returning the inner value.
+    pub fn into_inner(self) -> Cow<'a, str> {
+        self.0
+    }
+
+    /// Constructs a filter name without validation.
+    ///
+    /// # Warning: IMAP conformance
+    ///
+    /// The caller must ensure that `inner` is valid according to [`Self::validate`]. Failing to do
+    /// so may create invalid/unparsable IMAP messages, or even produce unintended protocol flows.
+    /// Do not call this constructor with untrusted data.
+    #[cfg(feature = "unvalidated")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "unvalidated")))]
+    pub fn unvalidated<C>(inner: C) -> Self
+    where
+        C: Into<Cow<'a, str>>,
+    {
+        let inner = inner.into();
+
+        #[cfg(debug_assertions)]
+        Self::validate(inner.as_bytes()).unwrap();
+
+        Self(inner)
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for FilterName<'a> {
+    type Error = ValidationError;
+
+    fn try_from(value: &'a [u8]) -> Result<Self, Self::Error> {
+        Self::validate(value)?;
+
+        // Safety: `unwrap` can't panic due to `validate`.
+        Ok(Self(Cow::Borrowed(from_utf8(value).unwrap())))
+    }
+}
+
+impl<'a> TryFrom<&'a str> for FilterName<'a> {
+    type Error = ValidationError;
+
+    fn try_from(value: &'a str) -> Result<Self, Self::Error> {
+        Self::validate(value)?;
+
+        Ok(Self(Cow::Borrowed(value)))
+    }
+}
+
+impl<'a> TryFrom<String> for FilterName<'a> {
+    type Error = ValidationError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::validate(&value)?;
+
+        Ok(Self(Cow::Owned(value)))
+    }
+}
+
+impl<'a> AsRef<str> for FilterName<'a> {
+    fn as_ref(&self) -> &str {
+        self.0.as_ref()
+    }
+}
+
+impl<'a> Display for FilterName<'a> {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Whether a filter is private to the user or shared with other users.
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FilterScope {
+    /// Stored below `/private/filters/`.
+    Private,
+    /// Stored below `/shared/filters/`.
+    Shared,
+}
+
+/// What a filter METADATA entry holds.
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FilterEntryKind {
+    /// The filter's search program, e.g., `UNSEEN FLAGGED`.
+    Value,
+    /// A human-readable description of the filter.
+    Description,
+}
+
+/// A METADATA entry name that stores a filter.
+///
+/// The [`Display`] implementation produces the entry name, e.g.,
+/// `/private/filters/values/urgent`. Use [`FilterEntry::try_from`] to go the other way.
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FilterEntry<'a> {
+    pub scope: FilterScope,
+    pub kind: FilterEntryKind,
+    pub name: FilterName<'a>,
+}
+
+impl<'a> FilterEntry<'a> {
+    /// Entry holding the search program of filter `name`.
+    pub fn value(scope: FilterScope, name: FilterName<'a>) -> Self {
+        Self {
+            scope,
+            kind: FilterEntryKind::Value,
+            name,
+        }
+    }
+
+    /// Entry holding the description of filter `name`.
+    pub fn description(scope: FilterScope, name: FilterName<'a>) -> Self {
+        Self {
+            scope,
+            kind: FilterEntryKind::Description,
+            name,
+        }
+    }
+}
+
+impl<'a> Display for FilterEntry<'a> {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        let scope = match self.scope {
+            FilterScope::Private => "private",
+            FilterScope::Shared => "shared",
+        };
+
+        let kind = match self.kind {
+            FilterEntryKind::Value => "values",
+            FilterEntryKind::Description => "descriptions",
+        };
+
+        write!(f, "/{scope}/filters/{kind}/{}", self.name)
+    }
+}
+
+impl<'a> TryFrom<&'a str> for FilterEntry<'a> {
+    type Error = ValidationError;
+
+    fn try_from(value: &'a str) -> Result<Self, Self::Error> {
+        let invalid = || ValidationError::new(ValidationErrorKind::Invalid);
+
+        let (scope, rest) = if let Some(rest) = value.strip_prefix("/private/filters/") {
+            (FilterScope::Private, rest)
+        } else if let Some(rest) = value.strip_prefix("/shared/filters/") {
+            (FilterScope::Shared, rest)
+        } else {
+            return Err(invalid());
+        };
+
+        let (kind, name) = if let Some(name) = rest.strip_prefix("values/") {
+            (FilterEntryKind::Value, name)
+        } else if let Some(name) = rest.strip_prefix("descriptions/") {
+            (FilterEntryKind::Description, name)
+        } else {
+            return Err(invalid());
+        };
+
+        Ok(Self {
+            scope,
+            kind,
+            name: FilterName::try_from(name)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conversion_filter_name() {
+        assert!(FilterName::try_from("urgent").is_ok());
+        assert!(FilterName::try_from("").is_err());
+        assert!(FilterName::try_from("a/b").is_err());
+        assert!(FilterName::try_from("a b").is_err());
+    }
+
+    #[test]
+    fn test_conversion_filter_entry() {
+        let tests = [
+            (
+                "/private/filters/values/urgent",
+                FilterEntry::value(FilterScope::Private, "urgent".try_into().unwrap()),
+            ),
+            (
+                "/shared/filters/descriptions/team",
+                FilterEntry::description(FilterScope::Shared, "team".try_into().unwrap()),
+            ),
+        ];
+
+        for (entry, expected) in tests {
+            assert_eq!(FilterEntry::try_from(entry).unwrap(), expected);
+            assert_eq!(expected.to_string(), entry);
+        }
+
+        for entry in [
+            "/private/filters/values/",
+            "/private/filters/values/a/b",
+            "/private/filters/other/urgent",
+            "/vendor/filters/values/urgent",
+        ] {
+            assert!(FilterEntry::try_from(entry).is_err());
+        }
+    }
+}
@@ -0,0 +1,160 @@
+//! Gmail IMAP Extensions (X-GM-EXT-1)
+//!
+//! See <https://developers.google.com/gmail/imap/imap-extensions>.
+//!
+//! This extends ...
+//!
+//! * [`Capability`](crate::response::Capability) with a new variant:
+//!
+//!     - [`Capability::GmailExt1`](crate::response::Capability::GmailExt1)
+//!
+//! * [`MessageDataItemName`](crate::fetch::MessageDataItemName) with new variants:
+//!
+//!     - [`MessageDataItemName::GmailMessageId`](crate::fetch::MessageDataItemName::GmailMessageId)
+//!     - [`MessageDataItemName::GmailThreadId`](crate::fetch::MessageDataItemName::GmailThreadId)
+//!     - [`MessageDataItemName::GmailLabels`](crate::fetch::MessageDataItemName::GmailLabels)
+//!
+//! * [`MessageDataItem`](crate::fetch::MessageDataItem) with new variants:
+//!
+//!     - [`MessageDataItem::GmailMessageId`](crate::fetch::MessageDataItem::GmailMessageId)
+//!     - [`MessageDataItem::GmailThreadId`](crate::fetch::MessageDataItem::GmailThreadId)
+//!     - [`MessageDataItem::GmailLabels`](crate::fetch::MessageDataItem::GmailLabels)
+//!
+//! * [`StoreAttribute`](crate::flag::StoreAttribute) with a new variant:
+//!
+//!     - [`StoreAttribute::GmailLabels`](crate::flag::StoreAttribute::GmailLabels)
+
+#[cfg(feature = "arbitrary")]
+use arbitrary::Arbitrary;
+#[cfg(feature = "bounded-static")]
+use bounded_static::ToStatic;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    command::CommandBody,
+    core::{AString, Atom},
+    error::{ValidationError, ValidationErrorKind},
+    flag::{StoreAttribute, StoreResponse, StoreType},
+    sequence::SequenceSet,
+};
+
+impl<'a> CommandBody<'a> {
+    /// Construct a STORE command that modifies Gmail labels (`X-GM-LABELS`).
+    pub fn store_gmail_labels<S>(
+        sequence_set: S,
+        kind: StoreType,
+        response: StoreResponse,
+        labels: Vec<GmailLabel<'a>>,
+        uid: bool,
+    ) -> Result<Self, S::Error>
+    where
+        S: TryInto<SequenceSet>,
+    {
+        let sequence_set = sequence_set.try_into()?;
+
+        Ok(CommandBody::Store {
+            sequence_set,
+            kind,
+            response,
+            attribute: StoreAttribute::GmailLabels(labels),
+            uid,
+        })
+    }
+}
+
+/// A Gmail label.
+///
+/// Labels are either system labels, e.g., `\Inbox`, `\Sent`, or `\Important`, or user-defined
+/// labels. User-defined labels are `astring`s and may contain spaces or non-ASCII characters.
+///
+/// # ABNF definition
+///
+/// ```abnf
+/// label = "\" atom / astring
+/// ```
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum GmailLabel<'a> {
+    /// A system label, e.g., `\Inbox`.
+    ///
+    /// Note: The atom does not include the leading backslash.
+    System(Atom<'a>),
+    /// A user-defined label.
+    User(AString<'a>),
+}
+
+impl<'a> GmailLabel<'a> {
+    pub fn system(atom: Atom<'a>) -> Self {
+        Self::System(atom)
+    }
+
+    pub fn user(astring: AString<'a>) -> Self {
+        Self::User(astring)
+    }
+}
+
+impl<'a> TryFrom<&'a str> for GmailLabel<'a> {
+    type Error = ValidationError;
+
+    fn try_from(value: &'a str) -> Result<Self, Self::Error> {
+        if value.is_empty() {
+            return Err(ValidationError::new(ValidationErrorKind::Empty));
+        }
+
+        Ok(if let Some(value) = value.strip_prefix('\\') {
+            Self::System(Atom::try_from(value)?)
+        } else {
+            Self::User(AString::try_from(value)?)
+        })
+    }
+}
+
+impl<'a> TryFrom<String> for GmailLabel<'a> {
+    type Error = ValidationError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        if value.is_empty() {
+            return Err(ValidationError::new(ValidationErrorKind::Empty));
+        }
+
+        Ok(if let Some(value) = value.strip_prefix('\\') {
+            Self::System(Atom::try_from(value.to_owned())?)
+        } else {
+            Self::User(AString::try_from(value)?)
+        })
+    }
+}
+
+impl<'a> From<AString<'a>> for GmailLabel<'a> {
+    fn from(value: AString<'a>) -> Self {
+        Self::User(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conversion_gmail_label() {
+        assert_eq!(
+            GmailLabel::try_from("\\Inbox").unwrap(),
+            GmailLabel::System(Atom::try_from("Inbox").unwrap())
+        );
+        assert_eq!(
+            GmailLabel::try_from("Muy Importante").unwrap(),
+            GmailLabel::User(AString::try_from("Muy Importante").unwrap())
+        );
+        assert_eq!(
+            GmailLabel::try_from(String::from("\\Sent")).unwrap(),
+            GmailLabel::System(Atom::try_from("Sent").unwrap())
+        );
+
+        assert!(GmailLabel::try_from("").is_err());
+        assert!(GmailLabel::try_from("\\").is_err());
+        assert!(GmailLabel::try_from("\\In box").is_err());
+    }
+}
@@ -0,0 +1,130 @@
+//! IMAP Response Code for Command Progress Notifications
+//!
+//! This extends ...
+//!
+//! * [`Code`] with a new variant:
+//!
+//!     - [`Code::InProgress`]
+//!
+//! Use [`Status::progress`] or [`Response::progress`] to tie an `INPROGRESS` update to the
+//! command it belongs to.
+
+use std::num::NonZeroU32;
+
+#[cfg(feature = "arbitrary")]
+use arbitrary::Arbitrary;
+#[cfg(feature = "bounded-static")]
+use bounded_static::ToStatic;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    core::Tag,
+    response::{Code, Response, Status},
+};
+
+impl<'a> Code<'a> {
+    pub fn in_progress(
+        tag: Option<Tag<'a>>,
+        current: Option<u32>,
+        goal: Option<NonZeroU32>,
+    ) -> Self {
+        Self::InProgress { tag, current, goal }
+    }
+}
+
+/// Progress of a long-running command as reported by [`Code::InProgress`].
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Progress {
+    /// Number of items processed so far.
+    pub current: Option<u32>,
+    /// Total number of items to process.
+    pub goal: Option<NonZeroU32>,
+}
+
+impl<'a> Status<'a> {
+    /// Return the progress reported for the command tagged with `tag`.
+    ///
+    /// Returns `None` when this is not an untagged OK response with an `INPROGRESS` code that
+    /// references `tag`. Note that servers may send `INPROGRESS` without a tag. Such updates
+    /// can't be attributed to a command and are thus ignored here.
+    pub fn progress(&self, tag: &Tag) -> Option<Progress> {
+        match self {
+            Status::Ok {
+                tag: None,
+                code:
+                    Some(Code::InProgress {
+                        tag: Some(progress_tag),
+                        current,
+                        goal,
+                    }),
+                ..
+            } if progress_tag == tag => Some(Progress {
+                current: *current,
+                goal: *goal,
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl<'a> Response<'a> {
+    /// Return the progress reported for the command tagged with `tag`.
+    ///
+    /// See [`Status::progress`].
+    pub fn progress(&self, tag: &Tag) -> Option<Progress> {
+        match self {
+            Response::Status(status) => status.progress(tag),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress() {
+        let tag = Tag::try_from("A1").unwrap();
+        let other = Tag::try_from("A2").unwrap();
+
+        let status = Status::ok(
+            None,
+            Some(Code::in_progress(
+                Some(tag.clone()),
+                Some(10),
+                NonZeroU32::new(100),
+            )),
+            "Still searching",
+        )
+        .unwrap();
+
+        assert_eq!(
+            status.progress(&tag),
+            Some(Progress {
+                current: Some(10),
+                goal: NonZeroU32::new(100),
+            })
+        );
+        assert_eq!(status.progress(&other), None);
+        assert_eq!(
+            Response::Status(status.clone()).progress(&tag),
+            status.progress(&tag)
+        );
+
+        let untagged = Status::ok(None, Some(Code::in_progress(None, None, None)), "...").unwrap();
+        assert_eq!(untagged.progress(&tag), None);
+
+        let tagged = Status::ok(
+            Some(tag.clone()),
+            Some(Code::in_progress(Some(tag.clone()), None, None)),
+            "...",
+        )
+        .unwrap();
+        assert_eq!(tagged.progress(&tag), None);
+    }
+}
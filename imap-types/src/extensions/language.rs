@@ -0,0 +1,77 @@
+//! Internet Message Access Protocol Internationalization
+//!
+//! This extends ...
+//!
+//! * [`Capability`](crate::response::Capability) with new variants:
+//!
+//!     - [`Capability::Language`](crate::response::Capability::Language)
+//!     - [`Capability::I18nLevel`](crate::response::Capability::I18nLevel)
+//!
+//! * [`CommandBody`] with new variants:
+//!
+//!     - [`CommandBody::Language`]
+//!     - [`CommandBody::Comparator`]
+//!
+//! * [`Data`] with new variants:
+//!
+//!     - [`Data::Language`]
+//!     - [`Data::Comparator`]
+//!
+//! * [`Code`](crate::response::Code) with a new variant:
+//!
+//!     - [`Code::BadComparator`](crate::response::Code::BadComparator)
+
+use crate::{
+    command::CommandBody,
+    core::{AString, NonEmptyVec},
+    response::Data,
+};
+
+impl<'a> CommandBody<'a> {
+    /// Construct a LANGUAGE command.
+    ///
+    /// Note: Use an empty list to request the list of supported languages.
+    pub fn language<R>(ranges: R) -> Result<Self, R::Error>
+    where
+        R: TryInto<Vec<AString<'a>>>,
+    {
+        Ok(CommandBody::Language {
+            ranges: ranges.try_into()?,
+        })
+    }
+
+    /// Construct a COMPARATOR command.
+    ///
+    /// Note: Use an empty list to request the active comparator.
+    pub fn comparator<C>(comparators: C) -> Result<Self, C::Error>
+    where
+        C: TryInto<Vec<AString<'a>>>,
+    {
+        Ok(CommandBody::Comparator {
+            comparators: comparators.try_into()?,
+        })
+    }
+}
+
+impl<'a> Data<'a> {
+    /// Construct a LANGUAGE response.
+    pub fn language<T>(tags: T) -> Result<Self, T::Error>
+    where
+        T: TryInto<NonEmptyVec<AString<'a>>>,
+    {
+        Ok(Data::Language {
+            tags: tags.try_into()?,
+        })
+    }
+
+    /// Construct a COMPARATOR response.
+    pub fn comparator<S>(selected: S, matching: Vec<AString<'a>>) -> Result<Self, S::Error>
+    where
+        S: TryInto<AString<'a>>,
+    {
+        Ok(Data::Comparator {
+            selected: selected.try_into()?,
+            matching,
+        })
+    }
+}
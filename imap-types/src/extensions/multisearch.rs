@@ -0,0 +1,88 @@
+//! IMAP4 Multimailbox SEARCH Extension
+//!
+//! This extends ...
+//!
+//! * [`Capability`](crate::response::Capability) with a new variant:
+//!
+//!     - [`Capability::MultiSearch`](crate::response::Capability::MultiSearch)
+//!
+//! * [`CommandBody`] with a new variant:
+//!
+//!     - [`CommandBody::ESearch`]
+//!
+//! * [`SearchCorrelator`](crate::extensions::esearch::SearchCorrelator) with a new field:
+//!
+//!     - `mailbox`
+
+#[cfg(feature = "arbitrary")]
+use arbitrary::Arbitrary;
+#[cfg(feature = "bounded-static")]
+use bounded_static::ToStatic;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    command::CommandBody,
+    core::{Charset, NonEmptyVec},
+    extensions::esearch::SearchReturnOption,
+    mailbox::Mailbox,
+    search::SearchKey,
+};
+
+impl<'a> CommandBody<'a> {
+    /// Construct an ESEARCH command.
+    pub fn esearch(
+        source_options: Vec<FilterMailboxes<'a>>,
+        return_options: Vec<SearchReturnOption>,
+        charset: Option<Charset<'a>>,
+        criteria: SearchKey<'a>,
+    ) -> Self {
+        CommandBody::ESearch {
+            source_options,
+            return_options,
+            charset,
+            criteria,
+        }
+    }
+}
+
+/// Mailboxes to search.
+///
+/// # ABNF definition
+///
+/// ```abnf
+/// filter-mailboxes = filter-mailboxes-selected / filter-mailboxes-other
+///
+/// filter-mailboxes-selected = "selected" / "selected-delayed"
+///
+/// filter-mailboxes-other = "inboxes" / "personal" / "subscribed" /
+///                          ("subtree" SP one-or-more-mailbox) /
+///                          ("subtree-one" SP one-or-more-mailbox) /
+///                          ("mailboxes" SP one-or-more-mailbox)
+///
+/// one-or-more-mailbox = mailbox / many-mailboxes
+///
+/// many-mailboxes = "(" mailbox *(SP mailbox) ")"
+/// ```
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum FilterMailboxes<'a> {
+    /// The currently selected mailbox.
+    Selected,
+    /// The currently selected mailbox (with delayed notifications).
+    SelectedDelayed,
+    /// All mailboxes that receive messages, e.g., INBOX.
+    Inboxes,
+    /// All mailboxes in the personal namespace(s).
+    Personal,
+    /// All subscribed mailboxes.
+    Subscribed,
+    /// The given mailboxes and all mailboxes below them.
+    Subtree(NonEmptyVec<Mailbox<'a>>),
+    /// The given mailboxes and their immediate children.
+    SubtreeOne(NonEmptyVec<Mailbox<'a>>),
+    /// The given mailboxes.
+    Mailboxes(NonEmptyVec<Mailbox<'a>>),
+}
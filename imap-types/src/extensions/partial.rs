@@ -0,0 +1,50 @@
+//! IMAP PARTIAL Extension for Paged SEARCH and FETCH
+//!
+//! This extends ...
+//!
+//! * [`Capability`](crate::response::Capability) with a new variant:
+//!
+//!     - [`Capability::Partial`](crate::response::Capability::Partial)
+//!
+//! * [`SearchReturnOption`](crate::extensions::esearch::SearchReturnOption) with a new variant:
+//!
+//!     - [`SearchReturnOption::Partial`](crate::extensions::esearch::SearchReturnOption::Partial)
+//!
+//! * [`SearchReturnData`](crate::extensions::esearch::SearchReturnData) with a new variant:
+//!
+//!     - [`SearchReturnData::Partial`](crate::extensions::esearch::SearchReturnData::Partial)
+//!
+//! * [`FetchModifier`](crate::fetch::FetchModifier) with a new variant:
+//!
+//!     - [`FetchModifier::Partial`](crate::fetch::FetchModifier::Partial)
+
+use std::num::NonZeroU32;
+
+#[cfg(feature = "arbitrary")]
+use arbitrary::Arbitrary;
+#[cfg(feature = "bounded-static")]
+use bounded_static::ToStatic;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A range of results, counted either from the first or from the last result.
+///
+/// # ABNF definition
+///
+/// ```abnf
+/// partial-range-first = nz-number ":" nz-number
+/// partial-range-last  = MINUS nz-number ":" MINUS nz-number
+/// partial-range       = partial-range-first / partial-range-last
+/// ```
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PartialRange {
+    /// Count from the first result, e.g., `1:100` are the first 100 results.
+    First(NonZeroU32, NonZeroU32),
+    /// Count from the last result, e.g., `-1:-100` are the last 100 results.
+    ///
+    /// Note: The numbers are stored without the minus sign.
+    Last(NonZeroU32, NonZeroU32),
+}
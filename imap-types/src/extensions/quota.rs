@@ -199,6 +199,20 @@ impl<'a> From<Atom<'a>> for Resource<'a> {
     }
 }
 
+impl<'a> Resource<'a> {
+    /// Returns the unit in which usage and limits of this resource are counted.
+    ///
+    /// Returns `None` for unknown resources.
+    pub fn unit(&self) -> Option<ResourceUnit> {
+        match self {
+            Self::Storage | Self::AnnotationStorage => Some(ResourceUnit::Kibibytes),
+            Self::Message => Some(ResourceUnit::Messages),
+            Self::Mailbox => Some(ResourceUnit::Mailboxes),
+            Self::Other(_) => None,
+        }
+    }
+}
+
 impl<'a> Display for Resource<'a> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         f.write_str(match self {
@@ -211,6 +225,32 @@ impl<'a> Display for Resource<'a> {
     }
 }
 
+/// The unit of a [`Resource`].
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ResourceUnit {
+    /// Units of 1024 octets, e.g., for `STORAGE` and `ANNOTATION-STORAGE`.
+    Kibibytes,
+    /// Number of messages, e.g., for `MESSAGE`.
+    Messages,
+    /// Number of mailboxes, e.g., for `MAILBOX`.
+    Mailboxes,
+}
+
+impl ResourceUnit {
+    /// Converts an amount in this unit to octets.
+    ///
+    /// Returns `None` when the unit is not storage-based or the result overflows.
+    pub fn to_octets(&self, amount: u64) -> Option<u64> {
+        match self {
+            Self::Kibibytes => amount.checked_mul(1024),
+            Self::Messages | Self::Mailboxes => None,
+        }
+    }
+}
+
 /// A type that holds a resource name, usage, and limit.
 /// Used in the response of the GETQUOTA command.
 #[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
@@ -231,6 +271,16 @@ impl<'a> QuotaGet<'a> {
             limit,
         }
     }
+
+    /// Returns the usage in octets for storage-based resources.
+    pub fn usage_octets(&self) -> Option<u64> {
+        self.resource.unit()?.to_octets(self.usage)
+    }
+
+    /// Returns the limit in octets for storage-based resources.
+    pub fn limit_octets(&self) -> Option<u64> {
+        self.resource.unit()?.to_octets(self.limit)
+    }
 }
 
 /// A type that holds a resource name and limit.
@@ -278,3 +328,35 @@ pub mod error {
         QuotaSet(S),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resource_unit() {
+        let tests = [
+            (Resource::Storage, Some(ResourceUnit::Kibibytes)),
+            (Resource::AnnotationStorage, Some(ResourceUnit::Kibibytes)),
+            (Resource::Message, Some(ResourceUnit::Messages)),
+            (Resource::Mailbox, Some(ResourceUnit::Mailboxes)),
+            (Resource::try_from("X-CUSTOM").unwrap(), None),
+        ];
+
+        for (resource, expected) in tests {
+            assert_eq!(resource.unit(), expected);
+        }
+    }
+
+    #[test]
+    fn test_quota_get_octets() {
+        let quota = QuotaGet::new(Resource::Storage, 10, 512);
+        assert_eq!(quota.usage_octets(), Some(10 * 1024));
+        assert_eq!(quota.limit_octets(), Some(512 * 1024));
+
+        let quota = QuotaGet::new(Resource::Message, 10, 512);
+        assert_eq!(quota.usage_octets(), None);
+
+        assert_eq!(ResourceUnit::Kibibytes.to_octets(u64::MAX), None);
+    }
+}
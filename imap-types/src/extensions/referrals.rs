@@ -0,0 +1,376 @@
+//! IMAP4 Login Referrals and IMAP4 Mailbox Referrals
+//!
+//! This extends ...
+//!
+//! * [`Capability`](crate::response::Capability) with new variants:
+//!
+//!     - [`Capability::LoginReferrals`](crate::response::Capability::LoginReferrals)
+//!     - [`Capability::MailboxReferrals`](crate::response::Capability::MailboxReferrals)
+//!
+//! * [`Code`](crate::response::Code) with a new variant:
+//!
+//!     - [`Code::Referral`](crate::response::Code::Referral) (carrying one or more [`ImapUrl`]s)
+//!
+//! * [`CommandBody`](crate::command::CommandBody) with new variants (mailbox referrals only):
+//!
+//!     - [`CommandBody::RList`](crate::command::CommandBody::RList)
+//!     - [`CommandBody::RLsub`](crate::command::CommandBody::RLsub)
+//!
+//! * [`FlagNameAttribute`](crate::flag::FlagNameAttribute) with a new variant (mailbox referrals only):
+//!
+//!     - [`FlagNameAttribute::Remote`](crate::flag::FlagNameAttribute::Remote)
+
+use std::{
+    borrow::Cow,
+    fmt::{Display, Formatter},
+    num::NonZeroU32,
+};
+
+#[cfg(feature = "bounded-static")]
+use bounded_static::ToStatic;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ValidationError, ValidationErrorKind};
+#[cfg(feature = "ext_mailbox_referrals")]
+use crate::{
+    command::{error::ListError, CommandBody},
+    mailbox::{ListMailbox, Mailbox},
+};
+
+#[cfg(feature = "ext_mailbox_referrals")]
+#[cfg_attr(docsrs, doc(cfg(feature = "ext_mailbox_referrals")))]
+impl<'a> CommandBody<'a> {
+    /// Construct an RLIST command.
+    pub fn rlist<A, B>(
+        reference: A,
+        mailbox_wildcard: B,
+    ) -> Result<Self, ListError<A::Error, B::Error>>
+    where
+        A: TryInto<Mailbox<'a>>,
+        B: TryInto<ListMailbox<'a>>,
+    {
+        Ok(CommandBody::RList {
+            reference: reference.try_into().map_err(ListError::Reference)?,
+            mailbox_wildcard: mailbox_wildcard.try_into().map_err(ListError::Mailbox)?,
+        })
+    }
+
+    /// Construct an RLSUB command.
+    pub fn rlsub<A, B>(
+        reference: A,
+        mailbox_wildcard: B,
+    ) -> Result<Self, ListError<A::Error, B::Error>>
+    where
+        A: TryInto<Mailbox<'a>>,
+        B: TryInto<ListMailbox<'a>>,
+    {
+        Ok(CommandBody::RLsub {
+            reference: reference.try_into().map_err(ListError::Reference)?,
+            mailbox_wildcard: mailbox_wildcard.try_into().map_err(ListError::Mailbox)?,
+        })
+    }
+}
+
+/// An IMAP URL pointing to a server or mailbox, as used in referrals.
+///
+/// Components are kept in their percent-encoded form.
+///
+/// # ABNF definition
+///
+/// ```abnf
+/// imapurl     = "imap://" iserver "/" [enc-mailbox [uidvalidity]]
+///
+/// iserver     = [iuserinfo "@"] host [":" port]
+///
+/// iuserinfo   = enc-user [iauth] / [enc-user] iauth
+///
+/// iauth       = ";AUTH=" ("*" / enc-auth-type)
+///
+/// uidvalidity = ";UIDVALIDITY=" nz-number
+/// ```
+///
+/// Note: URLs that point to messages (`/;UID=`) or contain a search (`?`) are not supported.
+/// IP literals (`[...]`) are not supported as host.
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ImapUrl<'a> {
+    user: Option<Cow<'a, str>>,
+    auth: Option<ImapUrlAuth<'a>>,
+    host: Cow<'a, str>,
+    port: Option<u16>,
+    mailbox: Option<Cow<'a, str>>,
+    uid_validity: Option<NonZeroU32>,
+}
+
+/// The authentication mechanism of an [`ImapUrl`].
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ImapUrlAuth<'a> {
+    /// Any mechanism (`;AUTH=*`).
+    Any,
+    /// A specific mechanism, e.g., `;AUTH=GSSAPI`.
+    Mechanism(Cow<'a, str>),
+}
+
+impl<'a> ImapUrl<'a> {
+    /// Returns the (percent-encoded) user.
+    pub fn user(&self) -> Option<&str> {
+        self.user.as_deref()
+    }
+
+    /// Returns the authentication mechanism.
+    pub fn auth(&self) -> Option<&ImapUrlAuth<'a>> {
+        self.auth.as_ref()
+    }
+
+    /// Returns the host.
+    pub fn host(&self) -> &str {
+        self.host.as_ref()
+    }
+
+    /// Returns the port.
+    pub fn port(&self) -> Option<u16> {
+        self.port
+    }
+
+    /// Returns the (percent-encoded) mailbox.
+    pub fn mailbox(&self) -> Option<&str> {
+        self.mailbox.as_deref()
+    }
+
+    /// Returns the UIDVALIDITY of the mailbox.
+    pub fn uid_validity(&self) -> Option<NonZeroU32> {
+        self.uid_validity
+    }
+}
+
+impl<'a> TryFrom<&'a str> for ImapUrl<'a> {
+    type Error = ValidationError;
+
+    fn try_from(value: &'a str) -> Result<Self, Self::Error> {
+        let invalid = || ValidationError::new(ValidationErrorKind::Invalid);
+
+        let rest = strip_prefix_no_case(value, "imap://").ok_or_else(invalid)?;
+        let (server, path) = rest.split_once('/').ok_or_else(invalid)?;
+
+        let (userinfo, hostport) = match server.split_once('@') {
+            Some((userinfo, hostport)) => (Some(userinfo), hostport),
+            None => (None, server),
+        };
+
+        let (user, auth) = match userinfo {
+            Some(userinfo) => {
+                let (user, auth) = match userinfo.split_once(';') {
+                    Some((user, auth)) => {
+                        let auth = strip_prefix_no_case(auth, "AUTH=").ok_or_else(invalid)?;
+                        let auth = if auth == "*" {
+                            ImapUrlAuth::Any
+                        } else {
+                            validate_chars(auth, is_achar)?;
+                            ImapUrlAuth::Mechanism(Cow::Borrowed(auth))
+                        };
+
+                        (user, Some(auth))
+                    }
+                    None => (userinfo, None),
+                };
+
+                let user = if user.is_empty() {
+                    if auth.is_none() {
+                        return Err(invalid());
+                    }
+                    None
+                } else {
+                    validate_chars(user, is_achar)?;
+                    Some(Cow::Borrowed(user))
+                };
+
+                (user, auth)
+            }
+            None => (None, None),
+        };
+
+        let (host, port) = match hostport.split_once(':') {
+            Some((host, port)) => (host, Some(port.parse::<u16>().map_err(|_| invalid())?)),
+            None => (hostport, None),
+        };
+        validate_chars(host, is_host_char)?;
+
+        let (mailbox, uid_validity) = if path.is_empty() {
+            (None, None)
+        } else {
+            let (mailbox, uid_validity) = match path.split_once(';') {
+                Some((mailbox, uid_validity)) => {
+                    let uid_validity =
+                        strip_prefix_no_case(uid_validity, "UIDVALIDITY=").ok_or_else(invalid)?;
+
+                    (
+                        mailbox,
+                        Some(uid_validity.parse::<NonZeroU32>().map_err(|_| invalid())?),
+                    )
+                }
+                None => (path, None),
+            };
+            validate_chars(mailbox, is_bchar)?;
+
+            (Some(Cow::Borrowed(mailbox)), uid_validity)
+        };
+
+        Ok(Self {
+            user,
+            auth,
+            host: Cow::Borrowed(host),
+            port,
+            mailbox,
+            uid_validity,
+        })
+    }
+}
+
+impl<'a> Display for ImapUrl<'a> {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        f.write_str("imap://")?;
+
+        if self.user.is_some() || self.auth.is_some() {
+            if let Some(user) = &self.user {
+                f.write_str(user)?;
+            }
+            match &self.auth {
+                Some(ImapUrlAuth::Any) => f.write_str(";AUTH=*")?,
+                Some(ImapUrlAuth::Mechanism(mechanism)) => write!(f, ";AUTH={mechanism}")?,
+                None => {}
+            }
+            f.write_str("@")?;
+        }
+
+        f.write_str(&self.host)?;
+        if let Some(port) = self.port {
+            write!(f, ":{port}")?;
+        }
+
+        f.write_str("/")?;
+        if let Some(mailbox) = &self.mailbox {
+            f.write_str(mailbox)?;
+        }
+        if let Some(uid_validity) = self.uid_validity {
+            write!(f, ";UIDVALIDITY={uid_validity}")?;
+        }
+
+        Ok(())
+    }
+}
+
+fn strip_prefix_no_case<'a>(value: &'a str, prefix: &str) -> Option<&'a str> {
+    match value.get(..prefix.len()) {
+        Some(head) if head.eq_ignore_ascii_case(prefix) => Some(&value[prefix.len()..]),
+        _ => None,
+    }
+}
+
+fn validate_chars(value: &str, is_char: fn(u8) -> bool) -> Result<(), ValidationError> {
+    let bytes = value.as_bytes();
+
+    if bytes.is_empty() {
+        return Err(ValidationError::new(ValidationErrorKind::Empty));
+    }
+
+    let mut at = 0;
+    while at < bytes.len() {
+        if bytes[at] == b'%' {
+            match bytes.get(at + 1..at + 3) {
+                Some([a, b]) if a.is_ascii_hexdigit() && b.is_ascii_hexdigit() => at += 3,
+                _ => {
+                    return Err(ValidationError::new(ValidationErrorKind::InvalidByteAt {
+                        byte: b'%',
+                        at,
+                    }))
+                }
+            }
+        } else if is_char(bytes[at]) {
+            at += 1;
+        } else {
+            return Err(ValidationError::new(ValidationErrorKind::InvalidByteAt {
+                byte: bytes[at],
+                at,
+            }));
+        }
+    }
+
+    Ok(())
+}
+
+/// `achar = unreserved / sub-delims-sh / "&" / "=" / "~"` (pct-encoded is handled separately)
+fn is_achar(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric()
+        || matches!(
+            byte,
+            b'-' | b'.' | b'_' | b'~' | b'!' | b'$' | b'\'' | b'(' | b')' | b'*' | b'+' | b','
+        )
+        || matches!(byte, b'&' | b'=')
+}
+
+/// `bchar = achar / ":" / "@" / "/"`
+fn is_bchar(byte: u8) -> bool {
+    is_achar(byte) || matches!(byte, b':' | b'@' | b'/')
+}
+
+/// `reg-name = *(unreserved / pct-encoded / sub-delims)`
+///
+/// Note: `;` is excluded to stay unambiguous.
+fn is_host_char(byte: u8) -> bool {
+    is_achar(byte)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conversion_imap_url() {
+        let tests = [
+            "imap://SERVER2/",
+            "imap://user;AUTH=*@SERVER2/",
+            "imap://;AUTH=GSSAPI@server2.example.com:143/SHARED/FOO",
+            "imap://user@server2/INBOX;UIDVALIDITY=385759045",
+            "imap://server2/%E4%BD%A0%E5%A5%BD",
+        ];
+
+        for test in tests {
+            let url = ImapUrl::try_from(test).unwrap();
+            assert_eq!(url.to_string(), test);
+        }
+
+        let url = ImapUrl::try_from("IMAP://user;AUTH=*@SERVER2:1430/REMOTE").unwrap();
+        assert_eq!(url.user(), Some("user"));
+        assert_eq!(url.auth(), Some(&ImapUrlAuth::Any));
+        assert_eq!(url.host(), "SERVER2");
+        assert_eq!(url.port(), Some(1430));
+        assert_eq!(url.mailbox(), Some("REMOTE"));
+        assert_eq!(url.uid_validity(), None);
+    }
+
+    #[test]
+    fn test_conversion_imap_url_failing() {
+        let tests = [
+            "",
+            "imap://",
+            "imap://server",
+            "http://server/",
+            "imap://@server/",
+            "imap://user;PASS=x@server/",
+            "imap://server:port/",
+            "imap://server/INBOX;UID=1",
+            "imap://server/INBOX;UIDVALIDITY=0",
+            "imap://server/IN BOX",
+            "imap://server/%zz",
+            "imap://[::1]/",
+        ];
+
+        for test in tests {
+            assert!(ImapUrl::try_from(test).is_err(), "{test}");
+        }
+    }
+}
@@ -0,0 +1,60 @@
+//! IMAP REPLACE Extension
+//!
+//! This extends ...
+//!
+//! * [`Capability`](crate::response::Capability) with a new variant:
+//!
+//!     - [`Capability::Replace`](crate::response::Capability::Replace)
+//!
+//! * [`CommandBody`](crate::command::CommandBody) with a new variant:
+//!
+//!     - [`CommandBody::Replace`](crate::command::CommandBody::Replace)
+
+use crate::{
+    command::CommandBody, core::Literal, datetime::DateTime,
+    extensions::replace::error::ReplaceError, flag::Flag, mailbox::Mailbox, sequence::SeqOrUid,
+};
+
+impl<'a> CommandBody<'a> {
+    /// Construct a REPLACE command.
+    #[allow(clippy::type_complexity)]
+    pub fn replace<S, M, D>(
+        sequence_number_or_uid: S,
+        mailbox: M,
+        flags: Vec<Flag<'a>>,
+        date: Option<DateTime>,
+        message: D,
+        uid: bool,
+    ) -> Result<Self, ReplaceError<S::Error, M::Error, D::Error>>
+    where
+        S: TryInto<SeqOrUid>,
+        M: TryInto<Mailbox<'a>>,
+        D: TryInto<Literal<'a>>,
+    {
+        Ok(CommandBody::Replace {
+            sequence_number_or_uid: sequence_number_or_uid
+                .try_into()
+                .map_err(ReplaceError::Sequence)?,
+            mailbox: mailbox.try_into().map_err(ReplaceError::Mailbox)?,
+            flags,
+            date,
+            message: message.try_into().map_err(ReplaceError::Data)?,
+            uid,
+        })
+    }
+}
+
+/// Error-related types.
+pub mod error {
+    use thiserror::Error;
+
+    #[derive(Clone, Debug, Eq, Error, Hash, Ord, PartialEq, PartialOrd)]
+    pub enum ReplaceError<S, M, D> {
+        #[error("Invalid sequence number or UID: {0}")]
+        Sequence(S),
+        #[error("Invalid mailbox: {0}")]
+        Mailbox(M),
+        #[error("Invalid data: {0}")]
+        Data(D),
+    }
+}
@@ -0,0 +1,70 @@
+//! The IMAP UNAUTHENTICATE Extension
+//!
+//! This extends ...
+//!
+//! * [`CommandBody`] with a new variant:
+//!
+//!     - [`CommandBody::Unauthenticate`]
+//!
+//! * [`Capability`](crate::response::Capability) with a new variant:
+//!
+//!     - [`Capability::Unauthenticate`](crate::response::Capability::Unauthenticate)
+//!
+//! * [`State`] with a new transition:
+//!
+//!     - [`State::unauthenticate`]
+
+use crate::{command::CommandBody, state::State};
+
+impl CommandBody<'_> {
+    pub fn unauthenticate() -> Self {
+        CommandBody::Unauthenticate
+    }
+}
+
+impl<'a> State<'a> {
+    /// Transition to [`State::NotAuthenticated`] after a successful UNAUTHENTICATE command.
+    ///
+    /// UNAUTHENTICATE is only valid in the Authenticated and Selected state. In any other state,
+    /// the state is left untouched and `false` is returned.
+    pub fn unauthenticate(&mut self) -> bool {
+        match self {
+            State::Authenticated | State::Selected(_) => {
+                *self = State::NotAuthenticated;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{core::Tag, mailbox::Mailbox};
+
+    #[test]
+    fn test_state_unauthenticate() {
+        let tests = [
+            (State::Authenticated, State::NotAuthenticated, true),
+            (
+                State::Selected(Mailbox::Inbox),
+                State::NotAuthenticated,
+                true,
+            ),
+            (State::Greeting, State::Greeting, false),
+            (State::NotAuthenticated, State::NotAuthenticated, false),
+            (State::Logout, State::Logout, false),
+            (
+                State::IdleAuthenticated(Tag::try_from("A").unwrap()),
+                State::IdleAuthenticated(Tag::try_from("A").unwrap()),
+                false,
+            ),
+        ];
+
+        for (mut state, expected_state, expected) in tests {
+            assert_eq!(state.unauthenticate(), expected);
+            assert_eq!(state, expected_state);
+        }
+    }
+}
@@ -17,7 +17,9 @@ use crate::{
     core::{AString, NString, NonEmptyVec},
     datetime::DateTime,
     envelope::Envelope,
+    extensions::{annotate::AnnotationData, gmail::GmailLabel, partial::PartialRange},
     flag::FlagFetch,
+    mailbox::ListMailbox,
 };
 
 /// Shorthands for commonly-used message data items.
@@ -57,6 +59,24 @@ impl Display for Macro {
     }
 }
 
+/// A fetch modifier (RFC 4466).
+///
+/// # ABNF definition
+///
+/// ```abnf
+/// fetch-modifiers = SP "(" fetch-modifier *(SP fetch-modifier) ")"
+/// ```
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum FetchModifier {
+    /// Only return a subset of the matching messages.
+    ///
+    /// See RFC 9394.
+    Partial(PartialRange),
+}
+
 /// Either a macro or a list of message data items.
 ///
 /// A macro must be used by itself, and not in conjunction with other macros or data items.
@@ -229,6 +249,59 @@ pub enum MessageDataItemName<'a> {
     /// UID
     /// ```
     Uid,
+
+    /// The date and time the message was saved in the mailbox (see RFC 8514).
+    ///
+    /// ```imap
+    /// SAVEDATE
+    /// ```
+    SaveDate,
+
+    /// A brief text summary of the message (see RFC 8970).
+    ///
+    /// When `lazy` is set, the server may return `NIL` instead of generating a preview that is
+    /// expensive to compute. The client can then request it later without `LAZY`.
+    ///
+    /// ```imap
+    /// PREVIEW
+    /// PREVIEW (LAZY)
+    /// ```
+    Preview { lazy: bool },
+
+    /// The Gmail message ID (see [Gmail IMAP Extensions](crate::extensions::gmail)).
+    ///
+    /// ```imap
+    /// X-GM-MSGID
+    /// ```
+    GmailMessageId,
+
+    /// The Gmail thread ID (see [Gmail IMAP Extensions](crate::extensions::gmail)).
+    ///
+    /// ```imap
+    /// X-GM-THRID
+    /// ```
+    GmailThreadId,
+
+    /// The Gmail labels (see [Gmail IMAP Extensions](crate::extensions::gmail)).
+    ///
+    /// ```imap
+    /// X-GM-LABELS
+    /// ```
+    GmailLabels,
+
+    /// Annotations (see [IMAP ANNOTATE Extension](crate::extensions::annotate)).
+    ///
+    /// ```imap
+    /// ANNOTATION (<entries> <attributes>)
+    /// ```
+    ///
+    /// Entries and attributes may contain the wildcards `*` and `%`.
+    Annotation {
+        /// Entries, e.g., `/comment` or `/*`.
+        entries: NonEmptyVec<ListMailbox<'a>>,
+        /// Attributes, e.g., `value.priv` or `value.*`.
+        attributes: NonEmptyVec<ListMailbox<'a>>,
+    },
 }
 
 /// Message data item.
@@ -356,6 +429,53 @@ pub enum MessageDataItem<'a> {
     /// UID
     /// ```
     Uid(NonZeroU32),
+
+    /// The date and time the message was saved in the mailbox (see RFC 8514).
+    ///
+    /// `None` (`NIL`) indicates that the server can't provide a save date for the message.
+    ///
+    /// ```imap
+    /// SAVEDATE
+    /// ```
+    SaveDate(Option<DateTime>),
+
+    /// A brief text summary of the message (see RFC 8970).
+    ///
+    /// `NIL` indicates that the server didn't generate a preview (yet), e.g., as a result of
+    /// `PREVIEW (LAZY)`. An empty string indicates that there is no text to preview.
+    ///
+    /// ```imap
+    /// PREVIEW
+    /// ```
+    Preview(NString<'a>),
+
+    /// A number expressing the Gmail message ID of a message.
+    ///
+    /// ```imap
+    /// X-GM-MSGID
+    /// ```
+    GmailMessageId(u64),
+
+    /// A number expressing the Gmail thread ID of a message.
+    ///
+    /// ```imap
+    /// X-GM-THRID
+    /// ```
+    GmailThreadId(u64),
+
+    /// A list of Gmail labels that are set for a message.
+    ///
+    /// ```imap
+    /// X-GM-LABELS
+    /// ```
+    GmailLabels(Vec<GmailLabel<'a>>),
+
+    /// Annotations of a message.
+    ///
+    /// ```imap
+    /// ANNOTATION
+    /// ```
+    Annotation(AnnotationData<'a>),
 }
 
 /// A part specifier is either a part number or one of the following:
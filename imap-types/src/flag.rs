@@ -9,7 +9,7 @@ use bounded_static::ToStatic;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-use crate::{core::Atom, error::ValidationError};
+use crate::{core::Atom, error::ValidationError, extensions::gmail::GmailLabel};
 
 /// There are two types of flags in IMAP4rev1: System and keyword flags.
 ///
@@ -53,13 +53,20 @@ pub struct FlagExtension<'a>(Atom<'a>);
 
 impl<'a> Flag<'a> {
     pub fn system(atom: Atom<'a>) -> Self {
-        match atom.as_ref().to_ascii_lowercase().as_ref() {
-            "answered" => Self::Answered,
-            "deleted" => Self::Deleted,
-            "draft" => Self::Draft,
-            "flagged" => Self::Flagged,
-            "seen" => Self::Seen,
-            _ => Self::Extension(FlagExtension(atom)),
+        let flag = atom.as_ref();
+
+        if flag.eq_ignore_ascii_case("answered") {
+            Self::Answered
+        } else if flag.eq_ignore_ascii_case("deleted") {
+            Self::Deleted
+        } else if flag.eq_ignore_ascii_case("draft") {
+            Self::Draft
+        } else if flag.eq_ignore_ascii_case("flagged") {
+            Self::Flagged
+        } else if flag.eq_ignore_ascii_case("seen") {
+            Self::Seen
+        } else {
+            Self::Extension(FlagExtension(atom))
         }
     }
 
@@ -144,6 +151,12 @@ pub enum FlagNameAttribute<'a> {
     /// last time the mailbox was selected. (`\Unmarked`)
     Unmarked,
 
+    /// The mailbox is located on a remote server and must be accessed via a
+    /// referral. (`\Remote`, see RFC 2193)
+    #[cfg(feature = "ext_mailbox_referrals")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ext_mailbox_referrals")))]
+    Remote,
+
     /// An extension flags.
     Extension(FlagNameAttributeExtension<'a>),
 }
@@ -170,6 +183,8 @@ impl<'a> From<Atom<'a>> for FlagNameAttribute<'a> {
             "noselect" => Self::Noselect,
             "marked" => Self::Marked,
             "unmarked" => Self::Unmarked,
+            #[cfg(feature = "ext_mailbox_referrals")]
+            "remote" => Self::Remote,
             _ => Self::Extension(FlagNameAttributeExtension(atom)),
         }
     }
@@ -182,6 +197,8 @@ impl<'a> Display for FlagNameAttribute<'a> {
             Self::Noselect => f.write_str("\\Noselect"),
             Self::Marked => f.write_str("\\Marked"),
             Self::Unmarked => f.write_str("\\Unmarked"),
+            #[cfg(feature = "ext_mailbox_referrals")]
+            Self::Remote => f.write_str("\\Remote"),
             Self::Extension(extension) => write!(f, "\\{}", extension.0),
         }
     }
@@ -205,3 +222,27 @@ pub enum StoreResponse {
     Answer,
     Silent,
 }
+
+/// Message attribute modified by a STORE command.
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "bounded-static", derive(ToStatic))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum StoreAttribute<'a> {
+    /// Flags (`FLAGS`).
+    Flags(Vec<Flag<'a>>), // FIXME(misuse): must not accept "\*" or "\Recent"
+    /// Gmail labels (`X-GM-LABELS`).
+    GmailLabels(Vec<GmailLabel<'a>>),
+}
+
+impl<'a> From<Vec<Flag<'a>>> for StoreAttribute<'a> {
+    fn from(flags: Vec<Flag<'a>>) -> Self {
+        Self::Flags(flags)
+    }
+}
+
+impl<'a> From<Vec<GmailLabel<'a>>> for StoreAttribute<'a> {
+    fn from(labels: Vec<GmailLabel<'a>>) -> Self {
+        Self::GmailLabels(labels)
+    }
+}
@@ -109,6 +109,22 @@
 //! |The IMAP ENABLE Extension ([RFC 5161])                       |
 //! |IMAP4 IDLE command ([RFC 2177])                              |
 //! |IMAP QUOTA Extension ([RFC 9208])                            |
+//! |IMAP4 Extension for Returning Message Previews ([RFC 8970])  |
+//! |IMAP APPENDLIMIT Extension ([RFC 7889])                      |
+//! |IMAP REPLACE Extension ([RFC 8508])                          |
+//! |IMAP UNAUTHENTICATE Extension ([RFC 8437])                   |
+//! |IMAP4 Extension for SEARCH Return Options ([RFC 4731])       |
+//! |IMAP PARTIAL Extension ([RFC 9394])                          |
+//! |IMAP Response Code for Command Progress ([RFC 9585])         |
+//! |IMAP Internationalization ([RFC 5255])                       |
+//! |Contexts for IMAP4 ([RFC 5267])                              |
+//! |IMAP4 Multimailbox SEARCH Extension ([RFC 7377])             |
+//! |IMAP4 Extension for Named Searches (Filters) ([RFC 5466])    |
+//! |IMAP CONVERT Extension ([RFC 5259])                          |
+//! |IMAP ANNOTATE Extension ([RFC 5257])                         |
+//! |IMAP4 Extension for Returning STATUS=SIZE ([RFC 8438])       |
+//! |IMAP Extension for SAVEDATE ([RFC 8514])                     |
+//! |Gmail IMAP Extensions ([X-GM-EXT-1])                         |
 //!
 //! # Features
 //!
@@ -117,8 +133,8 @@
 //! |Feature              |Description                                                                          |Status    |
 //! |---------------------|-------------------------------------------------------------------------------------|----------|
 //! |ext_condstore_qresync|Quick Flag Changes Resynchronization and Quick Mailbox Resynchronization ([RFC 7162])|Unfinished|
-//! |ext_login_referrals  |IMAP4 Login Referrals ([RFC 2221])                                                   |Unfinished|
-//! |ext_mailbox_referrals|IMAP4 Mailbox Referrals ([RFC 2193])                                                 |Unfinished|
+//! |ext_login_referrals  |IMAP4 Login Referrals ([RFC 2221])                                                   |          |
+//! |ext_mailbox_referrals|IMAP4 Mailbox Referrals ([RFC 2193])                                                 |          |
 //! |starttls             |IMAP4rev1 ([RFC 3501]; section 6.2.1)                                                |          |
 //!
 //! STARTTLS is not an IMAP extension but feature-gated because it [should be avoided](https://nostarttls.secvuln.info/).
@@ -149,13 +165,29 @@
 //! [RFC 2221]: https://datatracker.ietf.org/doc/html/rfc2221
 //! [RFC 3501]: https://datatracker.ietf.org/doc/html/rfc3501
 //! [RFC 3691]: https://datatracker.ietf.org/doc/html/rfc3691
+//! [RFC 4731]: https://datatracker.ietf.org/doc/html/rfc4731
 //! [RFC 4959]: https://datatracker.ietf.org/doc/html/rfc4959
 //! [RFC 4978]: https://datatracker.ietf.org/doc/html/rfc4978
 //! [RFC 5161]: https://datatracker.ietf.org/doc/html/rfc5161
+//! [RFC 5255]: https://datatracker.ietf.org/doc/html/rfc5255
+//! [RFC 5257]: https://datatracker.ietf.org/doc/html/rfc5257
+//! [RFC 5259]: https://datatracker.ietf.org/doc/html/rfc5259
+//! [RFC 5267]: https://datatracker.ietf.org/doc/html/rfc5267
+//! [RFC 5466]: https://datatracker.ietf.org/doc/html/rfc5466
 //! [RFC 6851]: https://datatracker.ietf.org/doc/html/rfc6851
 //! [RFC 7162]: https://datatracker.ietf.org/doc/html/rfc7162
+//! [RFC 7377]: https://datatracker.ietf.org/doc/html/rfc7377
 //! [RFC 7888]: https://datatracker.ietf.org/doc/html/rfc7888
+//! [RFC 7889]: https://datatracker.ietf.org/doc/html/rfc7889
+//! [RFC 8437]: https://datatracker.ietf.org/doc/html/rfc8437
+//! [RFC 8438]: https://datatracker.ietf.org/doc/html/rfc8438
+//! [RFC 8508]: https://datatracker.ietf.org/doc/html/rfc8508
+//! [RFC 8514]: https://datatracker.ietf.org/doc/html/rfc8514
+//! [RFC 8970]: https://datatracker.ietf.org/doc/html/rfc8970
 //! [RFC 9208]: https://datatracker.ietf.org/doc/html/rfc9208
+//! [RFC 9394]: https://datatracker.ietf.org/doc/html/rfc9394
+//! [RFC 9585]: https://datatracker.ietf.org/doc/html/rfc9585
+//! [X-GM-EXT-1]: https://developers.google.com/gmail/imap/imap-extensions
 
 #![forbid(unsafe_code)]
 #![deny(missing_debug_implementations)]
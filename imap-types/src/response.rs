@@ -14,13 +14,17 @@ use bounded_static::ToStatic;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
+#[cfg(any(feature = "ext_login_referrals", feature = "ext_mailbox_referrals"))]
+use crate::extensions::referrals::ImapUrl;
 use crate::{
     auth::AuthMechanism,
     core::{impl_try_from, AString, Atom, Charset, NonEmptyVec, QuotedChar, Tag, Text},
     error::ValidationError,
     extensions::{
         compress::CompressionAlgorithm,
+        convert::ConvertItem,
         enable::CapabilityEnable,
+        esearch::{SearchCorrelator, SearchReturnData},
         quota::{QuotaGet, Resource},
     },
     fetch::MessageDataItem,
@@ -550,6 +554,52 @@ pub enum Data<'a> {
         /// List of quota roots.
         roots: Vec<AString<'a>>,
     },
+
+    /// ESEARCH response (RFC 4731).
+    ///
+    /// The ESEARCH response occurs as a result of a SEARCH or UID SEARCH
+    /// command with return options.
+    ESearch {
+        /// Correlates the response with the command that caused it.
+        correlator: Option<SearchCorrelator<'a>>,
+        /// Whether the results are UIDs (instead of message sequence numbers).
+        uid: bool,
+        /// Search return data.
+        items: Vec<SearchReturnData>,
+    },
+
+    /// LANGUAGE response (RFC 5255).
+    ///
+    /// Lists the supported languages (as a result of LANGUAGE without arguments) or the
+    /// selected language (as a result of LANGUAGE with arguments).
+    Language {
+        /// Language tags, e.g., `en` or `de-CH`.
+        tags: NonEmptyVec<AString<'a>>,
+    },
+
+    /// COMPARATOR response (RFC 5255).
+    Comparator {
+        /// Active comparator.
+        selected: AString<'a>,
+        /// Comparators that match the requested collation orders.
+        ///
+        /// Note: Use an empty list to omit the list.
+        matching: Vec<AString<'a>>,
+    },
+
+    /// CONVERTED response (RFC 5259).
+    ///
+    /// Contains the result of a CONVERT command.
+    Converted {
+        /// Message sequence number.
+        seq: NonZeroU32,
+        /// Correlates the response with the CONVERT command that caused it.
+        correlator: SearchCorrelator<'a>,
+        /// UID of the message.
+        uid: Option<NonZeroU32>,
+        /// Converted items.
+        items: NonEmptyVec<ConvertItem<'a>>,
+    },
 }
 
 impl<'a> Data<'a> {
@@ -945,14 +995,16 @@ pub enum Code<'a> {
     /// message without the \Seen flag set.
     Unseen(NonZeroU32),
 
-    /// IMAP4 Login Referrals (RFC 2221)
-    // TODO(misuse): the imap url is more complicated than that...
+    /// `REFERRAL` (see RFC 2221 and RFC 2193)
+    ///
+    /// Followed by one or more IMAP URLs, indicates that the user (or mailbox) lives on another
+    /// server.
     #[cfg(any(feature = "ext_mailbox_referrals", feature = "ext_login_referrals"))]
     #[cfg_attr(
         docsrs,
         doc(cfg(any(feature = "ext_mailbox_referrals", feature = "ext_login_referrals")))
     )]
-    Referral(Cow<'a, str>),
+    Referral(NonEmptyVec<ImapUrl<'a>>),
 
     CompressionActive,
 
@@ -963,6 +1015,52 @@ pub enum Code<'a> {
     /// Server got a non-synchronizing literal larger than 4096 bytes.
     TooBig,
 
+    /// `BADCOMPARATOR` (RFC 5255)
+    ///
+    /// The requested comparator is not supported.
+    BadComparator,
+
+    /// `NOUPDATE` (RFC 5267)
+    ///
+    /// The server can't keep the search result of the command with the given tag up to date.
+    NoUpdate {
+        /// Tag of the search command.
+        tag: Tag<'a>,
+    },
+
+    /// `UNKNOWN-CVT` (RFC 5259)
+    ///
+    /// The server does not know how to perform the requested conversion.
+    UnknownCvt,
+
+    /// `BADPARAMS` (RFC 5259)
+    ///
+    /// A conversion parameter is unknown or has an invalid value.
+    BadParams,
+
+    /// `ANNOTATE TOOBIG` (RFC 5257)
+    ///
+    /// The annotation value is too large to be stored.
+    AnnotateTooBig,
+
+    /// `ANNOTATE TOOMANY` (RFC 5257)
+    ///
+    /// The message has too many annotations to store another one.
+    AnnotateTooMany,
+
+    /// `INPROGRESS` (RFC 9585)
+    ///
+    /// Sent in an untagged OK response to report the progress of a long-running command.
+    /// All fields are optional, i.e., a server may send `INPROGRESS` without any details.
+    InProgress {
+        /// Tag of the command in progress.
+        tag: Option<Tag<'a>>,
+        /// Number of items processed so far.
+        current: Option<u32>,
+        /// Total number of items to process.
+        goal: Option<NonZeroU32>,
+    },
+
     /// Additional response codes defined by particular client or server
     /// implementations SHOULD be prefixed with an "X" until they are
     /// added to a revision of this protocol.  Client implementations
@@ -1097,6 +1195,44 @@ pub enum Capability<'a> {
     LiteralMinus,
     /// See RFC 6851.
     Move,
+    /// See RFC 8508.
+    Replace,
+    /// See RFC 8437.
+    Unauthenticate,
+    /// See RFC 4731.
+    ESearch,
+    /// See RFC 9394.
+    Partial,
+    /// See RFC 5255.
+    Language,
+    /// See RFC 5267.
+    ContextSearch,
+    /// See RFC 5267.
+    ContextSort,
+    /// See RFC 7377.
+    MultiSearch,
+    /// See RFC 5466.
+    Filters,
+    /// See RFC 5259.
+    Convert,
+    /// See RFC 5257.
+    AnnotateExperiment1,
+    /// See RFC 5255.
+    ///
+    /// The internationalization level, i.e., `I18NLEVEL=1` or `I18NLEVEL=2`.
+    I18nLevel(u32),
+    /// See RFC 7889.
+    ///
+    /// The server-wide append limit, if any.
+    AppendLimit(Option<u64>),
+    /// See RFC 8438.
+    StatusSize,
+    /// See RFC 8514.
+    SaveDate,
+    /// See RFC 8970.
+    Preview,
+    /// Gmail IMAP Extensions.
+    GmailExt1,
     /// Other/Unknown
     Other(CapabilityOther<'a>),
 }
@@ -1124,6 +1260,24 @@ impl<'a> Display for Capability<'a> {
             Self::LiteralPlus => write!(f, "LITERAL+"),
             Self::LiteralMinus => write!(f, "LITERAL-"),
             Self::Move => write!(f, "MOVE"),
+            Self::Replace => write!(f, "REPLACE"),
+            Self::Unauthenticate => write!(f, "UNAUTHENTICATE"),
+            Self::ESearch => write!(f, "ESEARCH"),
+            Self::Partial => write!(f, "PARTIAL"),
+            Self::Language => write!(f, "LANGUAGE"),
+            Self::ContextSearch => write!(f, "CONTEXT=SEARCH"),
+            Self::ContextSort => write!(f, "CONTEXT=SORT"),
+            Self::MultiSearch => write!(f, "MULTISEARCH"),
+            Self::Filters => write!(f, "FILTERS"),
+            Self::Convert => write!(f, "CONVERT"),
+            Self::AnnotateExperiment1 => write!(f, "ANNOTATE-EXPERIMENT-1"),
+            Self::I18nLevel(level) => write!(f, "I18NLEVEL={}", level),
+            Self::AppendLimit(None) => write!(f, "APPENDLIMIT"),
+            Self::AppendLimit(Some(limit)) => write!(f, "APPENDLIMIT={}", limit),
+            Self::StatusSize => write!(f, "STATUS=SIZE"),
+            Self::SaveDate => write!(f, "SAVEDATE"),
+            Self::Preview => write!(f, "PREVIEW"),
+            Self::GmailExt1 => write!(f, "X-GM-EXT-1"),
             Self::Other(other) => write!(f, "{}", other.0),
         }
     }
@@ -1179,6 +1333,22 @@ impl<'a> From<Atom<'a>> for Capability<'a> {
             "literal+" => Self::LiteralPlus,
             "literal-" => Self::LiteralMinus,
             "move" => Self::Move,
+            "replace" => Self::Replace,
+            "unauthenticate" => Self::Unauthenticate,
+            "esearch" => Self::ESearch,
+            "partial" => Self::Partial,
+            "language" => Self::Language,
+            "context=search" => Self::ContextSearch,
+            "context=sort" => Self::ContextSort,
+            "multisearch" => Self::MultiSearch,
+            "filters" => Self::Filters,
+            "convert" => Self::Convert,
+            "annotate-experiment-1" => Self::AnnotateExperiment1,
+            "appendlimit" => Self::AppendLimit(None),
+            "status=size" => Self::StatusSize,
+            "savedate" => Self::SaveDate,
+            "preview" => Self::Preview,
+            "x-gm-ext-1" => Self::GmailExt1,
             _ => {
                 // TODO(efficiency)
                 if let Some((left, right)) = split_once_cow(cow.clone(), "=") {
@@ -1195,6 +1365,17 @@ impl<'a> From<Atom<'a>> for Capability<'a> {
                                 }
                             }
                         }
+                        // Note: `parse` would also accept a leading "+".
+                        "appendlimit" if right.bytes().all(|b| b.is_ascii_digit()) => {
+                            if let Ok(limit) = right.parse::<u64>() {
+                                return Self::AppendLimit(Some(limit));
+                            }
+                        }
+                        "i18nlevel" if right.bytes().all(|b| b.is_ascii_digit()) => {
+                            if let Ok(level) = right.parse::<u32>() {
+                                return Self::I18nLevel(level);
+                            }
+                        }
                         "quota" => {
                             if let Some((_, right)) =
                                 right.as_ref().to_ascii_lowercase().split_once("res-")
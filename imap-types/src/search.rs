@@ -8,6 +8,8 @@ use serde::{Deserialize, Serialize};
 use crate::{
     core::{AString, Atom, NonEmptyVec},
     datetime::NaiveDate,
+    extensions::filters::FilterName,
+    mailbox::ListMailbox,
     sequence::SequenceSet,
 };
 
@@ -63,6 +65,23 @@ pub enum SearchKey<'a> {
     /// Messages with the \Flagged flag set.
     Flagged,
 
+    /// Messages with an annotation attribute that contains the specified string.
+    ///
+    /// See RFC 5257.
+    Annotation {
+        /// Entry, e.g., `/comment`.
+        entry: ListMailbox<'a>,
+        /// Attribute, e.g., `value.priv`.
+        attribute: ListMailbox<'a>,
+        /// Searched value.
+        value: AString<'a>,
+    },
+
+    /// Messages that match the search program stored in the named filter.
+    ///
+    /// See RFC 5466.
+    Filter(FilterName<'a>),
+
     /// Messages that contain the specified string in the envelope
     /// structure's FROM field.
     From(AString<'a>),
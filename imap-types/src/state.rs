@@ -49,6 +49,10 @@
 //! (6) CLOSE command, or failed SELECT or EXAMINE command
 //! (7) LOGOUT command, server shutdown, or connection closed
 //! ```
+//!
+//! Note: With the UNAUTHENTICATE extension ([RFC 8437](https://www.rfc-editor.org/rfc/rfc8437.html)),
+//! a successful UNAUTHENTICATE command brings the connection from the Authenticated or Selected
+//! state back to the Not Authenticated state. See [`State::unauthenticate`].
 
 #[cfg(feature = "bounded-static")]
 use bounded_static::ToStatic;
@@ -33,8 +33,16 @@ pub enum StatusDataItemName {
     Deleted,
 
     /// The amount of storage space that can be reclaimed by performing EXPUNGE on the mailbox.
+    ///
+    /// The amount is given in units of 1024 octets (see RFC 9208).
     DeletedStorage,
 
+    /// The total size of the mailbox in octets (see RFC 8438).
+    Size,
+
+    /// The maximum size of a message that can be appended to the mailbox (see RFC 7889).
+    AppendLimit,
+
     #[cfg(feature = "ext_condstore_qresync")]
     #[cfg_attr(docsrs, doc(cfg(feature = "ext_condstore_qresync")))]
     HighestModSeq,
@@ -68,5 +76,16 @@ pub enum StatusDataItem {
     Deleted(u32),
 
     /// The amount of storage space that can be reclaimed by performing EXPUNGE on the mailbox.
+    ///
+    /// The amount is given in units of 1024 octets (see RFC 9208). Use
+    /// [`ResourceUnit::to_octets`](crate::extensions::quota::ResourceUnit::to_octets) to convert it.
     DeletedStorage(u64),
+
+    /// The total size of the mailbox in octets (see RFC 8438).
+    Size(u64),
+
+    /// The maximum size of a message that can be appended to the mailbox (see RFC 7889).
+    ///
+    /// `None` (`NIL`) indicates that there is no limit for the mailbox.
+    AppendLimit(Option<u64>),
 }
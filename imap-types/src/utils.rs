@@ -65,6 +65,11 @@ pub mod indicators {
         is_char(b) && !is_atom_specials(b)
     }
 
+    /// `filter-name = 1*<any ATOM-CHAR except "/">` (RFC 5466)
+    pub fn is_filter_name_char(b: u8) -> bool {
+        is_atom_char(b) && b != b'/'
+    }
+
     /// `atom-specials = "(" / ")" / "{" / SP / CTL / list-wildcards / quoted-specials / resp-specials`
     pub fn is_atom_specials(i: u8) -> bool {
         match i {